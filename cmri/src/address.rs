@@ -49,6 +49,49 @@ impl Address {
     pub const fn as_unit_address(&self) -> u8 {
         self.value + 65
     }
+
+    /// Iterate over every valid address, from 0 to 127 (inclusive).
+    ///
+    /// ```
+    /// use cmri::Address;
+    /// assert_eq!(Address::iter().len(), 128);
+    /// assert_eq!(Address::iter().next(), Address::try_from_node_address(0).ok());
+    /// assert_eq!(Address::iter().last(), Address::try_from_node_address(127).ok());
+    /// ```
+    #[must_use]
+    pub fn iter() -> impl ExactSizeIterator<Item = Self> + DoubleEndedIterator + Clone {
+        (0..=127).map(|value| Self { value })
+    }
+
+    /// Get the following address, `None` if this is the last (127).
+    #[must_use]
+    pub const fn next(&self) -> Option<Self> {
+        if self.value < 127 { Some(Self { value: self.value + 1 }) } else { None }
+    }
+
+    /// Get the preceeding address, `None` if this is the first (0).
+    #[must_use]
+    pub const fn prev(&self) -> Option<Self> {
+        if self.value > 0 { Some(Self { value: self.value - 1 }) } else { None }
+    }
+
+    /// Get the following address, staying at the last (127) rather than going past it.
+    #[must_use]
+    pub const fn saturating_next(&self) -> Self {
+        match self.next() {
+            Some(address) => address,
+            None => *self
+        }
+    }
+
+    /// Get the preceeding address, staying at the first (0) rather than going past it.
+    #[must_use]
+    pub const fn saturating_prev(&self) -> Self {
+        match self.prev() {
+            Some(address) => address,
+            None => *self
+        }
+    }
 }
 
 impl core::fmt::Debug for Address {
@@ -63,6 +106,44 @@ impl core::fmt::Display for Address {
     }
 }
 
+impl core::str::FromStr for Address {
+    type Err = ParseAddressError;
+
+    /// Parse a node address (human facing), in decimal.
+    ///
+    /// ```
+    /// use cmri::{Address, ParseAddressError};
+    /// assert_eq!("10".parse(), Ok(Address::try_from_node_address(10).unwrap()));
+    /// assert_eq!("128".parse::<Address>(), Err(ParseAddressError::OutOfRange));
+    /// assert_eq!("ten".parse::<Address>(), Err(ParseAddressError::InvalidDigit));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.parse::<u8>().map_err(|error| match error.kind() {
+            core::num::IntErrorKind::Empty => ParseAddressError::Empty,
+            core::num::IntErrorKind::PosOverflow => ParseAddressError::OutOfRange,
+            _ => ParseAddressError::InvalidDigit
+        })?;
+        Self::try_from_node_address(value).map_err(|_| ParseAddressError::OutOfRange)
+    }
+}
+
+/// The errors which can occur when parsing an [`Address`] from a string.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParseAddressError {
+    /// The string is empty.
+    #[error("Empty node address")]
+    Empty,
+
+    /// The string isn't a decimal number.
+    #[error("Invalid digit in node address")]
+    InvalidDigit,
+
+    /// The number is outside the valid range of 0-127.
+    #[error("Invalid Node Address (must be 0-127)")]
+    OutOfRange
+}
+
 
 #[cfg(feature = "serde")]
 #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "serde")))]
@@ -166,6 +247,79 @@ mod tests {
         assert_eq!(Address { value: 10 }.as_unit_address(), 75);
     }
 
+    #[test]
+    fn iter() {
+        let mut iter = Address::iter();
+        assert_eq!(iter.len(), 128);
+        assert_eq!(iter.next(), Some(Address { value: 0 }));
+        assert_eq!(iter.next(), Some(Address { value: 1 }));
+        assert_eq!(iter.next_back(), Some(Address { value: 127 }));
+        assert_eq!(iter.len(), 125);
+        assert!(Address::iter().map(|address| address.as_node_address()).eq(0..=127));
+    }
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn valid() {
+            assert_eq!("0".parse(), Ok(Address { value: 0 }));
+            assert_eq!("127".parse(), Ok(Address { value: 127 }));
+            assert_eq!("+5".parse(), Ok(Address { value: 5 }));
+        }
+
+        #[test]
+        fn out_of_range() {
+            assert_eq!("128".parse::<Address>(), Err(ParseAddressError::OutOfRange));
+            assert_eq!("255".parse::<Address>(), Err(ParseAddressError::OutOfRange));
+            assert_eq!("256".parse::<Address>(), Err(ParseAddressError::OutOfRange));
+            assert_eq!("99999".parse::<Address>(), Err(ParseAddressError::OutOfRange));
+        }
+
+        #[test]
+        fn not_numeric() {
+            assert_eq!("".parse::<Address>(), Err(ParseAddressError::Empty));
+            assert_eq!("abc".parse::<Address>(), Err(ParseAddressError::InvalidDigit));
+            assert_eq!("1.5".parse::<Address>(), Err(ParseAddressError::InvalidDigit));
+            assert_eq!("-1".parse::<Address>(), Err(ParseAddressError::InvalidDigit));
+            assert_eq!(" 1".parse::<Address>(), Err(ParseAddressError::InvalidDigit));
+        }
+    }
+
+    mod next {
+        use super::*;
+
+        #[test]
+        fn mid_range() {
+            assert_eq!(Address { value: 10 }.next(), Some(Address { value: 11 }));
+            assert_eq!(Address { value: 10 }.saturating_next(), Address { value: 11 });
+        }
+
+        #[test]
+        fn boundary() {
+            assert_eq!(Address { value: 126 }.next(), Some(Address { value: 127 }));
+            assert_eq!(Address { value: 127 }.next(), None);
+            assert_eq!(Address { value: 127 }.saturating_next(), Address { value: 127 });
+        }
+    }
+
+    mod prev {
+        use super::*;
+
+        #[test]
+        fn mid_range() {
+            assert_eq!(Address { value: 10 }.prev(), Some(Address { value: 9 }));
+            assert_eq!(Address { value: 10 }.saturating_prev(), Address { value: 9 });
+        }
+
+        #[test]
+        fn boundary() {
+            assert_eq!(Address { value: 1 }.prev(), Some(Address { value: 0 }));
+            assert_eq!(Address { value: 0 }.prev(), None);
+            assert_eq!(Address { value: 0 }.saturating_prev(), Address { value: 0 });
+        }
+    }
+
     #[cfg(feature = "serde")]
     mod serde {
         use super::*;
@@ -0,0 +1,253 @@
+use crate::Address;
+
+/// A set of node addresses, stored as a 128 bit bitset so membership checks are cheap.
+///
+/// # Example
+///
+/// ```
+/// use cmri::{Address, AddressSet};
+///
+/// let mut set = AddressSet::new();
+/// set.insert(Address::try_from_node_address(10).unwrap());
+/// set.insert(Address::try_from_node_address(2).unwrap());
+///
+/// assert!(set.contains(Address::try_from_node_address(10).unwrap()));
+/// assert!(!set.contains(Address::try_from_node_address(3).unwrap()));
+/// assert_eq!(set.iter().map(|a| a.as_node_address()).collect::<Vec<_>>(), [2, 10]);
+/// ```
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct AddressSet {
+    bits: u128
+}
+
+impl AddressSet {
+    /// Create a new, empty, `AddressSet`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Create a new `AddressSet` containing every address.
+    #[must_use]
+    pub const fn all() -> Self {
+        Self { bits: u128::MAX }
+    }
+
+    /// Add an address to the set, returns whether it was newly added.
+    pub const fn insert(&mut self, address: Address) -> bool {
+        let added = !self.contains(address);
+        self.bits |= Self::bit(address);
+        added
+    }
+
+    /// Remove an address from the set, returns whether it was present.
+    pub const fn remove(&mut self, address: Address) -> bool {
+        let removed = self.contains(address);
+        self.bits &= !Self::bit(address);
+        removed
+    }
+
+    /// Whether the set contains the address.
+    #[must_use]
+    pub const fn contains(&self, address: Address) -> bool {
+        self.bits & Self::bit(address) != 0
+    }
+
+    /// Remove all addresses from the set.
+    pub const fn clear(&mut self) {
+        self.bits = 0;
+    }
+
+    /// The number of addresses in the set.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    /// Whether the set contains no addresses.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Whether the set contains every address.
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.bits == u128::MAX
+    }
+
+    /// Iterate over the addresses in the set, lowest first.
+    #[must_use]
+    pub const fn iter(&self) -> AddressSetIter {
+        AddressSetIter { bits: self.bits }
+    }
+
+    const fn bit(address: Address) -> u128 {
+        1 << address.as_node_address()
+    }
+}
+
+impl core::fmt::Debug for AddressSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Address> for AddressSet {
+    fn from_iter<T: IntoIterator<Item = Address>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Address> for AddressSet {
+    fn extend<T: IntoIterator<Item = Address>>(&mut self, iter: T) {
+        for address in iter {
+            self.insert(address);
+        }
+    }
+}
+
+impl IntoIterator for &AddressSet {
+    type Item = Address;
+    type IntoIter = AddressSetIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for AddressSet {
+    type Item = Address;
+    type IntoIter = AddressSetIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the addresses in an `AddressSet`, lowest first.
+#[derive(Debug, Clone)]
+pub struct AddressSetIter {
+    bits: u128
+}
+
+impl Iterator for AddressSetIter {
+    type Item = Address;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 { return None }
+        #[expect(clippy::cast_possible_truncation, reason = "trailing_zeros of a non-zero u128 is at most 127")]
+        let value = self.bits.trailing_zeros() as u8;
+        self.bits &= self.bits - 1; // Clear the lowest set bit
+        Address::try_from_node_address(value).ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for AddressSetIter {}
+
+
+#[allow(clippy::missing_panics_doc, reason = "tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(value: u8) -> Address {
+        Address::try_from_node_address(value).unwrap()
+    }
+
+    mod membership {
+        use super::*;
+
+        #[test]
+        fn insert() {
+            let mut set = AddressSet::new();
+            assert!(set.insert(address(5)));
+            assert!(!set.insert(address(5)));
+            assert!(set.contains(address(5)));
+            assert!(!set.contains(address(4)));
+            assert!(!set.contains(address(6)));
+            assert_eq!(set.len(), 1);
+        }
+
+        #[test]
+        fn remove() {
+            let mut set = AddressSet::new();
+            set.insert(address(5));
+            assert!(set.remove(address(5)));
+            assert!(!set.remove(address(5)));
+            assert!(!set.contains(address(5)));
+            assert_eq!(set.len(), 0);
+        }
+
+        #[test]
+        fn boundaries() {
+            let mut set = AddressSet::new();
+            set.insert(address(0));
+            set.insert(address(127));
+            assert!(set.contains(address(0)));
+            assert!(set.contains(address(127)));
+            assert!(!set.contains(address(1)));
+            assert!(!set.contains(address(126)));
+        }
+
+        #[test]
+        fn clear() {
+            let mut set = AddressSet::all();
+            set.clear();
+            assert_eq!(set, AddressSet::new());
+        }
+    }
+
+    mod iter {
+        use super::*;
+
+        #[test]
+        fn lowest_first() {
+            let set = [address(127), address(3), address(64), address(0)].into_iter().collect::<AddressSet>();
+            assert!(set.iter().eq([address(0), address(3), address(64), address(127)]));
+        }
+
+        #[test]
+        fn exact_size() {
+            let set = [address(1), address(2), address(3)].into_iter().collect::<AddressSet>();
+            let mut iter = set.iter();
+            assert_eq!(iter.len(), 3);
+            iter.next();
+            assert_eq!(iter.len(), 2);
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn debug() {
+            let set = [address(2), address(1)].into_iter().collect::<AddressSet>();
+            assert_eq!(format!("{set:?}"), "{1, 2}");
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let set = AddressSet::new();
+        assert!(set.is_empty());
+        assert!(!set.is_full());
+        assert_eq!(set.len(), 0);
+        assert_eq!(set.iter().next(), None);
+        assert_eq!(set, AddressSet::default());
+    }
+
+    #[test]
+    fn full() {
+        let set = AddressSet::all();
+        assert!(!set.is_empty());
+        assert!(set.is_full());
+        assert_eq!(set.len(), 128);
+        assert!(set.iter().map(|a| a.as_node_address()).eq(0..128));
+        assert_eq!((0..128).map(address).collect::<AddressSet>(), set);
+    }
+}
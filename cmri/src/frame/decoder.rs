@@ -0,0 +1,167 @@
+use super::{Raw, ReceiveError};
+
+/// Decodes frames from arbitrary chunks of bytes (E.G. as read from a serial port or TCP socket),
+/// which may contain any number of complete frames plus a partial one.
+///
+/// A partially received frame is kept between calls to `push_slice`, and an error part way
+/// through a chunk doesn't stop the rest of the chunk from being decoded.
+///
+/// # Example
+///
+/// ```
+/// use cmri::frame::Decoder;
+/// let mut decoder = Decoder::new();
+///
+/// // A complete frame, and the start of another
+/// let mut frames = decoder.push_slice(&[0xFF, 0xFF, 0x02, 65, b'P', 0x03, 0xFF, 0xFF, 0x02]);
+/// assert_eq!(frames.next().unwrap().unwrap().as_slice(), &[0xFF, 0xFF, 0x02, 65, b'P', 0x03]);
+/// assert!(frames.next().is_none());
+///
+/// // The rest of the second frame
+/// let mut frames = decoder.push_slice(&[66, b'P', 0x03]);
+/// assert_eq!(frames.next().unwrap().unwrap().as_slice(), &[0xFF, 0xFF, 0x02, 66, b'P', 0x03]);
+/// assert!(frames.next().is_none());
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Decoder {
+    frame: Raw
+}
+
+impl Decoder {
+    /// Create a new `Decoder`, waiting for the start of a frame.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { frame: Raw::new() }
+    }
+
+    /// Whether part of a frame has been received, but not yet completed.
+    #[must_use]
+    pub const fn has_partial_frame(&self) -> bool {
+        !self.frame.is_empty()
+    }
+
+    /// Discard any partially received frame.
+    pub fn reset(&mut self) {
+        self.frame.reset();
+    }
+
+    /// Decode a chunk of bytes, returning an iterator over the frames (and errors) in it, in order.
+    ///
+    /// Any partial frame at the end of the chunk is kept, ready for the next chunk.
+    /// The bytes are decoded as the iterator is advanced,
+    /// so any not reached before it's dropped are discarded.
+    #[must_use = "the bytes are only decoded as the iterator is advanced"]
+    pub fn push_slice<'a>(&'a mut self, bytes: &'a [u8]) -> DecoderIter<'a> {
+        DecoderIter { decoder: self, bytes }
+    }
+}
+
+/// Iterator over the frames decoded from a chunk of bytes, see [`Decoder::push_slice`].
+#[derive(Debug)]
+pub struct DecoderIter<'a> {
+    decoder: &'a mut Decoder,
+    bytes: &'a [u8]
+}
+
+impl Iterator for DecoderIter<'_> {
+    type Item = Result<Raw, ReceiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.bytes.is_empty() {
+            let (used, result) = self.decoder.frame.receive_slice(self.bytes);
+            self.bytes = &self.bytes[used..];
+            match result {
+                Ok(false) => (),
+                Ok(true) => return Some(Ok(core::mem::take(&mut self.decoder.frame))),
+                Err(error) => return Some(Err(error)) // The frame has been reset, so carry on from the next byte
+            }
+        }
+        None
+    }
+}
+
+
+#[allow(clippy::missing_panics_doc, reason = "tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLL_0: [u8; 6] = [0xFF, 0xFF, 0x02, 65, b'P', 0x03];
+    const POLL_1: [u8; 6] = [0xFF, 0xFF, 0x02, 66, b'P', 0x03];
+
+    #[test]
+    fn no_frames() {
+        let mut decoder = Decoder::new();
+        assert!(decoder.push_slice(&[]).next().is_none());
+        assert!(decoder.push_slice(&[0x00, 0x01]).next().is_none());
+        assert!(!decoder.has_partial_frame());
+    }
+
+    #[test]
+    fn several_frames_in_order() {
+        let mut decoder = Decoder::new();
+        let mut bytes = [0; 12];
+        bytes[..6].copy_from_slice(&POLL_0);
+        bytes[6..].copy_from_slice(&POLL_1);
+
+        let mut frames = decoder.push_slice(&bytes);
+        assert_eq!(frames.next().unwrap().unwrap().as_slice(), &POLL_0);
+        assert_eq!(frames.next().unwrap().unwrap().as_slice(), &POLL_1);
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn frame_split_across_chunks() {
+        let mut decoder = Decoder::new();
+        assert!(decoder.push_slice(&POLL_0[..4]).next().is_none());
+        assert!(decoder.has_partial_frame());
+
+        let mut frames = decoder.push_slice(&POLL_0[4..]);
+        assert_eq!(frames.next().unwrap().unwrap().as_slice(), &POLL_0);
+        assert!(frames.next().is_none());
+        assert!(!decoder.has_partial_frame());
+    }
+
+    #[test]
+    fn too_short_mid_chunk() {
+        let mut decoder = Decoder::new();
+        let mut bytes = [0; 10];
+        bytes[..4].copy_from_slice(&[0xFF, 0xFF, 0x02, 0x03]);
+        bytes[4..].copy_from_slice(&POLL_1);
+
+        let mut frames = decoder.push_slice(&bytes);
+        assert_eq!(frames.next(), Some(Err(ReceiveError::TooShort)));
+        assert_eq!(frames.next().unwrap().unwrap().as_slice(), &POLL_1);
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn too_long_mid_chunk() {
+        let mut decoder = Decoder::new();
+        assert!(decoder.push_slice(&[0xFF, 0xFF, 0x02]).next().is_none());
+        let body = [b'A'; 300];
+        let mut frames = decoder.push_slice(&body);
+        assert_eq!(frames.next().unwrap(), Err(ReceiveError::TooLong));
+        assert!(frames.next().is_none());
+
+        // The rest of the too long frame is discarded
+        assert!(decoder.push_slice(&body).next().is_none());
+
+        // Once reset the remaining bytes are still decoded
+        let mut bytes = [0; 7];
+        bytes[0] = b'A';
+        bytes[1..].copy_from_slice(&POLL_0);
+        let mut frames = decoder.push_slice(&bytes);
+        assert_eq!(frames.next().unwrap().unwrap().as_slice(), &POLL_0);
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn reset() {
+        let mut decoder = Decoder::new();
+        assert!(decoder.push_slice(&POLL_0[..4]).next().is_none());
+        decoder.reset();
+        assert!(!decoder.has_partial_frame());
+        assert!(decoder.push_slice(&POLL_0[4..]).next().is_none());
+    }
+}
@@ -1,5 +1,7 @@
+use super::Raw;
+
 /// The errors which can occur on decoding a frame.
-#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::module_name_repetitions)]
 pub enum DecodeError {
@@ -32,6 +34,54 @@ pub enum DecodeError {
     }
 }
 
+impl DecodeError {
+    /// Pair the error with the bytes of the frame which couldn't be decoded,
+    /// so they can be kept (or serialised) for later analysis.
+    ///
+    /// ```
+    /// use cmri::frame::{Raw, DecodeError};
+    ///
+    /// let frame = Raw::try_from(&[0xFF, 0xFF, 0x02, 65, b'P']).unwrap();
+    /// let report = frame.try_as_packet().unwrap_err().with_context(&frame);
+    /// assert_eq!(report.error(), &DecodeError::MissingEnd);
+    /// assert_eq!(report.bytes().as_slice(), &[0xFF, 0xFF, 0x02, 65, b'P']);
+    /// ```
+    #[must_use]
+    pub const fn with_context(self, bytes: &Raw) -> DecodeErrorReport {
+        DecodeErrorReport { error: self, bytes: *bytes }
+    }
+}
+
+/// A `DecodeError` along with the bytes of the frame which caused it.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::module_name_repetitions)]
+#[error("{error} (frame {bytes:x})")]
+pub struct DecodeErrorReport {
+    #[source]
+    error: DecodeError,
+    bytes: Raw
+}
+
+impl DecodeErrorReport {
+    /// The error which occured on decoding the frame.
+    #[must_use]
+    pub const fn error(&self) -> &DecodeError {
+        &self.error
+    }
+
+    /// The frame which couldn't be decoded.
+    #[must_use]
+    pub const fn bytes(&self) -> &Raw {
+        &self.bytes
+    }
+
+    /// Split into the error and the frame.
+    #[must_use]
+    pub const fn into_parts(self) -> (DecodeError, Raw) {
+        (self.error, self.bytes)
+    }
+}
 
 /// The errors which can occur on receiving a frame.
 #[derive(Debug, Eq, PartialEq, thiserror::Error)]
@@ -51,6 +101,26 @@ pub enum ReceiveError {
     AlreadyComplete
 }
 
+/// The errors which can occur on reading a frame (or packet) from a `std::io::Read`.
+#[cfg(feature = "std")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "std")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature std only.**\n\n")]
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError {
+    /// Reading failed, including `std::io::ErrorKind::UnexpectedEof` if the reader
+    /// ran out of bytes before a complete frame was read.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The bytes read weren't a valid frame.
+    #[error(transparent)]
+    Receive(#[from] ReceiveError),
+
+    /// The frame read doesn't contain a valid packet (only from [`super::read_frames`]).
+    #[error(transparent)]
+    Decode(#[from] DecodeError)
+}
+
 /// The frame is already full.
 #[derive(Debug, Eq, PartialEq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -60,3 +130,70 @@ impl core::fmt::Display for Full {
         f.write_str("Full")
     }
 }
+
+
+#[allow(clippy::missing_panics_doc, reason = "tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod decode_error_report {
+        use super::*;
+
+        fn frame() -> Raw {
+            Raw::try_from(&[0xFF, 0xFF, 0x02, 65, b'P']).unwrap()
+        }
+
+        #[test]
+        fn with_context() {
+            let report = DecodeError::MissingEnd.with_context(&frame());
+            assert_eq!(report.error(), &DecodeError::MissingEnd);
+            assert_eq!(report.bytes(), &frame());
+            assert_eq!(report.into_parts(), (DecodeError::MissingEnd, frame()));
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn display() {
+            assert_eq!(
+                DecodeError::MissingEnd.with_context(&frame()).to_string(),
+                "Frame is missing the end byte (frame [0xff, 0xff, 0x02, 0x41, 0x50])"
+            );
+        }
+
+        #[cfg(feature = "serde")]
+        mod serde {
+            use super::*;
+            use serde_test::{assert_tokens, assert_de_tokens_error, Token};
+
+            #[test]
+            fn round_trip() {
+                assert_tokens(
+                    &DecodeError::MissingEnd.with_context(&frame()),
+                    &[
+                        Token::Struct { name: "DecodeErrorReport", len: 2 },
+                        Token::Str("error"),
+                        Token::UnitVariant { name: "DecodeError", variant: "MissingEnd" },
+                        Token::Str("bytes"),
+                        Token::Bytes(&[0xFF, 0xFF, 0x02, 65, b'P']),
+                        Token::StructEnd
+                    ]
+                );
+            }
+
+            #[test]
+            fn invalid_bytes() {
+                assert_de_tokens_error::<DecodeErrorReport>(
+                    &[
+                        Token::Struct { name: "DecodeErrorReport", len: 2 },
+                        Token::Str("error"),
+                        Token::UnitVariant { name: "DecodeError", variant: "MissingEnd" },
+                        Token::Str("bytes"),
+                        Token::Bytes(&[0xFF, 0xFF]),
+                    ],
+                    "Frame is too short"
+                );
+            }
+        }
+    }
+}
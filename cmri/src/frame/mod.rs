@@ -1,9 +1,13 @@
 //! Handling of frames on a CMRInet network.
 
+mod decoder;
 mod error;
 mod raw;
 
-pub use error::{DecodeError, ReceiveError, Full};
+pub use decoder::{Decoder, DecoderIter};
+pub use error::{DecodeError, DecodeErrorReport, ReceiveError, Full};
+#[cfg(feature = "std")]
+pub use error::ReadError;
 pub use raw::Raw;
 
 /// Value of a Synchronization byte in a frame.
@@ -14,3 +18,390 @@ const STX: u8 = 0x02;
 const ETX: u8 = 0x03;
 /// Value of a Data-Link-Escape byte in a frame.
 const DLE: u8 = 0x10;
+
+/// Get the node address from the start of a frame, without receiving/validating the whole frame.
+///
+/// Returns `None` if bytes doesn't start with SYN SYN STX, or the address isn't valid.
+///
+/// ```
+/// assert_eq!(cmri::frame::peek_address(&[0xFF, 0xFF, 0x02, 77, b'P', 0x03]), Some(12));
+/// assert_eq!(cmri::frame::peek_address(&[0xFF, 0x02, 77, b'P', 0x03]), None);
+/// ```
+#[must_use]
+pub fn peek_address(bytes: &[u8]) -> Option<u8> {
+    match bytes {
+        [SYN, SYN, STX, address, ..] => crate::Address::try_from_unit_address(*address)
+            .map(|a| a.as_node_address())
+            .ok(),
+        _ => None
+    }
+}
+
+/// Get the length of the frame needed to send a packet's body.
+///
+/// This is the body with each SYN, STX, DLE and ETX byte escaped (so taking two bytes),
+/// plus the SYN, SYN, STX, address, message type and ETX bytes around it.
+///
+/// ```
+/// assert_eq!(cmri::frame::escaped_len(&[]), 6);
+/// assert_eq!(cmri::frame::escaped_len(&[1, 2, 4]), 10); // STX (2) needs escaping
+/// ```
+#[must_use]
+pub const fn escaped_len(body: &[u8]) -> usize {
+    let mut len = 6 + body.len();
+    let mut index = 0;
+    while index < body.len() {
+        if needs_escape(body[index]) {
+            len += 1;
+        }
+        index += 1;
+    }
+    len
+}
+
+/// Whether a byte must be escaped (preceded by a DLE) when within a frame.
+const fn needs_escape(byte: u8) -> bool {
+    matches!(byte, SYN | STX | DLE | ETX)
+}
+
+/// Escape bytes for sending within a frame, each SYN, STX, DLE and ETX byte is preceded by a DLE.
+/// This is the same escaping as `Raw::push`, but for any bytes, E.G. for a custom transport.
+///
+/// Returns the number of bytes written to dst, in the worst case (every byte needing escaping)
+/// this is twice the length of src. The exact length is `escaped_len(src) - 6`.
+///
+/// ```
+/// let mut escaped = [0; 8];
+/// assert_eq!(cmri::frame::escape(&[1, 0x02, 4], &mut escaped), Ok(4));
+/// assert_eq!(escaped[..4], [1, 0x10, 0x02, 4]);
+/// ```
+///
+/// # Errors
+///
+/// [`Full`] if dst isn't long enough, some of the bytes may have been written.
+pub fn escape(src: &[u8], dst: &mut [u8]) -> Result<usize, Full> {
+    let mut len = 0;
+    for &byte in src {
+        if needs_escape(byte) {
+            *dst.get_mut(len).ok_or(Full)? = DLE;
+            len += 1;
+        }
+        *dst.get_mut(len).ok_or(Full)? = byte;
+        len += 1;
+    }
+    Ok(len)
+}
+
+/// Unescape bytes received within a frame, removing the DLE before each escaped byte
+/// (the same as decoding a `Raw`). A DLE at the end of src, with nothing to escape, is dropped.
+///
+/// Returns the number of bytes written to dst, this is never more than the length of src.
+///
+/// ```
+/// let mut unescaped = [0; 4];
+/// assert_eq!(cmri::frame::unescape(&[1, 0x10, 0x02, 4], &mut unescaped), Ok(3));
+/// assert_eq!(unescaped[..3], [1, 0x02, 4]);
+/// ```
+///
+/// # Errors
+///
+/// [`Full`] if dst isn't long enough, some of the bytes may have been written.
+pub fn unescape(src: &[u8], dst: &mut [u8]) -> Result<usize, Full> {
+    let mut len = 0;
+    let mut escaped = false;
+    for &byte in src {
+        if byte == DLE && !escaped {
+            escaped = true;
+        } else {
+            escaped = false;
+            *dst.get_mut(len).ok_or(Full)? = byte;
+            len += 1;
+        }
+    }
+    Ok(len)
+}
+
+/// Lazily read, frame and decode packets from a reader, E.G. a file of captured bytes.
+///
+/// Anything between frames is skipped, and the iterator ends when the reader does
+/// (discarding any partial frame at the end). The reader is buffered, so doesn't need to be.
+/// Errors reading from the reader end the iterator after being returned.
+///
+/// ```
+/// use cmri::{Address, frame::read_frames, packet::Packet};
+/// let bytes = [0xFF, 0xFF, 0x02, 65, b'P', 0x03, 0xFF, 0xFF, 0x02, 66, b'P', 0x03];
+/// let packets = read_frames(bytes.as_slice()).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(packets, [
+///     Packet::new_poll_request(Address::try_from_node_address(0).unwrap()),
+///     Packet::new_poll_request(Address::try_from_node_address(1).unwrap())
+/// ]);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "std")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature std only.**\n\n")]
+pub fn read_frames<R: std::io::Read>(reader: R) -> impl Iterator<Item = Result<crate::Packet, ReadError>> {
+    let mut reader = std::io::BufReader::new(reader);
+    let mut finished = false;
+    core::iter::from_fn(move || {
+        if finished { return None }
+        match Raw::read_from(&mut reader) {
+            Ok(frame) => Some(frame.try_as_packet().map_err(ReadError::from)),
+            Err(ReadError::Io(error)) => {
+                finished = true;
+                (error.kind() != std::io::ErrorKind::UnexpectedEof).then_some(Err(error.into()))
+            },
+            Err(error) => Some(Err(error))
+        }
+    })
+}
+
+
+#[allow(clippy::missing_panics_doc, reason = "tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod peek_address {
+        use super::*;
+
+        #[test]
+        fn valid() {
+            assert_eq!(peek_address(&[SYN, SYN, STX, 65, b'P', ETX]), Some(0));
+            assert_eq!(peek_address(&[SYN, SYN, STX, 192]), Some(127));
+        }
+
+        #[test]
+        fn misaligned() {
+            assert_eq!(peek_address(&[SYN, STX, 77, b'P', ETX]), None);
+            assert_eq!(peek_address(&[0, SYN, SYN, STX, 77, b'P', ETX]), None);
+        }
+
+        #[test]
+        fn too_short() {
+            assert_eq!(peek_address(&[]), None);
+            assert_eq!(peek_address(&[SYN, SYN, STX]), None);
+        }
+
+        #[test]
+        fn invalid_address() {
+            assert_eq!(peek_address(&[SYN, SYN, STX, 64, b'P', ETX]), None);
+            assert_eq!(peek_address(&[SYN, SYN, STX, 193, b'P', ETX]), None);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod read_frames {
+        use std::io::Cursor;
+        use crate::{Address, packet::Packet};
+        use super::*;
+
+        fn packets() -> [Packet; 3] {
+            [
+                Packet::new_poll_request(Address::try_from_node_address(1).unwrap()),
+                Packet::new_receive_data(Address::try_from_node_address(1).unwrap(), [STX, 0, DLE].try_into().unwrap()),
+                Packet::new_transmit_data(Address::try_from_node_address(2).unwrap(), [1, 2, 3].try_into().unwrap())
+            ]
+        }
+
+        #[test]
+        fn several_frames_with_trailing_garbage() {
+            let mut bytes = vec![0x00, 0x12]; // Leading garbage
+            for packet in packets() {
+                bytes.extend_from_slice(&packet.encode_frame());
+            }
+            bytes.extend_from_slice(&[0x55, SYN, SYN, STX, 65]); // Garbage then a partial frame
+            let read = read_frames(Cursor::new(bytes)).collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(read, packets());
+        }
+
+        #[test]
+        fn empty() {
+            assert!(read_frames(Cursor::new([])).next().is_none());
+        }
+
+        #[test]
+        fn invalid_packet_is_skipped() {
+            let mut bytes = vec![SYN, SYN, STX, 65, b'p', ETX]; // Invalid message type
+            bytes.extend_from_slice(&packets()[0].encode_frame());
+            let mut read = read_frames(Cursor::new(bytes));
+            assert!(matches!(read.next(), Some(Err(ReadError::Decode(_)))));
+            assert_eq!(read.next().unwrap().unwrap(), packets()[0]);
+            assert!(read.next().is_none());
+        }
+
+        #[test]
+        fn invalid_frame_is_skipped() {
+            let mut bytes = vec![SYN, SYN, STX, 65, ETX]; // Too short
+            bytes.extend_from_slice(&packets()[0].encode_frame());
+            let mut read = read_frames(Cursor::new(bytes));
+            assert!(matches!(read.next(), Some(Err(ReadError::Receive(ReceiveError::TooShort)))));
+            assert_eq!(read.next().unwrap().unwrap(), packets()[0]);
+            assert!(read.next().is_none());
+        }
+
+        #[test]
+        fn read_error_ends() {
+            struct Failing;
+            impl std::io::Read for Failing {
+                fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                    Err(std::io::ErrorKind::PermissionDenied.into())
+                }
+            }
+            let mut read = read_frames(Failing);
+            assert!(matches!(read.next(), Some(Err(ReadError::Io(error))) if error.kind() == std::io::ErrorKind::PermissionDenied));
+            assert!(read.next().is_none());
+        }
+    }
+
+    mod escape {
+        use super::*;
+
+        fn escaped<const N: usize>(src: &[u8]) -> ([u8; N], usize) {
+            let mut dst = [0; N];
+            let len = escape(src, &mut dst).unwrap();
+            (dst, len)
+        }
+
+        #[test]
+        fn nothing_to_escape() {
+            let (dst, len) = escaped::<4>(&[0, 1, 4, 254]);
+            assert_eq!(dst[..len], [0, 1, 4, 254]);
+        }
+
+        #[test]
+        fn at_start() {
+            let (dst, len) = escaped::<4>(&[SYN, 1, 4]);
+            assert_eq!(dst[..len], [DLE, SYN, 1, 4]);
+        }
+
+        #[test]
+        fn in_middle() {
+            let (dst, len) = escaped::<4>(&[1, STX, 4]);
+            assert_eq!(dst[..len], [1, DLE, STX, 4]);
+        }
+
+        #[test]
+        fn at_end() {
+            let (dst, len) = escaped::<4>(&[1, 4, ETX]);
+            assert_eq!(dst[..len], [1, 4, DLE, ETX]);
+        }
+
+        #[test]
+        fn worst_case() {
+            let (dst, len) = escaped::<6>(&[DLE; 3]);
+            assert_eq!(dst[..len], [DLE; 6]);
+        }
+
+        #[test]
+        fn full() {
+            assert_eq!(escape(&[1, 2, 3], &mut [0; 2]), Err(Full));
+            assert_eq!(escape(&[1, STX], &mut [0; 2]), Err(Full));
+            assert_eq!(escape(&[], &mut []), Ok(0));
+        }
+
+        #[test]
+        fn matches_escaped_len() {
+            let src = [SYN, 0, STX, 1, DLE, 2, ETX, 3];
+            let (_, len) = escaped::<16>(&src);
+            assert_eq!(len, escaped_len(&src) - 6);
+        }
+    }
+
+    mod unescape {
+        use super::*;
+
+        fn unescaped<const N: usize>(src: &[u8]) -> ([u8; N], usize) {
+            let mut dst = [0; N];
+            let len = unescape(src, &mut dst).unwrap();
+            (dst, len)
+        }
+
+        #[test]
+        fn nothing_to_unescape() {
+            let (dst, len) = unescaped::<4>(&[0, 1, 4, 254]);
+            assert_eq!(dst[..len], [0, 1, 4, 254]);
+        }
+
+        #[test]
+        fn at_start() {
+            let (dst, len) = unescaped::<4>(&[DLE, SYN, 1, 4]);
+            assert_eq!(dst[..len], [SYN, 1, 4]);
+        }
+
+        #[test]
+        fn in_middle() {
+            let (dst, len) = unescaped::<4>(&[1, DLE, STX, 4]);
+            assert_eq!(dst[..len], [1, STX, 4]);
+        }
+
+        #[test]
+        fn at_end() {
+            let (dst, len) = unescaped::<4>(&[1, 4, DLE, ETX]);
+            assert_eq!(dst[..len], [1, 4, ETX]);
+        }
+
+        #[test]
+        fn escaped_dle() {
+            let (dst, len) = unescaped::<4>(&[DLE, DLE, DLE, DLE]);
+            assert_eq!(dst[..len], [DLE, DLE]);
+        }
+
+        #[test]
+        fn trailing_dle() {
+            let (dst, len) = unescaped::<4>(&[1, DLE]);
+            assert_eq!(dst[..len], [1]);
+        }
+
+        #[test]
+        fn full() {
+            assert_eq!(unescape(&[1, 2, 3], &mut [0; 2]), Err(Full));
+            assert_eq!(unescape(&[1, DLE, STX], &mut [0; 2]), Ok(2));
+        }
+    }
+
+    #[test]
+    fn escape_unescape_round_trip() {
+        let mut escaped = [0; 4];
+        let mut unescaped = [0; 2];
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                let len = escape(&[a, b], &mut escaped).unwrap();
+                assert_eq!(len, escaped_len(&[a, b]) - 6);
+                let len = unescape(&escaped[..len], &mut unescaped).unwrap();
+                assert_eq!(unescaped[..len], [a, b]);
+            }
+        }
+
+        let all = core::array::from_fn::<u8, 256, _>(|index| u8::try_from(index).unwrap());
+        let mut escaped = [0; 512];
+        let mut unescaped = [0; 256];
+        let len = escape(&all, &mut escaped).unwrap();
+        assert_eq!(len, 260);
+        assert_eq!(unescape(&escaped[..len], &mut unescaped), Ok(256));
+        assert_eq!(unescaped, all);
+    }
+
+    mod escaped_len {
+        use super::*;
+        use crate::{Address, packet::Packet};
+
+        #[test]
+        fn no_special_bytes() {
+            assert_eq!(escaped_len(&[]), 6);
+            assert_eq!(escaped_len(&[0, 1, 4, 15, 17, 254]), 12);
+        }
+
+        #[test]
+        fn special_bytes() {
+            assert_eq!(escaped_len(&[SYN, 1, STX, DLE, 5, ETX]), 16);
+            assert_eq!(escaped_len(&[DLE; 10]), 26);
+        }
+
+        #[test]
+        fn matches_encoded_frame() {
+            let body = [SYN, 0, STX, 1, DLE, 2, ETX, 3];
+            let packet = Packet::new_transmit_data(Address::try_from_node_address(0).unwrap(), body.try_into().unwrap());
+            assert_eq!(escaped_len(&body), packet.encode_frame().len());
+        }
+    }
+}
@@ -101,22 +101,37 @@ impl Raw {
             .ok()
     }
 
+    /// Check whether the contained packet is being sent to / has been received from `node_address`,
+    /// without decoding it (E.G. so a node can cheaply ignore frames for other nodes).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::frame::Raw;
+    /// let raw = Raw::try_from(&[0xFF, 0xFF, 0x02, 65 + 5, b'P', 0x03]).unwrap();
+    /// assert!(raw.is_for(5));
+    /// assert!(!raw.is_for(6));
+    /// ```
+    #[must_use]
+    pub fn is_for(&self, node_address: u8) -> bool {
+        self.address() == Some(node_address)
+    }
+
     /// Get the message type of the contained packet (if it's valid).
     #[must_use]
     pub fn message_type(&self) -> Option<char> {
+        self.message_type_is_valid().then(|| self.raw[4].into())
+    }
+
+    /// Check whether the message type of the contained packet is valid,
+    /// the same as `message_type().is_some()` but cheaper (E.G. for filtering lots of frames).
+    #[must_use]
+    pub const fn message_type_is_valid(&self) -> bool {
         #[cfg(not(feature = "experimenter"))]
-        if [b'I', b'P', b'R', b'T'].contains(&self.raw[4]) {
-            Some(self.raw[4].into())
-        } else {
-            None
-        }
+        return matches!(self.raw[4], b'I' | b'P' | b'R' | b'S' | b'T');
 
         #[cfg(feature = "experimenter")]
-        if self.raw[4].is_ascii_uppercase() {
-            Some(self.raw[4].into())
-        } else {
-            None
-        }
+        return self.raw[4].is_ascii_uppercase();
     }
 
     /// Begin building a Raw, ready for putting onto a CMRInet Network.
@@ -145,7 +160,7 @@ impl Raw {
     ///
     /// If the Raw is full.
     pub fn push(&mut self, value: u8) -> Result<usize, Full> {
-        let escape = [SYN, STX, DLE, ETX].contains(&value);
+        let escape = super::needs_escape(value);
         let count = if escape { 2 } else { 1 };
         if self.available() < count { return Err(Full) }
 
@@ -246,12 +261,88 @@ impl Raw {
         Ok(false)
     }
 
+    /// Updates the Raw with bytes received from a CMRInet Network, stopping once a frame is complete
+    /// (or an error occurs). This is the same as calling [`Self::receive`] for each byte,
+    /// but runs of ordinary bytes within a frame are copied in one go.
+    ///
+    /// Returns how many bytes were used (upto and including the one completing the frame or
+    /// causing the error) along with what [`Self::receive`] returned for the last of them,
+    /// so the rest of the bytes can be passed in again once the frame (or error) has been handled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::frame::Raw;
+    /// let bytes = [0xFF, 0xFF, 0x02, 65, b'P', 0x03, 0xFF, 0xFF, 0x02];
+    /// let mut raw = Raw::new();
+    /// assert_eq!(raw.receive_slice(&bytes), (6, Ok(true)));
+    /// assert_eq!(raw.as_slice(), &[0xFF, 0xFF, 0x02, 65, b'P', 0x03]);
+    ///
+    /// raw.reset();
+    /// assert_eq!(raw.receive_slice(&bytes[6..]), (3, Ok(false)));
+    /// assert_eq!(raw.as_slice(), &[0xFF, 0xFF, 0x02]);
+    /// ```
+    pub fn receive_slice(&mut self, bytes: &[u8]) -> (usize, Result<bool, ReceiveError>) {
+        if self.receive_state == ReceiveFrameState::Received {
+            return (0, Err(ReceiveError::AlreadyComplete));
+        }
+
+        let mut used = 0;
+        while used < bytes.len() {
+            if self.receive_state == ReceiveFrameState::Receiving {
+                // Copy the run of ordinary bytes, leaving any which would be too long for receive to reject
+                let rest = &bytes[used..];
+                let run = rest.iter()
+                    .position(|&byte| byte == ETX || byte == DLE)
+                    .unwrap_or(rest.len())
+                    .min(RawPacket::MAX_LEN - self.packet_len);
+                self.raw[self.len..(self.len + run)].copy_from_slice(&rest[..run]);
+                self.len += run;
+                self.packet_len += run;
+                used += run;
+                if used == bytes.len() { break }
+            }
+
+            let result = self.receive(bytes[used]);
+            used += 1;
+            if result != Ok(false) {
+                return (used, result);
+            }
+        }
+        (used, Ok(false))
+    }
+
     /// Reset the Raw, ready to try receiving a new frame from a CMRInet Network.
     pub fn reset(&mut self) {
         self.len = 0;
+        self.packet_len = 0;
         self.receive_state = ReceiveFrameState::WaitingForSyn;
     }
 
+    /// Describe how far through receiving a frame this Raw is,
+    /// E.G. for debugging a CMRInet which never completes a frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::frame::Raw;
+    /// let mut raw = Raw::new();
+    /// assert_eq!(raw.receive_state_description(), "waiting for first SYN");
+    /// raw.receive(0xFF);
+    /// assert_eq!(raw.receive_state_description(), "waiting for second SYN");
+    /// ```
+    #[must_use]
+    pub const fn receive_state_description(&self) -> &'static str {
+        match self.receive_state {
+            ReceiveFrameState::WaitingForSyn => "waiting for first SYN",
+            ReceiveFrameState::WaitingForSynSyn => "waiting for second SYN",
+            ReceiveFrameState::WaitingForSynSynStx => "waiting for STX",
+            ReceiveFrameState::Receiving => "receiving body",
+            ReceiveFrameState::ReceivingEscaped => "receiving escaped byte",
+            ReceiveFrameState::Received => "received"
+        }
+    }
+
     /// Decode this Raw into a `Packet`.
     ///
     /// # Errors
@@ -263,8 +354,45 @@ impl Raw {
     ///   * [`DecodeError::InvalidPacket`] if the packet inside the frame is invalid.
     ///
     pub fn try_as_packet(&self) -> Result<Packet, DecodeError> {
-        // SYN SYN STX <escaped packet data> ETX
         trace!("Raw.as_packet({self:?})");
+        let escaped = self.escaped_packet()?;
+        if self.len < 6 { return Err(PacketError::TooShort)? }
+        let mut unescaped = [0; RawPacket::MAX_LEN];
+        let len = super::unescape(escaped, &mut unescaped).map_err(|Full| PacketError::TooLong)?;
+        Ok(RawPacket::try_from(&unescaped[..len])?.try_decode()?)
+    }
+
+    /// Unescape the bytes between STX and ETX into buf, without decoding them as a `Packet`
+    /// (E.G. to forward or hash them). Returns the number of bytes written to buf.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::frame::Raw;
+    /// let frame = Raw::try_from(&[0xFF, 0xFF, 0x02, 65, b'T', 0x10, 0x02, 0x03]).unwrap();
+    /// let mut buf = [0; 8];
+    /// assert_eq!(frame.packet_bytes(&mut buf), Ok(3));
+    /// assert_eq!(buf[..3], [65, b'T', 0x02]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    ///   * [`DecodeError::TooShort`] if the frame is so short it couldn't possibly contain a packet.
+    ///   * [`DecodeError::MissingSynchronisation`] if the frame doesn't start with 2 SYN bytes.
+    ///   * [`DecodeError::MissingStart`] if the SYN bytes aren't followed by a STX byte.
+    ///   * [`DecodeError::MissingEnd`] if the frame doesn't end with an ETX byte.
+    ///   * [`DecodeError::TooLong`] if the unescaped bytes don't fit in buf.
+    pub fn packet_bytes(&self, buf: &mut [u8]) -> Result<usize, DecodeError> {
+        super::unescape(self.escaped_packet()?, buf).map_err(|_| DecodeError::TooLong)
+    }
+
+    /// Check the frame's structure (SYN SYN STX <escaped packet data> ETX),
+    /// returning the escaped packet data.
+    ///
+    /// # Errors
+    ///
+    /// As for [`Self::packet_bytes`], except for [`DecodeError::TooLong`].
+    fn escaped_packet(&self) -> Result<&[u8], DecodeError> {
         if self.len < 4 {
             return Err(DecodeError::TooShort);
         }
@@ -277,19 +405,94 @@ impl Raw {
         if self.raw[self.len - 1] != ETX {
             return Err(DecodeError::MissingEnd);
         }
+        Ok(&self.raw[3..(self.len - 1)])
+    }
 
-        if self.len < 6 { return Err(PacketError::TooShort)? }
-        let mut raw_packet = RawPacket::new();
-        let mut escape = false;
-        for &byte in &self.raw[3..(self.len-1)] {
-            if byte == DLE && !escape {
-                escape = true;
-            } else {
-                escape = false;
-                if raw_packet.push(byte).is_err() { return Err(PacketError::TooLong)? };
+    /// Decode this Raw into a `Packet`, along with the node address and message type
+    /// which are available even if decoding the rest of the packet fails
+    /// (E.G. so a monitor can still show who sent a packet with an invalid node configuration).
+    ///
+    /// The address and message type are only given if the frame starts with SYN SYN STX
+    /// and is long enough to contain them, and each is `None` if it isn't valid.
+    #[must_use = "this returns the decoded packet without modifying the original"]
+    pub fn try_as_packet_partial(&self) -> (Option<u8>, Option<char>, Result<Packet, DecodeError>) {
+        let result = self.try_as_packet();
+        if self.len < 6 || self.raw[0..3] != [SYN, SYN, STX] {
+            return (None, None, result);
+        }
+        (self.address(), self.message_type(), result)
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "heapless")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature heapless only.**\n\n")]
+impl Raw {
+    /// Copy the frame's bytes into a `heapless::Vec`.
+    ///
+    /// ```
+    /// use cmri::frame::Raw;
+    /// let frame = Raw::try_from(&[0xFF, 0xFF, 0x02, 65, b'P', 0x03]).unwrap();
+    /// let vec = frame.to_heapless::<8>().unwrap();
+    /// assert_eq!(vec.as_slice(), &[0xFF, 0xFF, 0x02, 65, b'P', 0x03]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`Full`] if the frame is longer than N bytes.
+    pub fn to_heapless<const N: usize>(&self) -> Result<heapless::Vec<u8, N>, Full> {
+        heapless::Vec::from_slice(self.as_slice()).map_err(|()| Full)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "std")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature std only.**\n\n")]
+impl Raw {
+    /// Read a frame from a blocking reader (E.G. in tests or simple tools).
+    ///
+    /// Bytes are read one at a time, so nothing after the frame is consumed from the reader.
+    ///
+    /// ```
+    /// use cmri::frame::Raw;
+    /// let mut reader = std::io::Cursor::new([0x00, 0xFF, 0xFF, 0x02, 65, b'P', 0x03, 0xFF]);
+    /// let frame = Raw::read_from(&mut reader).unwrap();
+    /// assert_eq!(frame.as_slice(), &[0xFF, 0xFF, 0x02, 65, b'P', 0x03]);
+    /// assert_eq!(reader.position(), 7);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    ///   * [`super::ReadError::Io`] if reading fails, with `std::io::ErrorKind::UnexpectedEof` if the reader ends before a frame is complete.
+    ///   * [`super::ReadError::Receive`] if the bytes read are too short/long for a frame (see [`Self::receive`]).
+    pub fn read_from(reader: &mut impl std::io::Read) -> Result<Self, super::ReadError> {
+        let mut frame = Self::new();
+        let mut byte = [0];
+        loop {
+            match reader.read(&mut byte) {
+                Ok(0) => return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+                Ok(_) => if frame.receive(byte[0])? { return Ok(frame) },
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(error) => return Err(error.into())
             }
         }
-        Ok(raw_packet.try_decode()?)
+    }
+
+    /// Write the frame to a blocking writer.
+    ///
+    /// ```
+    /// use cmri::frame::Raw;
+    /// let frame = Raw::try_from(&[0xFF, 0xFF, 0x02, 65, b'P', 0x03]).unwrap();
+    /// let mut writer = Vec::new();
+    /// frame.write_to(&mut writer).unwrap();
+    /// assert_eq!(writer, frame.as_slice());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If writing fails, see `std::io::Write::write_all`.
+    pub fn write_to(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        writer.write_all(self.as_slice())
     }
 }
 
@@ -363,6 +566,63 @@ impl<const N: usize> TryFrom<[u8; N]> for Raw {
     }
 }
 
+/// Serialized as the frame's bytes, exactly as they'd be on the wire (including any escaping).
+#[cfg(feature = "serde")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "serde")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature serde only.**\n\n")]
+impl serde::Serialize for Raw {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+        serializer.serialize_bytes(self)
+    }
+}
+
+/// Deserialized from the frame's bytes (as bytes or a sequence), which must be a valid length for a `Raw`.
+#[cfg(feature = "serde")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "serde")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature serde only.**\n\n")]
+impl<'de> serde::Deserialize<'de> for Raw {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+        struct Visitor;
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Raw;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(formatter, "Between 4 and {} bytes", Self::Value::MAX_LEN)
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> where E: serde::de::Error {
+                Self::Value::try_from(v).map_err(serde::de::Error::custom)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
+                let mut bytes = [0; Self::Value::MAX_LEN];
+                let mut len = 0;
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    if len == bytes.len() {
+                        return Err(serde::de::Error::custom(DecodeError::TooLong));
+                    }
+                    bytes[len] = byte;
+                    len += 1;
+                }
+                self.visit_bytes(&bytes[..len])
+            }
+        }
+
+        deserializer.deserialize_bytes(Visitor)
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "heapless")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature heapless only.**\n\n")]
+impl<const N: usize> TryFrom<&heapless::Vec<u8, N>> for Raw {
+    type Error = DecodeError;
+
+    fn try_from(value: &heapless::Vec<u8, N>) -> Result<Self, Self::Error> {
+        TryFrom::try_from(value.as_slice())
+    }
+}
+
 impl TryFrom<&RawPacket> for Raw {
     type Error = PacketError;
 
@@ -552,6 +812,27 @@ mod tests {
     mod receive {
         use super::*;
 
+        #[test]
+        fn state_description() {
+            let mut raw_frame = Raw::new();
+            assert_eq!(raw_frame.receive_state_description(), "waiting for first SYN");
+            for (byte, description) in [
+                (SYN, "waiting for second SYN"),
+                (SYN, "waiting for STX"),
+                (STX, "receiving body"),
+                (65, "receiving body"),
+                (DLE, "receiving escaped byte"),
+                (b'P', "receiving body"),
+                (ETX, "received")
+            ] {
+                let _ = raw_frame.receive(byte);
+                assert_eq!(raw_frame.receive_state_description(), description, "After receiving {byte:#04x}");
+            }
+
+            raw_frame.reset();
+            assert_eq!(raw_frame.receive_state_description(), "waiting for first SYN");
+        }
+
         mod waits_for_first_syn {
             use super::*;
 
@@ -703,6 +984,7 @@ mod tests {
 
                 // Resets ready to try again
                 assert_eq!(raw_frame.len, 0);
+                assert_eq!(raw_frame.packet_len, 0);
                 assert_eq!(raw_frame.receive_state, ReceiveFrameState::WaitingForSyn);
             }
 
@@ -715,6 +997,149 @@ mod tests {
         }
     }
 
+    mod receive_slice {
+        use super::*;
+
+        /// Check receiving bytes a slice at a time matches receiving them a byte at a time.
+        fn compare(bytes: &[u8]) {
+            let mut expected = Raw::new();
+            let mut actual = Raw::new();
+            let mut start = 0;
+            for (index, byte) in bytes.iter().enumerate() {
+                let result = expected.receive(*byte);
+                if result != Ok(false) {
+                    assert_eq!(actual.receive_slice(&bytes[start..]), (index + 1 - start, result), "From byte {start}");
+                    assert_eq!(actual.as_slice(), expected.as_slice());
+                    expected.reset();
+                    actual.reset();
+                    start = index + 1;
+                }
+            }
+            assert_eq!(actual.receive_slice(&bytes[start..]), (bytes.len() - start, Ok(false)));
+            assert_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn back_to_back() {
+            compare(&[SYN, SYN, STX, 65, b'P', ETX, SYN, SYN, STX, 66, b'T', 1, 2, 3, ETX]);
+        }
+
+        #[test]
+        fn escaped() {
+            compare(&[SYN, SYN, STX, 65, b'T', DLE, STX, 1, DLE, ETX, 2, DLE, DLE, 3, ETX]);
+        }
+
+        #[test]
+        fn noise_between_frames() {
+            compare(&[0, 1, SYN, SYN, STX, 65, b'P', ETX, SYN, 7, SYN, SYN, SYN, STX, 66, b'P', ETX, 9]);
+        }
+
+        #[test]
+        fn partial_frame_at_end() {
+            compare(&[SYN, SYN, STX, 65, b'P', ETX, SYN, SYN, STX, 66, b'T', DLE]);
+        }
+
+        #[test]
+        fn too_short() {
+            compare(&[SYN, SYN, STX, 65, ETX, SYN, SYN, STX, 65, b'P', ETX]);
+        }
+
+        #[test]
+        fn too_long() {
+            let mut bytes = [1; 3 + RawPacket::MAX_LEN + 2 + 6];
+            bytes[..3].copy_from_slice(&[SYN, SYN, STX]);
+            bytes[(3 + RawPacket::MAX_LEN + 2)..].copy_from_slice(&[SYN, SYN, STX, 65, b'P', ETX]);
+            compare(&bytes);
+        }
+
+        #[test]
+        fn longest() {
+            let mut bytes = [1; 3 + RawPacket::MAX_LEN + 1];
+            bytes[..3].copy_from_slice(&[SYN, SYN, STX]);
+            bytes[3 + RawPacket::MAX_LEN] = ETX;
+            compare(&bytes);
+        }
+
+        #[test]
+        fn in_pieces() {
+            let mut raw_frame = Raw::new();
+            assert_eq!(raw_frame.receive_slice(&[SYN, SYN, STX, 65]), (4, Ok(false)));
+            assert_eq!(raw_frame.receive_slice(&[]), (0, Ok(false)));
+            assert_eq!(raw_frame.receive_slice(&[b'T', DLE]), (2, Ok(false)));
+            assert_eq!(raw_frame.receive_slice(&[ETX, 5, ETX, SYN]), (3, Ok(true)));
+            assert_eq!(raw_frame.as_slice(), &[SYN, SYN, STX, 65, b'T', DLE, ETX, 5, ETX]);
+        }
+
+        #[test]
+        fn already_complete() {
+            let mut raw_frame = Raw::new();
+            assert_eq!(raw_frame.receive_slice(&[SYN, SYN, STX, 65, b'P', ETX]), (6, Ok(true)));
+            assert_eq!(raw_frame.receive_slice(&[SYN]), (0, Err(ReceiveError::AlreadyComplete)));
+        }
+
+        #[cfg(toolchain = "nightly")]
+        mod benchmarks {
+            use super::*;
+            use test::Bencher;
+
+            const POLL_REQUEST: [u8; 6] = [SYN, SYN, STX, 65, b'P', ETX];
+            const TRANSMIT_DATA: [u8; 38] = [
+                SYN, SYN, STX, 65, b'T',
+                0x00, 0x01, DLE, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+                0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2A, 0x2B, 0x2C, 0x2D, 0x2E,
+                ETX
+            ];
+            const PAIRS: usize = 200;
+
+            /// Back to back poll requests and transmit data frames (8,800 bytes in 400 frames).
+            fn stream() -> [u8; PAIRS * (POLL_REQUEST.len() + TRANSMIT_DATA.len())] {
+                let mut stream = [0; PAIRS * (POLL_REQUEST.len() + TRANSMIT_DATA.len())];
+                for pair in stream.chunks_exact_mut(POLL_REQUEST.len() + TRANSMIT_DATA.len()) {
+                    pair[..POLL_REQUEST.len()].copy_from_slice(&POLL_REQUEST);
+                    pair[POLL_REQUEST.len()..].copy_from_slice(&TRANSMIT_DATA);
+                }
+                stream
+            }
+
+            #[bench]
+            // A byte at a time - 34,306 ns/iter (+/- 10,151)
+            fn receive(bencher: &mut Bencher) {
+                let stream = stream();
+                bencher.iter(|| {
+                    let mut raw_frame = Raw::new();
+                    let mut frames = 0;
+                    for &byte in &stream {
+                        if raw_frame.receive(byte).unwrap() {
+                            frames += 1;
+                            raw_frame.reset();
+                        }
+                    }
+                    assert_eq!(frames, PAIRS * 2);
+                });
+            }
+
+            #[bench]
+            // A slice at a time - 26,270 ns/iter (+/- 2,393)
+            fn receive_slice(bencher: &mut Bencher) {
+                let stream = stream();
+                bencher.iter(|| {
+                    let mut raw_frame = Raw::new();
+                    let mut frames = 0;
+                    let mut bytes = stream.as_slice();
+                    while !bytes.is_empty() {
+                        let (used, result) = raw_frame.receive_slice(bytes);
+                        bytes = &bytes[used..];
+                        if result.unwrap() {
+                            frames += 1;
+                            raw_frame.reset();
+                        }
+                    }
+                    assert_eq!(frames, PAIRS * 2);
+                });
+            }
+        }
+    }
+
     mod build {
         use super::*;
 
@@ -776,6 +1201,98 @@ mod tests {
         }
     }
 
+    mod packet_bytes {
+        use super::*;
+
+        #[test]
+        fn escaped() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 65, b'T', DLE, STX, DLE, ETX, DLE, DLE, 7, ETX]).unwrap();
+            let mut buf = [0; 8];
+            assert_eq!(raw_frame.packet_bytes(&mut buf), Ok(6));
+            assert_eq!(buf[..6], [65, b'T', STX, ETX, DLE, 7]);
+        }
+
+        #[test]
+        fn not_a_packet() {
+            // Only the frame's structure is checked, not the packet within it
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 200, ETX]).unwrap();
+            let mut buf = [0; 8];
+            assert_eq!(raw_frame.packet_bytes(&mut buf), Ok(1));
+            assert_eq!(buf[0], 200);
+        }
+
+        #[test]
+        fn buffer_too_short() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 65, b'T', DLE, STX, 1, ETX]).unwrap();
+            let mut buf = [0; 3];
+            assert_eq!(raw_frame.packet_bytes(&mut buf), Err(DecodeError::TooLong));
+        }
+
+        #[test]
+        fn empty() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, ETX]).unwrap();
+            assert_eq!(raw_frame.packet_bytes(&mut []), Ok(0));
+        }
+
+        #[test]
+        fn too_short() {
+            assert_eq!(Raw::new().packet_bytes(&mut [0; 8]), Err(DecodeError::TooShort));
+        }
+
+        #[test]
+        fn missing_synchronisation() {
+            let raw_frame = Raw::try_from(&[SYN, 0, STX, 65, b'P', ETX]).unwrap();
+            assert_eq!(raw_frame.packet_bytes(&mut [0; 8]), Err(DecodeError::MissingSynchronisation));
+        }
+
+        #[test]
+        fn missing_start() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, 0, 65, b'P', ETX]).unwrap();
+            assert_eq!(raw_frame.packet_bytes(&mut [0; 8]), Err(DecodeError::MissingStart));
+        }
+
+        #[test]
+        fn missing_end() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 65, b'P', 0]).unwrap();
+            assert_eq!(raw_frame.packet_bytes(&mut [0; 8]), Err(DecodeError::MissingEnd));
+        }
+    }
+
+    mod try_as_packet_partial {
+        use super::*;
+
+        #[test]
+        fn valid() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 75, b'P', ETX]).unwrap();
+            assert_eq!(
+                raw_frame.try_as_packet_partial(),
+                (Some(10), Some('P'), Ok(Packet::new_poll_request(Address::try_from_node_address(10).unwrap())))
+            );
+        }
+
+        #[test]
+        fn invalid_node_configuration() {
+            // A CPNODE with 0 input and 0 output bytes
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 70, b'I', b'C', 0, 0, 0, 0, 0, 0, ETX]).unwrap();
+            let (address, message_type, result) = raw_frame.try_as_packet_partial();
+            assert_eq!(address, Some(5));
+            assert_eq!(message_type, Some('I'));
+            assert!(matches!(result, Err(DecodeError::InvalidPacket { source: PacketError::InvalidConfiguration { .. } })));
+        }
+
+        #[test]
+        fn invalid_header() {
+            let raw_frame = Raw::try_from(&[0, SYN, STX, 70, b'P', ETX]).unwrap();
+            assert_eq!(raw_frame.try_as_packet_partial(), (None, None, Err(DecodeError::MissingSynchronisation)));
+        }
+
+        #[test]
+        fn too_short() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, ETX]).unwrap();
+            assert_eq!(raw_frame.try_as_packet_partial(), (None, None, Err(DecodeError::InvalidPacket { source: PacketError::TooShort })));
+        }
+    }
+
     mod address {
         use super::*;
 
@@ -792,6 +1309,29 @@ mod tests {
         }
     }
 
+    mod is_for {
+        use super::*;
+
+        #[test]
+        fn matching() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 67, b'P', ETX]).unwrap();
+            assert!(raw_frame.is_for(2));
+        }
+
+        #[test]
+        fn mismatched() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 67, b'P', ETX]).unwrap();
+            assert!(!raw_frame.is_for(3));
+        }
+
+        #[test]
+        fn invalid_address() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 200, b'P', ETX]).unwrap();
+            assert!(!raw_frame.is_for(200 - 65));
+            assert!(!raw_frame.is_for(200));
+        }
+    }
+
     mod message_type {
         use super::*;
 
@@ -807,6 +1347,26 @@ mod tests {
             assert_eq!(raw_frame.message_type(), None);
         }
 
+        #[test]
+        fn set() {
+            let raw_frame = Raw::try_from(&[SYN, SYN, STX, 65, b'S', 0x01, ETX]).unwrap();
+            assert_eq!(raw_frame.message_type(), Some('S'));
+        }
+
+        #[cfg(not(feature = "experimenter"))]
+        #[test]
+        fn known_message_types() {
+            let mut known = [0; 5];
+            let mut count = 0;
+            for byte in 0..=u8::MAX {
+                if Raw::try_from(&[SYN, SYN, STX, 65, byte, ETX]).unwrap().message_type().is_some() {
+                    known[count] = byte;
+                    count += 1;
+                }
+            }
+            assert_eq!(known, *b"IPRST");
+        }
+
         #[cfg(not(feature = "experimenter"))]
         #[test]
         fn unknown_message_type() {
@@ -827,6 +1387,24 @@ mod tests {
             );
         }
 
+        #[test]
+        fn is_valid_matches() {
+            for byte in 0..=u8::MAX {
+                let raw_frame = Raw::try_from(&[SYN, SYN, STX, 65, byte, ETX]).unwrap();
+                assert_eq!(raw_frame.message_type_is_valid(), raw_frame.message_type().is_some(), "Message type 0x{byte:02X}");
+            }
+        }
+
+        #[test]
+        fn is_valid() {
+            assert!(Raw::try_from(&[SYN, SYN, STX, 65, b'T', ETX]).unwrap().message_type_is_valid());
+            assert!(Raw::try_from(&[SYN, SYN, STX, 65, b'S', ETX]).unwrap().message_type_is_valid());
+            assert!(!Raw::try_from(&[SYN, SYN, STX, 65, b't', ETX]).unwrap().message_type_is_valid());
+            #[cfg(not(feature = "experimenter"))]
+            assert!(!Raw::try_from(&[SYN, SYN, STX, 65, b'Z', ETX]).unwrap().message_type_is_valid());
+            #[cfg(feature = "experimenter")]
+            assert!(Raw::try_from(&[SYN, SYN, STX, 65, b'Z', ETX]).unwrap().message_type_is_valid());
+        }
     }
 
     #[test]
@@ -843,6 +1421,70 @@ mod tests {
     }
 
 
+    #[cfg(feature = "std")]
+    mod read_from {
+        use std::io::{Cursor, Read};
+        use super::*;
+        use crate::frame::ReadError;
+
+        const FRAME: [u8; 7] = [0xFF, 0xFF, 0x02, 65, DLE, ETX, 0x03];
+
+        #[test]
+        fn split_awkwardly() {
+            // Split within the preamble and between an escape and the escaped byte
+            let mut reader = Cursor::new(&FRAME[..1])
+                .chain(Cursor::new(&FRAME[1..5]))
+                .chain(Cursor::new(&FRAME[5..]));
+            assert_eq!(Raw::read_from(&mut reader).unwrap().as_slice(), &FRAME);
+        }
+
+        #[test]
+        fn trailing_garbage() {
+            let mut bytes = FRAME.to_vec();
+            bytes.extend_from_slice(&[0x00, 0xFF, 0x12]);
+            let mut reader = Cursor::new(bytes);
+            assert_eq!(Raw::read_from(&mut reader).unwrap().as_slice(), &FRAME);
+            assert_eq!(reader.position(), 7, "The garbage should be left unread");
+            assert!(matches!(Raw::read_from(&mut reader), Err(ReadError::Io(error)) if error.kind() == std::io::ErrorKind::UnexpectedEof));
+        }
+
+        #[test]
+        fn leading_garbage() {
+            let mut bytes = vec![0x00, 0x12];
+            bytes.extend_from_slice(&FRAME);
+            assert_eq!(Raw::read_from(&mut Cursor::new(bytes)).unwrap().as_slice(), &FRAME);
+        }
+
+        #[test]
+        fn eof_before_complete() {
+            let result = Raw::read_from(&mut Cursor::new(&FRAME[..4]));
+            assert!(matches!(result, Err(ReadError::Io(error)) if error.kind() == std::io::ErrorKind::UnexpectedEof));
+        }
+
+        #[test]
+        fn too_short() {
+            let result = Raw::read_from(&mut Cursor::new([0xFF, 0xFF, 0x02, 65, 0x03]));
+            assert!(matches!(result, Err(ReadError::Receive(ReceiveError::TooShort))));
+        }
+
+        #[test]
+        fn too_long() {
+            let mut bytes = vec![0xFF, 0xFF, 0x02];
+            bytes.extend_from_slice(&[65; 300]);
+            let result = Raw::read_from(&mut Cursor::new(bytes));
+            assert!(matches!(result, Err(ReadError::Receive(ReceiveError::TooLong))));
+        }
+
+        #[test]
+        fn write_to() {
+            let frame = Raw::try_from(&FRAME).unwrap();
+            let mut writer = Cursor::new(Vec::new());
+            frame.write_to(&mut writer).unwrap();
+            writer.set_position(0);
+            assert_eq!(Raw::read_from(&mut writer).unwrap(), frame);
+        }
+    }
+
     mod try_from_slice_u8 {
         use super::*;
 
@@ -884,6 +1526,71 @@ mod tests {
         assert_eq!(raw_frame.receive_state, super::ReceiveFrameState::WaitingForSyn);
     }
 
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::*;
+        use serde_test::{assert_tokens, assert_de_tokens, assert_de_tokens_error, Token};
+
+        #[test]
+        fn round_trip() {
+            // The body is escaped DLE, STX & ETX bytes
+            let frame = Raw::try_from(&[SYN, SYN, STX, 65, b'T', DLE, DLE, DLE, STX, DLE, ETX, ETX]).unwrap();
+            assert_tokens(&frame, &[Token::Bytes(&[SYN, SYN, STX, 65, b'T', DLE, DLE, DLE, STX, DLE, ETX, ETX])]);
+        }
+
+        #[test]
+        fn from_seq() {
+            let frame = Raw::try_from(&[SYN, SYN, STX, 65, b'P', ETX]).unwrap();
+            assert_de_tokens(&frame, &[
+                Token::Seq { len: Some(6) },
+                Token::U8(SYN), Token::U8(SYN), Token::U8(STX), Token::U8(65), Token::U8(b'P'), Token::U8(ETX),
+                Token::SeqEnd
+            ]);
+        }
+
+        #[test]
+        fn too_short() {
+            assert_de_tokens_error::<Raw>(&[Token::Bytes(&[SYN, SYN, STX])], "Frame is too short");
+            assert_de_tokens_error::<Raw>(&[Token::Seq { len: Some(0) }, Token::SeqEnd], "Frame is too short");
+        }
+
+        #[test]
+        fn too_long() {
+            assert_de_tokens_error::<Raw>(&[Token::Bytes(&[0; Raw::MAX_LEN + 1])], "Raw frame is too long");
+        }
+    }
+
+    #[cfg(feature = "heapless")]
+    mod heapless {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let frame = Raw::try_from(&[SYN, SYN, STX, 65, b'T', DLE, ETX, ETX]).unwrap();
+            let vec = frame.to_heapless::<16>().unwrap();
+            assert_eq!(vec.as_slice(), frame.as_slice());
+            assert_eq!(Raw::try_from(&vec).unwrap(), frame);
+        }
+
+        #[test]
+        fn exact_fit() {
+            let frame = Raw::try_from(&[SYN, SYN, STX, 65, b'P', ETX]).unwrap();
+            assert_eq!(frame.to_heapless::<6>().unwrap().as_slice(), frame.as_slice());
+        }
+
+        #[test]
+        fn too_large() {
+            let frame = Raw::try_from(&[SYN, SYN, STX, 65, b'P', ETX]).unwrap();
+            assert_eq!(frame.to_heapless::<5>(), Err(Full));
+        }
+
+        #[test]
+        fn too_short() {
+            let vec = ::heapless::Vec::<u8, 8>::from_slice(&[SYN, SYN, STX]).unwrap();
+            assert_eq!(Raw::try_from(&vec), Err(DecodeError::TooShort));
+        }
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn lower_hex() {
@@ -22,13 +22,19 @@ pub const BAUDS: [u32; 5] = [9_600, 19_200, 28_800, 57_600, 115_200];
 pub const DEFAULT_BAUD: u32 = 19_200;
 
 mod address;
+mod address_set;
 mod raw_structs;
 
 pub mod packet;
 pub mod frame;
 pub mod node_configuration;
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "testing")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature testing only.**\n\n")]
+pub mod testing;
 
-pub use address::Address;
+pub use address::{Address, ParseAddressError};
+pub use address_set::{AddressSet, AddressSetIter};
 pub use packet::Packet;
 pub use node_configuration::{NodeSort, NodeConfiguration};
 pub use frame::Raw as Frame;
@@ -69,6 +75,7 @@ mod tests {
         is_decent_error::<frame::Full>();
 
         is_decent::<packet::Packet>();
+        is_orderable::<packet::Packet>();
         is_decent_collection::<packet::Raw>();
         is_decent_collection::<packet::Data>();
         is_decent::<packet::Payload>();
@@ -95,7 +102,7 @@ mod tests {
 
         test::<Address>();
 
-        // Exclude crate::frame::Raw
+        test::<frame::Raw>();
         test::<frame::DecodeError>();
         test::<frame::ReceiveError>();
         test::<frame::Full>();
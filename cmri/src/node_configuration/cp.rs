@@ -1,12 +1,16 @@
 //! Details for CPNODEs and CPMEGAs.
 
 /// Errors which can happen when decoding/creating a `CpnodeConfiguration` or `CpmegaConfiguration`.
-#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error {
     /// Invalid number of input/output bits for a CPNODE or CPMEGA
     #[error("Invalid input/output bits count: {0:?} not within {1:?}")]
-    InvalidInputOutputBitsCount(u16, core::ops::RangeInclusive<u8>)
+    InvalidInputOutputBitsCount(u16, core::ops::RangeInclusive<u8>),
+
+    /// Options bits are set which aren't documented (so may be from a different firmware version)
+    #[error("Unknown option bits set: {0:#06x}")]
+    UnknownOptionBits(u16)
 }
 impl From<Error> for crate::packet::Error {
     fn from(source: Error) -> Self {
@@ -108,15 +112,35 @@ macro_rules! common_implementation {
                     })
                 }
 
+                #[doc = concat!("Create a new `Configuration`, rejecting options bits other than")]
+                #[doc = "`USE_CMRIX`, `CAN_SEND_EOT_ON_NO_INPUTS_CHANGED` and `USE_BCC` (`try_new` keeps them)."]
+                #[doc = ""]
+                #[doc = "# Errors"]
+                #[doc = ""]
+                #[doc = "* [`Error::UnknownOptionBits`] if any other options bits are set."]
+                #[doc = concat!("* [`Error::InvalidInputOutputBitsCount`] if the total number of input and output bits is invalid for a `Configuration` (", stringify!(bits), ").")]
+                pub const fn try_new_strict(transmit_delay: u16, options: Options, input_bytes: u8, output_bytes: u8) -> Result<Self, Error> {
+                    let known = Options::USE_CMRIX.union(Options::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED).union(Options::USE_BCC);
+                    let unknown = options.difference(known);
+                    if !unknown.is_empty() {
+                        return Err(Error::UnknownOptionBits(unknown.bits()))
+                    }
+                    Self::try_new(transmit_delay, options, input_bytes, output_bytes)
+                }
+
                 #[doc = concat!("Create a new `Configuration` from raw bytes.")]
                 #[doc = ""]
                 #[doc = "# Errors"]
                 #[doc = ""]
+                #[doc = "* [`PacketError::TooShort`] if the slice is shorter than the 7 bytes needed."]
                 #[doc = concat!("* [`PacketError::InvalidNodeType`] if the NDP byte isn't valid for a ", stringify!($name), " node.")]
                 #[doc = "* [`PacketError::InvalidConfiguration`]:"]
                 #[doc = concat!("  * [`Error::InvalidInputOutputBitsCount`] if the total number of input and output bits is invalid for a `", stringify!($name), "` (", stringify!($bits), ").")]
                 pub(in super::super) fn decode(raw: &[u8]) -> Result<Self, PacketError> {
                     trace!(concat!(stringify!($name), "::decode({:?})"), raw);
+                    if raw.len() < 7 {
+                        return Err(PacketError::TooShort)
+                    }
                     if raw[0] != $ndp {
                         return Err(PacketError::InvalidNodeType(raw[0]))
                     }
@@ -279,6 +303,26 @@ mod tests {
                 );
             }
 
+            #[test]
+            fn strict_known_options() {
+                let options = CpnodeOptions::USE_CMRIX | CpnodeOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED | CpnodeOptions::USE_BCC;
+                assert_eq!(
+                    CpnodeConfiguration::try_new_strict(3, options, 5, 6),
+                    CpnodeConfiguration::try_new(3, options, 5, 6)
+                );
+                assert!(CpnodeConfiguration::try_new_strict(3, options, 5, 6).is_ok());
+            }
+
+            #[test]
+            fn strict_unknown_options() {
+                let options = CpnodeOptions::USE_BCC | CpnodeOptions::BIT_3 | CpnodeOptions::BIT_15;
+                assert_eq!(
+                    CpnodeConfiguration::try_new_strict(3, options, 5, 6),
+                    Err(Error::UnknownOptionBits(0x8008))
+                );
+                assert!(CpnodeConfiguration::try_new(3, options, 5, 6).is_ok());
+            }
+
             #[test]
             fn too_few_bytes() {
                 assert_eq!(
@@ -363,6 +407,15 @@ mod tests {
                     Err(PacketError::InvalidNodeType(90))
                 );
             }
+
+            #[test]
+            fn too_short() {
+                assert_eq!(CpnodeConfiguration::decode(&[]), Err(PacketError::TooShort));
+                assert_eq!(CpnodeConfiguration::decode(b"C"), Err(PacketError::TooShort));
+                assert_eq!(CpnodeConfiguration::decode(&[b'C', 0]), Err(PacketError::TooShort));
+                assert_eq!(CpnodeConfiguration::decode(&[b'C', 0, 0]), Err(PacketError::TooShort));
+                assert_eq!(CpnodeConfiguration::decode(&[b'C', 0, 0, 0, 0, 0]), Err(PacketError::TooShort));
+            }
         }
 
         mod getters {
@@ -394,6 +447,9 @@ mod tests {
             assert_eq!(configuration.transmit_delay(), 200);
             assert_eq!(configuration.input_bytes(), 4);
             assert_eq!(configuration.output_bytes(), 5);
+            assert_eq!(configuration.total_bytes(), configuration.input_bytes() + configuration.output_bytes());
+            assert_eq!(configuration.total_bits(), configuration.input_bits() + configuration.output_bits());
+            assert_eq!(configuration.total_bits(), 72);
         }
 
         #[cfg(feature = "serde")]
@@ -572,6 +628,26 @@ mod tests {
                 );
             }
 
+            #[test]
+            fn strict_known_options() {
+                let options = CpmegaOptions::USE_CMRIX | CpmegaOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED | CpmegaOptions::USE_BCC;
+                assert_eq!(
+                    CpmegaConfiguration::try_new_strict(3, options, 5, 6),
+                    CpmegaConfiguration::try_new(3, options, 5, 6)
+                );
+                assert!(CpmegaConfiguration::try_new_strict(3, options, 5, 6).is_ok());
+            }
+
+            #[test]
+            fn strict_unknown_options() {
+                let options = CpmegaOptions::USE_BCC | CpmegaOptions::BIT_3 | CpmegaOptions::BIT_15;
+                assert_eq!(
+                    CpmegaConfiguration::try_new_strict(3, options, 5, 6),
+                    Err(Error::UnknownOptionBits(0x8008))
+                );
+                assert!(CpmegaConfiguration::try_new(3, options, 5, 6).is_ok());
+            }
+
             #[test]
             fn too_many_bytes() {
                 assert_eq!(
@@ -636,6 +712,15 @@ mod tests {
                     Err(PacketError::InvalidNodeType(90))
                 );
             }
+
+            #[test]
+            fn too_short() {
+                assert_eq!(CpmegaConfiguration::decode(&[]), Err(PacketError::TooShort));
+                assert_eq!(CpmegaConfiguration::decode(b"O"), Err(PacketError::TooShort));
+                assert_eq!(CpmegaConfiguration::decode(&[b'O', 0]), Err(PacketError::TooShort));
+                assert_eq!(CpmegaConfiguration::decode(&[b'O', 0, 0]), Err(PacketError::TooShort));
+                assert_eq!(CpmegaConfiguration::decode(&[b'O', 0, 0, 0, 0, 0]), Err(PacketError::TooShort));
+            }
         }
 
         mod getters {
@@ -667,6 +752,9 @@ mod tests {
             assert_eq!(configuration.transmit_delay(), 200);
             assert_eq!(configuration.input_bytes(), 4);
             assert_eq!(configuration.output_bytes(), 5);
+            assert_eq!(configuration.total_bytes(), configuration.input_bytes() + configuration.output_bytes());
+            assert_eq!(configuration.total_bits(), configuration.input_bits() + configuration.output_bits());
+            assert_eq!(configuration.total_bits(), 72);
         }
 
         #[cfg(feature = "serde")]
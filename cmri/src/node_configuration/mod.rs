@@ -39,11 +39,105 @@ pub trait NodeConfiguration {
 
     /// The number of output bits on the node.
     fn output_bits(&self) -> u16 { self.output_bytes() * 8 }
+
+    /// The total number of input and output bytes on the node.
+    fn total_bytes(&self) -> u16 { self.input_bytes() + self.output_bytes() }
+
+    /// The total number of input and output bits on the node.
+    fn total_bits(&self) -> u16 { self.input_bits() + self.output_bits() }
+
+    /// Check data (E.G. from a receive data packet) is the same length as the node's input bytes.
+    ///
+    /// # Errors
+    ///
+    /// [`crate::packet::Error::DataLengthMismatch`] if the data is shorter or longer than the node's input bytes.
+    fn validate_input_data(&self, data: &crate::packet::Data) -> Result<(), crate::packet::Error> {
+        check_data_len(self.input_bytes(), data)
+    }
+
+    /// Check data (E.G. for a transmit data packet) is the same length as the node's output bytes,
+    /// as a node would misinterpret outputs of the wrong length.
+    ///
+    /// # Errors
+    ///
+    /// [`crate::packet::Error::DataLengthMismatch`] if the data is shorter or longer than the node's output bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{NodeSort, packet::{Data, Error}};
+    /// let node_sort = NodeSort::try_new_smini(0, [0; 6]).unwrap();
+    /// assert_eq!(node_sort.configuration().validate_output_data(&Data::new(6)), Ok(()));
+    /// assert_eq!(node_sort.configuration().validate_output_data(&Data::new(5)), Err(Error::DataLengthMismatch { expected: 6, actual: 5 }));
+    /// ```
+    fn validate_output_data(&self, data: &crate::packet::Data) -> Result<(), crate::packet::Error> {
+        check_data_len(self.output_bytes(), data)
+    }
+
+    /// Describe each of the node's bits, all the inputs (in order) followed by all the outputs (in order).
+    ///
+    /// By default bits aren't on cards, as is the case for CPNODEs and CPMEGAs.
+    #[cfg(feature = "std")]
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "std")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature std only.**\n\n")]
+    fn bit_map(&self) -> Vec<BitInfo> {
+        let bits = |direction, count| (0..count).map(move |index| BitInfo {
+            direction,
+            index,
+            card: None,
+            byte: index / 8,
+            bit: (index % 8) as u8
+        });
+        bits(Direction::Input, self.input_bits())
+            .chain(bits(Direction::Output, self.output_bits()))
+            .collect()
+    }
+}
+
+
+/// Whether a bit is an input or output.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// The bit is an input (sent from the node to the controller).
+    Input,
+    /// The bit is an output (sent from the controller to the node).
+    Output
+}
+
+/// Where one of a node's bits is, see [`NodeConfiguration::bit_map`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitInfo {
+    /// Whether the bit is an input or output.
+    pub direction: Direction,
+    /// The bit's index within the node's inputs or outputs.
+    pub index: u16,
+    /// The card the bit is on (by position on the node), `None` if the node doesn't have cards.
+    pub card: Option<u8>,
+    /// The byte (port) the bit is in, within the card (or within the node's inputs or outputs if it doesn't have cards).
+    pub byte: u16,
+    /// The bit within the byte (0-7).
+    pub bit: u8
+}
+
+/// Describe the bits on a sequence of cards with `bytes_per_card` bytes each, for overriding `NodeConfiguration::bit_map`.
+#[cfg(feature = "std")]
+fn card_bit_map(direction: Direction, cards: impl Iterator<Item = u8>, bytes_per_card: u8) -> impl Iterator<Item = BitInfo> {
+    let bits_per_card = u16::from(bytes_per_card) * 8;
+    cards.enumerate().flat_map(move |(position, card)| (0..bits_per_card).map(move |bit| BitInfo {
+        direction,
+        #[expect(clippy::cast_possible_truncation, reason = "There's at most 64 cards")]
+        index: position as u16 * bits_per_card + bit,
+        card: Some(card),
+        byte: bit / 8,
+        bit: (bit % 8) as u8
+    }))
 }
 
 
 /// The errors which can occur when decoding/creating a node's initialization data within a packet.
-#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum InvalidConfigurationError {
@@ -63,3 +157,72 @@ pub enum InvalidConfigurationError {
         source: crate::node_configuration::CpConfigurationError,
     },
 }
+
+/// Check data is expected bytes long, for `NodeConfiguration::validate_input_data` and `validate_output_data`.
+///
+/// # Errors
+///
+/// [`crate::packet::Error::DataLengthMismatch`] if it isn't.
+const fn check_data_len(expected: u16, data: &crate::packet::Data) -> Result<(), crate::packet::Error> {
+    let actual = data.len();
+    if actual == expected as usize {
+        Ok(())
+    } else {
+        Err(crate::packet::Error::DataLengthMismatch { expected, actual })
+    }
+}
+
+
+#[allow(clippy::missing_panics_doc, reason = "tests")]
+#[cfg(test)]
+mod tests {
+    use crate::{NodeSort, packet::{Data, Error}};
+    use super::*;
+
+    mod validate_data {
+        use super::*;
+
+        fn configuration() -> CpnodeConfiguration {
+            // 3 input bytes and 5 output bytes
+            CpnodeConfiguration::try_new(0, CpnodeOptions::default(), 3, 5).unwrap()
+        }
+
+        #[test]
+        fn input_matches() {
+            assert_eq!(configuration().validate_input_data(&Data::new(3)), Ok(()));
+        }
+
+        #[test]
+        fn input_too_short() {
+            assert_eq!(configuration().validate_input_data(&Data::new(2)), Err(Error::DataLengthMismatch { expected: 3, actual: 2 }));
+            assert_eq!(configuration().validate_input_data(&Data::new(0)), Err(Error::DataLengthMismatch { expected: 3, actual: 0 }));
+        }
+
+        #[test]
+        fn input_too_long() {
+            assert_eq!(configuration().validate_input_data(&Data::new(4)), Err(Error::DataLengthMismatch { expected: 3, actual: 4 }));
+        }
+
+        #[test]
+        fn output_matches() {
+            assert_eq!(configuration().validate_output_data(&Data::new(5)), Ok(()));
+        }
+
+        #[test]
+        fn output_too_short() {
+            assert_eq!(configuration().validate_output_data(&Data::new(4)), Err(Error::DataLengthMismatch { expected: 5, actual: 4 }));
+        }
+
+        #[test]
+        fn output_too_long() {
+            assert_eq!(configuration().validate_output_data(&Data::new(256)), Err(Error::DataLengthMismatch { expected: 5, actual: 256 }));
+        }
+
+        #[test]
+        fn dyn_configuration() {
+            let node_sort = NodeSort::try_new_smini(0, [0; 6]).unwrap();
+            assert_eq!(node_sort.configuration().validate_input_data(&Data::new(3)), Ok(()));
+            assert_eq!(node_sort.configuration().validate_output_data(&Data::new(3)), Err(Error::DataLengthMismatch { expected: 6, actual: 3 }));
+        }
+    }
+}
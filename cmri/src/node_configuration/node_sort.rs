@@ -130,12 +130,198 @@ impl NodeSort {
         }
     }
 
+    /// Call f with the configuration for the `NodeSort`, without needing to match each variant.
+    ///
+    /// Returns `None` (without calling f) for the Unknown variant, which has no configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::NodeSort;
+    /// let node_sort = NodeSort::try_new_smini(0, [0; 6]).unwrap();
+    /// assert_eq!(node_sort.map_configuration(|c| c.input_bits()), Some(24));
+    /// ```
+    pub fn map_configuration<R>(&self, mut f: impl FnMut(&dyn NodeConfiguration) -> R) -> Option<R> {
+        match self {
+            Self::Cpnode { configuration } => Some(f(configuration)),
+            Self::Cpmega { configuration } => Some(f(configuration)),
+            Self::Smini  { configuration } => Some(f(configuration)),
+            Self::Usic   { configuration } => Some(f(configuration)),
+            Self::Susic  { configuration } => Some(f(configuration)),
+            #[cfg(feature = "experimenter")]
+            Self::Unknown { .. } => None
+        }
+    }
+
+    /// Check whether data (from a receive data packet) is the right length for this node,
+    /// catching mis-addressed or mis-sized replies.
+    ///
+    /// The length must match the number of input bytes, except that a CPNODE/CPMEGA with
+    /// the `CAN_SEND_EOT_ON_NO_INPUTS_CHANGED` option may also send no data.
+    /// The length of data for an Unknown node can't be checked so is always accepted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{NodeSort, packet::Data};
+    /// let node_sort = NodeSort::try_new_smini(0, [0; 6]).unwrap();
+    /// assert!(node_sort.accepts_receive_data(&Data::new(3)));
+    /// assert!(!node_sort.accepts_receive_data(&Data::new(2)));
+    /// ```
+    #[must_use]
+    pub fn accepts_receive_data(&self, data: &PacketData) -> bool {
+        (self.can_send_eot_on_no_change() && data.is_empty()) ||
+            self.map_configuration(|configuration| configuration.validate_input_data(data).is_ok()).unwrap_or(true)
+    }
+
+    /// Whether the node may reply to a poll request with no data when its inputs haven't changed,
+    /// I.E. a CPNODE/CPMEGA with the `CAN_SEND_EOT_ON_NO_INPUTS_CHANGED` option.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{NodeSort, node_configuration::CpnodeOptions};
+    /// let node_sort = NodeSort::try_new_cpnode(0, CpnodeOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED, 2, 2).unwrap();
+    /// assert!(node_sort.can_send_eot_on_no_change());
+    /// ```
+    #[must_use]
+    pub const fn can_send_eot_on_no_change(&self) -> bool {
+        match self {
+            Self::Cpnode { configuration } => configuration.options().contains(CpnodeOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED),
+            Self::Cpmega { configuration } => configuration.options().contains(CpmegaOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED),
+            _ => false
+        }
+    }
+
+    /// Encode into the bytes which make up the body of an initialization packet,
+    /// E.G. for storing the node's configuration without needing a whole `Packet`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::NodeSort;
+    /// let node_sort = NodeSort::try_new_smini(0, [0; 6]).unwrap();
+    /// let bytes = node_sort.to_init_bytes();
+    /// assert_eq!(bytes, [b'M', 0, 0, 0]);
+    /// assert_eq!(NodeSort::from_init_bytes(&bytes), Ok(node_sort));
+    /// ```
+    #[must_use]
+    pub fn to_init_bytes(&self) -> PacketData {
+        self.encode()
+    }
+
+    /// Decode from the bytes which make up the body of an initialization packet,
+    /// as produced by [`Self::to_init_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::try_decode`].
+    pub fn from_init_bytes(bytes: &[u8]) -> Result<Self, PacketError> {
+        Self::try_decode(bytes)
+    }
+
+    /// The Node Definition Parameter (NDP) character which identifies the type of node
+    /// in an initialization packet, E.G. 'M' for a SMINI.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::NodeSort;
+    /// let node_sort = NodeSort::try_new_smini(0, [0; 6]).unwrap();
+    /// assert_eq!(node_sort.node_type_char(), 'M');
+    /// ```
+    #[must_use]
+    pub fn node_type_char(&self) -> char {
+        let ndp = match self {
+            Self::Usic { .. } => super::NDP_USIC,
+            Self::Susic { .. } => super::NDP_SUSIC,
+            Self::Smini { .. } => super::NDP_SMINI,
+            Self::Cpnode { .. } => super::NDP_CPNODE,
+            Self::Cpmega { .. } => super::NDP_CPMEGA,
+            #[cfg(feature = "experimenter")]
+            Self::Unknown { body } => body[0]
+        };
+        char::from(ndp)
+    }
+
+    /// Find which parts of the configuration differ between this and another `NodeSort`.
+    ///
+    /// If the node types differ then only [`NodeSortChanges::NODE_TYPE`] is set,
+    /// as the rest of the configurations can't be compared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{NodeSort, node_configuration::{NodeSortChanges, CpnodeOptions}};
+    /// let old = NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 1, 1).unwrap();
+    /// let new = NodeSort::try_new_cpnode(10, CpnodeOptions::default(), 1, 2).unwrap();
+    /// assert_eq!(old.diff(&new), NodeSortChanges::TRANSMIT_DELAY | NodeSortChanges::OUTPUT_BYTES);
+    /// assert!(old.diff(&old).is_empty());
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> NodeSortChanges {
+        let (old, new) = (self.encode(), other.encode());
+        if old[0] != new[0] { return NodeSortChanges::NODE_TYPE }
+
+        let mut changes = NodeSortChanges::empty();
+        match (self, other) {
+            (Self::Usic { configuration: old }, Self::Usic { configuration: new }) => {
+                changes.set(NodeSortChanges::CARDS, old.cards() != new.cards());
+            },
+            (Self::Susic { configuration: old }, Self::Susic { configuration: new }) => {
+                changes.set(NodeSortChanges::CARDS, old.cards() != new.cards());
+            },
+            (Self::Smini { configuration: old }, Self::Smini { configuration: new }) => {
+                changes.set(NodeSortChanges::OSCILLATING_PAIRS, old.oscillating_pairs() != new.oscillating_pairs());
+            },
+            (Self::Cpnode { configuration: old }, Self::Cpnode { configuration: new }) => {
+                changes.set(NodeSortChanges::OPTIONS, old.options() != new.options());
+            },
+            (Self::Cpmega { configuration: old }, Self::Cpmega { configuration: new }) => {
+                changes.set(NodeSortChanges::OPTIONS, old.options() != new.options());
+            },
+            #[cfg(feature = "experimenter")]
+            (Self::Unknown { .. }, Self::Unknown { .. }) => {
+                changes.set(NodeSortChanges::BODY, old != new);
+                return changes;
+            },
+            _ => unreachable!("Already checked the node types match")
+        }
+
+        let (old, new) = (self.configuration(), other.configuration());
+        changes.set(NodeSortChanges::TRANSMIT_DELAY, old.transmit_delay() != new.transmit_delay());
+        changes.set(NodeSortChanges::INPUT_BYTES, old.input_bytes() != new.input_bytes());
+        changes.set(NodeSortChanges::OUTPUT_BYTES, old.output_bytes() != new.output_bytes());
+        changes
+    }
+
+    /// Whether this and another `NodeSort` describe the same hardware, I.E. the node type
+    /// and its inputs/outputs (cards, oscillating pairs and input/output bytes) are the same.
+    ///
+    /// The transmit delay and (for CPNODE/CPMEGA) options are ignored as they don't change the hardware,
+    /// so a saved node can be checked against a controller's initialization packet which tunes them.
+    /// Use [`Self::diff`] to find exactly what differs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{NodeSort, node_configuration::node_cards::NodeCard};
+    /// let saved = NodeSort::try_new_usic(0, &[NodeCard::Input, NodeCard::Output]).unwrap();
+    /// assert!(saved.same_hardware(&NodeSort::try_new_usic(500, &[NodeCard::Input, NodeCard::Output]).unwrap()));
+    /// assert!(!saved.same_hardware(&NodeSort::try_new_usic(0, &[NodeCard::Output, NodeCard::Input]).unwrap()));
+    /// ```
+    #[must_use]
+    pub fn same_hardware(&self, other: &Self) -> bool {
+        self.diff(other).difference(NodeSortChanges::TRANSMIT_DELAY | NodeSortChanges::OPTIONS).is_empty()
+    }
+
     /// Decode from an unescaped packet payload
     ///
     /// # Errors
     ///
     ///   * [`PacketError::InvalidNodeType`] if the NDP byte isn't valid.
-    ///   * [`PacketError::TooShort`] if the slice isn't long enough.
+    ///   * [`PacketError::TooShort`] if the slice is empty.
+    ///   * [`PacketError::InitializationTooShort`] if the slice isn't long enough for the node type.
     ///   * [`PacketError::InvalidConfiguration`]
     ///     * For USIC/SUSIC nodes:
     ///       * [`crate::node_configuration::NodeCardsError::InvalidCardType`] if there's an invalid card type in the card types sequence.
@@ -148,7 +334,13 @@ impl NodeSort {
     ///       * [`crate::node_configuration::CpConfigurationError::InvalidInputOutputBitsCount`] if the total number of input and output bits is invalid for a `", stringify!($name), "` (", stringify!($bpc), ").")]
     pub(crate) fn try_decode(raw: &[u8]) -> Result<Self, PacketError> {
         trace!("NodeSort::decode({raw:?})");
-        match raw[0] {
+        let Some(&ndp) = raw.first() else { return Err(PacketError::TooShort) };
+        let min_len = Self::min_init_len(ndp);
+        if raw.len() < min_len {
+            return Err(PacketError::InitializationTooShort { node_type: ndp, expected: min_len, actual: raw.len() });
+        }
+
+        match ndp {
             super::NDP_CPNODE => Ok(Self::Cpnode { configuration: CpnodeConfiguration::decode(raw)? }),
             super::NDP_CPMEGA => Ok(Self::Cpmega { configuration: CpmegaConfiguration::decode(raw)? }),
             super::NDP_SMINI  => Ok(Self::Smini  { configuration: SminiConfiguration::decode(raw)? }),
@@ -156,14 +348,24 @@ impl NodeSort {
             super::NDP_SUSIC  => Ok(Self::Susic  { configuration: SusicConfiguration::decode(raw)? }),
             _ => {
                 #[cfg(feature = "experimenter")]
-                if raw[0].is_ascii_alphabetic() {
+                if ndp.is_ascii_alphabetic() {
                     return Ok(Self::Unknown { body: raw.try_into()? });
                 }
-                Err(PacketError::InvalidNodeType(raw[0]))
+                Err(PacketError::InvalidNodeType(ndp))
             }
         }
     }
 
+    /// The minimum length of an initialization body for a node type,
+    /// so the node's decoder can safely read the fixed position bytes.
+    const fn min_init_len(ndp: u8) -> usize {
+        match ndp {
+            super::NDP_USIC | super::NDP_SUSIC | super::NDP_SMINI => 4, // NDP, DL (2 bytes), NS
+            super::NDP_CPNODE | super::NDP_CPMEGA => 7, // NDP, DL (2 bytes), Options (2 bytes), NI, NO
+            _ => 1 // NDP
+        }
+    }
+
     pub(crate) fn encode(&self) -> PacketData {
         trace!("NodeSort.encode({self:?})");
         match self {
@@ -196,13 +398,135 @@ impl core::fmt::Display for NodeSort {
     }
 }
 
+/// Get the name of the type of node (as used by `NodeSort`'s `Display`) from
+/// just its Node Definition Parameter (NDP) byte, E.G. when the rest of an
+/// initialization packet is malformed.
+///
+/// # Example
+///
+/// ```
+/// use cmri::node_configuration::node_sort_name_from_ndp;
+/// assert_eq!(node_sort_name_from_ndp(b'M'), Some("SMINI"));
+/// assert_eq!(node_sort_name_from_ndp(b'Z'), None);
+/// ```
+#[must_use]
+pub const fn node_sort_name_from_ndp(ndp: u8) -> Option<&'static str> {
+    match ndp {
+        super::NDP_USIC => Some("USIC"),
+        super::NDP_SUSIC => Some("SUSIC"),
+        super::NDP_SMINI => Some("SMINI"),
+        super::NDP_CPNODE => Some("CPNODE"),
+        super::NDP_CPMEGA => Some("CPMEGA"),
+        _ => None
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "serde")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature serde only.**\n\n")]
+/// An alternate serde representation of a [`NodeSort`], for interop with non-Rust tools.
+///
+/// Instead of being keyed by the Rust variant name (E.G. `Usic`), the `NodeSort` is
+/// represented as a struct with a `node_type` field holding the on-wire node type
+/// (E.G. `"N"`) and a `configuration` field holding the node's configuration.
+/// Use it with `#[serde(with = "cmri::node_configuration::by_node_type")]`.
+pub mod by_node_type {
+    use super::{NodeSort, UsicConfiguration, SusicConfiguration, SminiConfiguration, CpnodeConfiguration, CpmegaConfiguration};
+    #[cfg(feature = "experimenter")]
+    use super::PacketData;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(tag = "node_type", content = "configuration")]
+    enum ByNodeType {
+        #[serde(rename = "N")]
+        Usic(UsicConfiguration),
+        #[serde(rename = "X")]
+        Susic(SusicConfiguration),
+        #[serde(rename = "M")]
+        Smini(SminiConfiguration),
+        #[serde(rename = "C")]
+        Cpnode(CpnodeConfiguration),
+        #[serde(rename = "O")]
+        Cpmega(CpmegaConfiguration),
+        #[cfg(feature = "experimenter")]
+        Unknown(PacketData)
+    }
+
+    impl From<NodeSort> for ByNodeType {
+        fn from(value: NodeSort) -> Self {
+            match value {
+                NodeSort::Usic { configuration } => Self::Usic(configuration),
+                NodeSort::Susic { configuration } => Self::Susic(configuration),
+                NodeSort::Smini { configuration } => Self::Smini(configuration),
+                NodeSort::Cpnode { configuration } => Self::Cpnode(configuration),
+                NodeSort::Cpmega { configuration } => Self::Cpmega(configuration),
+                #[cfg(feature = "experimenter")]
+                NodeSort::Unknown { body } => Self::Unknown(body)
+            }
+        }
+    }
+
+    impl From<ByNodeType> for NodeSort {
+        fn from(value: ByNodeType) -> Self {
+            match value {
+                ByNodeType::Usic(configuration) => Self::Usic { configuration },
+                ByNodeType::Susic(configuration) => Self::Susic { configuration },
+                ByNodeType::Smini(configuration) => Self::Smini { configuration },
+                ByNodeType::Cpnode(configuration) => Self::Cpnode { configuration },
+                ByNodeType::Cpmega(configuration) => Self::Cpmega { configuration },
+                #[cfg(feature = "experimenter")]
+                ByNodeType::Unknown(body) => Self::Unknown { body }
+            }
+        }
+    }
+
+    /// Serialize a `NodeSort` keyed by its node type.
+    ///
+    /// # Errors
+    ///
+    /// If the serializer fails.
+    pub fn serialize<S: serde::Serializer>(node_sort: &NodeSort, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&ByNodeType::from(*node_sort), serializer)
+    }
+
+    /// Deserialize a `NodeSort` keyed by its node type.
+    ///
+    /// # Errors
+    ///
+    /// If the deserializer fails, or the node type is unrecognised.
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<NodeSort, D::Error> {
+        <ByNodeType as serde::Deserialize>::deserialize(deserializer).map(NodeSort::from)
+    }
+}
+
+bitflags::bitflags! {
+    /// The parts of a `NodeSort` which differ, see [`NodeSort::diff`].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct NodeSortChanges: u8 {
+        /// The node type is different.
+        const NODE_TYPE = 1;
+        /// The transmit delay is different.
+        const TRANSMIT_DELAY = 2;
+        /// The number of input bytes is different.
+        const INPUT_BYTES = 4;
+        /// The number of output bytes is different.
+        const OUTPUT_BYTES = 8;
+        /// The cards are different (USIC & SUSIC).
+        const CARDS = 16;
+        /// The oscillating pairs are different (SMINI).
+        const OSCILLATING_PAIRS = 32;
+        /// The options are different (CPNODE & CPMEGA).
+        const OPTIONS = 64;
+        /// The body is different (unknown node types, with the experimenter feature).
+        const BODY = 128;
+    }
+}
+
 #[allow(clippy::missing_panics_doc, reason = "tests")]
 #[cfg(test)]
 mod tests {
     use crate::NodeSort;
-    use crate::packet::Error as PacketError;
-    #[cfg(feature = "experimenter")]
-    use crate::packet::Data as PacketData;
+    use crate::packet::{Data as PacketData, Error as PacketError};
     use crate::node_configuration::{*, sic::node_cards::{NodeCards, NodeCard}};
 
     mod creating {
@@ -301,9 +625,154 @@ mod tests {
         }
     }
 
+    mod map_configuration {
+        use super::*;
+
+        #[test]
+        fn usic() {
+            let configuration = UsicConfiguration::try_new(2, &[NodeCard::Input]).unwrap();
+            let node_type = NodeSort::Usic { configuration };
+            assert_eq!(node_type.map_configuration(|c| (c.transmit_delay(), c.input_bytes(), c.output_bytes())), Some((2, 3, 0)));
+        }
+
+        #[test]
+        fn susic() {
+            let configuration = SusicConfiguration::try_new(3, &[NodeCard::Output]).unwrap();
+            let node_type = NodeSort::Susic { configuration };
+            assert_eq!(node_type.map_configuration(|c| (c.transmit_delay(), c.input_bytes(), c.output_bytes())), Some((3, 0, 4)));
+        }
+
+        #[test]
+        fn smini() {
+            let configuration = SminiConfiguration::try_new(4, [0, 0, 0, 0, 0, 0]).unwrap();
+            let node_type = NodeSort::Smini { configuration };
+            assert_eq!(node_type.map_configuration(|c| (c.transmit_delay(), c.input_bytes(), c.output_bytes())), Some((4, 3, 6)));
+        }
+
+        #[test]
+        fn cpnode() {
+            let configuration = CpnodeConfiguration::try_new(5, CpnodeOptions::default(), 6, 7).unwrap();
+            let node_type = NodeSort::Cpnode { configuration };
+            assert_eq!(node_type.map_configuration(|c| (c.transmit_delay(), c.input_bytes(), c.output_bytes())), Some((5, 6, 7)));
+        }
+
+        #[test]
+        fn cpmega() {
+            let configuration = CpmegaConfiguration::try_new(6, CpmegaOptions::default(), 7, 8).unwrap();
+            let node_type = NodeSort::Cpmega { configuration };
+            assert_eq!(node_type.map_configuration(|c| (c.transmit_delay(), c.input_bytes(), c.output_bytes())), Some((6, 7, 8)));
+        }
+
+        #[test]
+        #[cfg(feature = "experimenter")]
+        fn unknown() {
+            let node_type = NodeSort::Unknown { body: PacketData::try_from(b"Z").unwrap() };
+            let mut called = false;
+            assert_eq!(node_type.map_configuration(|_| called = true), None);
+            assert!(!called);
+        }
+    }
+
+    mod can_send_eot_on_no_change {
+        use super::*;
+
+        #[test]
+        fn cpnode() {
+            let options = CpnodeOptions::USE_CMRIX | CpnodeOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED;
+            assert!(NodeSort::try_new_cpnode(0, options, 2, 3).unwrap().can_send_eot_on_no_change());
+            assert!(!NodeSort::try_new_cpnode(0, CpnodeOptions::USE_CMRIX, 2, 3).unwrap().can_send_eot_on_no_change());
+        }
+
+        #[test]
+        fn cpmega() {
+            assert!(NodeSort::try_new_cpmega(0, CpmegaOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED, 2, 3).unwrap().can_send_eot_on_no_change());
+            assert!(!NodeSort::try_new_cpmega(0, CpmegaOptions::default(), 2, 3).unwrap().can_send_eot_on_no_change());
+        }
+
+        #[test]
+        fn smini() {
+            assert!(!NodeSort::try_new_smini(0, [0; 6]).unwrap().can_send_eot_on_no_change());
+        }
+    }
+
+    mod accepts_receive_data {
+        use super::*;
+
+        #[test]
+        fn exact() {
+            let node_sort = NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 2, 3).unwrap();
+            assert!(node_sort.accepts_receive_data(&PacketData::new(2)));
+        }
+
+        #[test]
+        fn wrong_length() {
+            let node_sort = NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 2, 3).unwrap();
+            assert!(!node_sort.accepts_receive_data(&PacketData::new(1)));
+            assert!(!node_sort.accepts_receive_data(&PacketData::new(3)));
+            assert!(!node_sort.accepts_receive_data(&PacketData::new(0)));
+
+            let node_sort = NodeSort::try_new_usic(0, &[NodeCard::Input, NodeCard::Output]).unwrap();
+            assert!(node_sort.accepts_receive_data(&PacketData::new(3)));
+            assert!(!node_sort.accepts_receive_data(&PacketData::new(6)));
+        }
+
+        #[test]
+        fn empty_with_eot_option() {
+            let node_sort = NodeSort::try_new_cpnode(0, CpnodeOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED, 2, 3).unwrap();
+            assert!(node_sort.accepts_receive_data(&PacketData::new(0)));
+            assert!(node_sort.accepts_receive_data(&PacketData::new(2)));
+            assert!(!node_sort.accepts_receive_data(&PacketData::new(1)));
+
+            let node_sort = NodeSort::try_new_cpmega(0, CpmegaOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED, 2, 3).unwrap();
+            assert!(node_sort.accepts_receive_data(&PacketData::new(0)));
+        }
+
+        #[test]
+        #[cfg(feature = "experimenter")]
+        fn unknown() {
+            let node_sort = NodeSort::Unknown { body: PacketData::try_from(b"Z").unwrap() };
+            assert!(node_sort.accepts_receive_data(&PacketData::new(0)));
+            assert!(node_sort.accepts_receive_data(&PacketData::new(5)));
+        }
+    }
+
     mod try_decode {
         use super::*;
 
+        mod too_short {
+            use super::*;
+
+            #[test]
+            fn empty() {
+                assert_eq!(NodeSort::try_decode(&[]), Err(PacketError::TooShort));
+            }
+
+            #[test]
+            fn usic() {
+                assert_eq!(NodeSort::try_decode(&[b'N', 0, 0]), Err(PacketError::InitializationTooShort { node_type: b'N', expected: 4, actual: 3 }));
+            }
+
+            #[test]
+            fn susic() {
+                assert_eq!(NodeSort::try_decode(&[b'X', 0, 0]), Err(PacketError::InitializationTooShort { node_type: b'X', expected: 4, actual: 3 }));
+            }
+
+            #[test]
+            fn smini() {
+                assert_eq!(NodeSort::try_decode(&[b'M', 0, 0]), Err(PacketError::InitializationTooShort { node_type: b'M', expected: 4, actual: 3 }));
+            }
+
+            #[test]
+            fn cpnode() {
+                assert_eq!(NodeSort::try_decode(&[b'C', 0, 0, 0, 0, 1]), Err(PacketError::InitializationTooShort { node_type: b'C', expected: 7, actual: 6 }));
+            }
+
+            #[test]
+            fn cpmega() {
+                assert_eq!(NodeSort::try_decode(&[b'O', 0, 0, 0, 0, 2]), Err(PacketError::InitializationTooShort { node_type: b'O', expected: 7, actual: 6 }));
+            }
+        }
+
         #[test]
         fn usic() {
             assert_eq!(
@@ -454,6 +923,52 @@ mod tests {
         }
     }
 
+    mod init_bytes {
+        use super::*;
+
+        fn round_trip(node_sort: &NodeSort) {
+            let bytes = node_sort.to_init_bytes();
+            assert_eq!(bytes, node_sort.encode());
+            assert_eq!(NodeSort::from_init_bytes(&bytes).as_ref(), Ok(node_sort));
+        }
+
+        #[test]
+        fn usic() {
+            round_trip(&NodeSort::try_new_usic(10, &[NodeCard::Input, NodeCard::Output, NodeCard::Output]).unwrap());
+        }
+
+        #[test]
+        fn susic() {
+            round_trip(&NodeSort::try_new_susic(20, &[NodeCard::Output, NodeCard::Input]).unwrap());
+        }
+
+        #[test]
+        fn smini() {
+            round_trip(&NodeSort::try_new_smini(30, [0b0000_0011, 0, 0, 0, 0, 0b1100_0000]).unwrap());
+        }
+
+        #[test]
+        fn cpnode() {
+            round_trip(&NodeSort::try_new_cpnode(40, CpnodeOptions::from_bits_retain(1), 2, 3).unwrap());
+        }
+
+        #[test]
+        fn cpmega() {
+            round_trip(&NodeSort::try_new_cpmega(50, CpmegaOptions::from_bits_retain(1), 8, 16).unwrap());
+        }
+
+        #[cfg(feature = "experimenter")]
+        #[test]
+        fn unknown() {
+            round_trip(&NodeSort::try_new_unknown(&[b'A', 1, 2, 3]).unwrap());
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(NodeSort::from_init_bytes(&[]), Err(PacketError::TooShort));
+        }
+    }
+
     #[cfg(feature = "std")]
     mod display {
         use super::*;
@@ -520,6 +1035,50 @@ mod tests {
         }
     }
 
+    mod node_type_char {
+        use super::*;
+
+        #[test]
+        fn known() {
+            assert_eq!(NodeSort::try_new_usic(0, &[]).unwrap().node_type_char(), 'N');
+            assert_eq!(NodeSort::try_new_susic(0, &[]).unwrap().node_type_char(), 'X');
+            assert_eq!(NodeSort::try_new_smini(0, [0; 6]).unwrap().node_type_char(), 'M');
+            assert_eq!(NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 1, 1).unwrap().node_type_char(), 'C');
+            assert_eq!(NodeSort::try_new_cpmega(0, CpmegaOptions::default(), 1, 1).unwrap().node_type_char(), 'O');
+        }
+
+        #[test]
+        #[cfg(feature = "experimenter")]
+        fn unknown() {
+            let node_sort = NodeSort::Unknown { body: PacketData::try_from(b"Z").unwrap() };
+            assert_eq!(node_sort.node_type_char(), 'Z');
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn matches_name_from_ndp() {
+            let node_sorts = [
+                NodeSort::try_new_usic(0, &[]).unwrap(),
+                NodeSort::try_new_susic(0, &[]).unwrap(),
+                NodeSort::try_new_smini(0, [0; 6]).unwrap(),
+                NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 1, 1).unwrap(),
+                NodeSort::try_new_cpmega(0, CpmegaOptions::default(), 1, 1).unwrap()
+            ];
+            for node_sort in node_sorts {
+                let ndp = u8::try_from(node_sort.node_type_char()).unwrap();
+                assert_eq!(ndp, node_sort.to_init_bytes()[0]);
+                assert_eq!(node_sort_name_from_ndp(ndp), Some(format!("{node_sort}").as_str()));
+            }
+        }
+    }
+
+    #[test]
+    fn node_sort_name_from_ndp_unknown() {
+        assert_eq!(node_sort_name_from_ndp(b'Z'), None);
+        assert_eq!(node_sort_name_from_ndp(0), None);
+        assert_eq!(node_sort_name_from_ndp(b'm'), None);
+    }
+
     #[cfg(feature = "serde")]
     mod serde {
         use super::*;
@@ -645,4 +1204,292 @@ mod tests {
             );
         }
     }
+
+    #[cfg(feature = "serde")]
+    mod by_node_type {
+        use super::*;
+        use serde_test::{assert_tokens, assert_de_tokens_error, Token};
+
+        #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+        struct Wrapper(#[serde(with = "crate::node_configuration::by_node_type")] NodeSort);
+
+        /// Check `node_sort` round trips via the node type and configuration tokens.
+        fn round_trip(node_sort: &NodeSort, node_type: &'static str, configuration: &[Token]) {
+            let mut tokens = [Token::Unit; 32];
+            let len = configuration.len() + 6;
+            tokens[0] = Token::NewtypeStruct { name: "Wrapper" };
+            tokens[1] = Token::Struct { name: "ByNodeType", len: 2 };
+            tokens[2] = Token::Str("node_type");
+            tokens[3] = Token::UnitVariant { name: "ByNodeType", variant: node_type };
+            tokens[4] = Token::Str("configuration");
+            tokens[5..(len - 1)].copy_from_slice(configuration);
+            tokens[len - 1] = Token::StructEnd;
+            assert_tokens(&Wrapper(*node_sort), &tokens[..len]);
+        }
+
+        #[test]
+        fn usic() {
+            let configuration = UsicConfiguration::try_new(0, &[]).unwrap();
+            round_trip(
+                &NodeSort::Usic { configuration },
+                "N",
+                &[
+                    Token::Struct { name: "UsicConfiguration", len: 4 },
+                        Token::BorrowedStr("transmit_delay"),
+                        Token::U16(0),
+                        Token::BorrowedStr("cards"),
+                        Token::Seq { len: None },
+                        Token::SeqEnd,
+                        Token::BorrowedStr("input_bytes"),
+                        Token::U16(0),
+                        Token::BorrowedStr("output_bytes"),
+                        Token::U16(0),
+                    Token::StructEnd
+                ]
+            );
+        }
+
+        #[test]
+        fn susic() {
+            let configuration = SusicConfiguration::try_new(0, &[]).unwrap();
+            round_trip(
+                &NodeSort::Susic { configuration },
+                "X",
+                &[
+                    Token::Struct { name: "SusicConfiguration", len: 4 },
+                        Token::BorrowedStr("transmit_delay"),
+                        Token::U16(0),
+                        Token::BorrowedStr("cards"),
+                        Token::Seq { len: None },
+                        Token::SeqEnd,
+                        Token::BorrowedStr("input_bytes"),
+                        Token::U16(0),
+                        Token::BorrowedStr("output_bytes"),
+                        Token::U16(0),
+                    Token::StructEnd
+                ]
+            );
+        }
+
+        #[test]
+        fn smini() {
+            let configuration = SminiConfiguration::try_new(0, [0; 6]).unwrap();
+            round_trip(
+                &NodeSort::Smini { configuration },
+                "M",
+                &[
+                    Token::Struct { name: "SminiConfiguration", len: 3 },
+                        Token::BorrowedStr("transmit_delay"),
+                        Token::U16(0),
+                        Token::BorrowedStr("oscillating_count"),
+                        Token::U8(0),
+                        Token::BorrowedStr("oscillating_pairs"),
+                        Token::BorrowedBytes(&[0; 6]),
+                    Token::StructEnd
+                ]
+            );
+        }
+
+        #[test]
+        fn cpnode() {
+            let configuration = CpnodeConfiguration::try_new(0, CpnodeOptions::default(), 1, 1).unwrap();
+            round_trip(
+                &NodeSort::Cpnode { configuration },
+                "C",
+                &[
+                    Token::Struct { name: "CpnodeConfiguration", len: 4 },
+                        Token::BorrowedStr("transmit_delay"),
+                        Token::U16(0),
+                        Token::BorrowedStr("options"),
+                        Token::U16(0),
+                        Token::BorrowedStr("input_bytes"),
+                        Token::U16(1),
+                        Token::BorrowedStr("output_bytes"),
+                        Token::U16(1),
+                    Token::StructEnd
+                ]
+            );
+        }
+
+        #[test]
+        fn cpmega() {
+            let configuration = CpmegaConfiguration::try_new(0, CpmegaOptions::default(), 0, 0).unwrap();
+            round_trip(
+                &NodeSort::Cpmega { configuration },
+                "O",
+                &[
+                    Token::Struct { name: "CpmegaConfiguration", len: 4 },
+                        Token::BorrowedStr("transmit_delay"),
+                        Token::U16(0),
+                        Token::BorrowedStr("options"),
+                        Token::U16(0),
+                        Token::BorrowedStr("input_bytes"),
+                        Token::U16(0),
+                        Token::BorrowedStr("output_bytes"),
+                        Token::U16(0),
+                    Token::StructEnd
+                ]
+            );
+        }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn json() {
+            let configuration = CpnodeConfiguration::try_new(0, CpnodeOptions::default(), 1, 1).unwrap();
+            let node_sort = Wrapper(NodeSort::Cpnode { configuration });
+            let json = serde_json::to_string(&node_sort).unwrap();
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+                serde_json::json!({
+                    "node_type": "C",
+                    "configuration": { "transmit_delay": 0, "options": 0, "input_bytes": 1, "output_bytes": 1 }
+                })
+            );
+            assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap(), node_sort);
+        }
+
+        #[test]
+        fn unrecognised_node_type() {
+            assert_de_tokens_error::<Wrapper>(
+                &[
+                    Token::NewtypeStruct { name: "Wrapper" },
+                    Token::Struct { name: "ByNodeType", len: 2 },
+                        Token::Str("node_type"),
+                        Token::UnitVariant { name: "ByNodeType", variant: "Z" },
+                ],
+                #[cfg(not(feature = "experimenter"))]
+                "unknown variant `Z`, expected one of `N`, `X`, `M`, `C`, `O`",
+                #[cfg(feature = "experimenter")]
+                "unknown variant `Z`, expected one of `N`, `X`, `M`, `C`, `O`, `Unknown`"
+            );
+        }
+    }
+
+    mod diff {
+        use super::*;
+
+        #[test]
+        fn same() {
+            let node_sort = NodeSort::try_new_smini(10, [0; 6]).unwrap();
+            assert_eq!(node_sort.diff(&node_sort), NodeSortChanges::empty());
+        }
+
+        #[test]
+        fn node_type() {
+            let old = NodeSort::try_new_smini(10, [0; 6]).unwrap();
+            let new = NodeSort::try_new_usic(10, &[NodeCard::Input]).unwrap();
+            assert_eq!(old.diff(&new), NodeSortChanges::NODE_TYPE);
+        }
+
+        #[test]
+        fn usic() {
+            let old = NodeSort::try_new_usic(10, &[NodeCard::Input, NodeCard::Output]).unwrap();
+            let new = NodeSort::try_new_usic(20, &[NodeCard::Input, NodeCard::Output, NodeCard::Output]).unwrap();
+            assert_eq!(old.diff(&new), NodeSortChanges::TRANSMIT_DELAY | NodeSortChanges::OUTPUT_BYTES | NodeSortChanges::CARDS);
+        }
+
+        #[test]
+        fn susic() {
+            let old = NodeSort::try_new_susic(10, &[NodeCard::Input, NodeCard::Output]).unwrap();
+            let new = NodeSort::try_new_susic(10, &[NodeCard::Output, NodeCard::Input]).unwrap();
+            assert_eq!(old.diff(&new), NodeSortChanges::CARDS);
+        }
+
+        #[test]
+        fn smini() {
+            let old = NodeSort::try_new_smini(10, [0; 6]).unwrap();
+            let new = NodeSort::try_new_smini(10, [0b0000_0011, 0, 0, 0, 0, 0]).unwrap();
+            assert_eq!(old.diff(&new), NodeSortChanges::OSCILLATING_PAIRS);
+        }
+
+        #[test]
+        fn cpnode() {
+            let old = NodeSort::try_new_cpnode(10, CpnodeOptions::default(), 1, 2).unwrap();
+            let new = NodeSort::try_new_cpnode(10, CpnodeOptions::USE_BCC, 2, 2).unwrap();
+            assert_eq!(old.diff(&new), NodeSortChanges::OPTIONS | NodeSortChanges::INPUT_BYTES);
+        }
+
+        #[test]
+        fn cpmega() {
+            let old = NodeSort::try_new_cpmega(10, CpmegaOptions::default(), 1, 2).unwrap();
+            let new = NodeSort::try_new_cpmega(0, CpmegaOptions::default(), 1, 2).unwrap();
+            assert_eq!(old.diff(&new), NodeSortChanges::TRANSMIT_DELAY);
+        }
+
+        #[cfg(feature = "experimenter")]
+        #[test]
+        fn unknown() {
+            let old = NodeSort::try_new_unknown(&[b'A', 1]).unwrap();
+            assert_eq!(old.diff(&NodeSort::try_new_unknown(&[b'A', 2]).unwrap()), NodeSortChanges::BODY);
+            assert_eq!(old.diff(&NodeSort::try_new_unknown(&[b'B', 1]).unwrap()), NodeSortChanges::NODE_TYPE);
+        }
+    }
+
+    mod same_hardware {
+        use super::*;
+
+        #[test]
+        fn only_transmit_delay_differs() {
+            let pairs = [
+                (NodeSort::try_new_usic(0, &[NodeCard::Input, NodeCard::Output]).unwrap(), NodeSort::try_new_usic(10, &[NodeCard::Input, NodeCard::Output]).unwrap()),
+                (NodeSort::try_new_susic(0, &[NodeCard::Output]).unwrap(), NodeSort::try_new_susic(u16::MAX, &[NodeCard::Output]).unwrap()),
+                (NodeSort::try_new_smini(0, [0b0000_0011, 0, 0, 0, 0, 0]).unwrap(), NodeSort::try_new_smini(20, [0b0000_0011, 0, 0, 0, 0, 0]).unwrap()),
+                (NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 1, 2).unwrap(), NodeSort::try_new_cpnode(30, CpnodeOptions::default(), 1, 2).unwrap()),
+                (NodeSort::try_new_cpmega(0, CpmegaOptions::default(), 3, 4).unwrap(), NodeSort::try_new_cpmega(40, CpmegaOptions::default(), 3, 4).unwrap())
+            ];
+            for (a, b) in pairs {
+                assert!(a.same_hardware(&b), "{a:?} {b:?}");
+                assert!(b.same_hardware(&a), "{b:?} {a:?}");
+                assert_ne!(a, b);
+            }
+        }
+
+        #[test]
+        fn options_differ() {
+            let a = NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 1, 2).unwrap();
+            let b = NodeSort::try_new_cpnode(10, CpnodeOptions::USE_BCC, 1, 2).unwrap();
+            assert!(a.same_hardware(&b));
+        }
+
+        #[test]
+        fn card_layout_differs() {
+            let a = NodeSort::try_new_usic(0, &[NodeCard::Input, NodeCard::Output]).unwrap();
+            assert!(!a.same_hardware(&NodeSort::try_new_usic(0, &[NodeCard::Output, NodeCard::Input]).unwrap()));
+            assert!(!a.same_hardware(&NodeSort::try_new_usic(0, &[NodeCard::Input, NodeCard::Output, NodeCard::Output]).unwrap()));
+
+            let a = NodeSort::try_new_susic(0, &[NodeCard::Input, NodeCard::Output]).unwrap();
+            assert!(!a.same_hardware(&NodeSort::try_new_susic(0, &[NodeCard::Output, NodeCard::Input]).unwrap()));
+        }
+
+        #[test]
+        fn oscillating_pairs_differ() {
+            let a = NodeSort::try_new_smini(0, [0; 6]).unwrap();
+            assert!(!a.same_hardware(&NodeSort::try_new_smini(0, [0b0000_0011, 0, 0, 0, 0, 0]).unwrap()));
+        }
+
+        #[test]
+        fn input_output_bytes_differ() {
+            let a = NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 1, 2).unwrap();
+            assert!(!a.same_hardware(&NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 2, 2).unwrap()));
+            assert!(!a.same_hardware(&NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 1, 1).unwrap()));
+
+            let a = NodeSort::try_new_cpmega(0, CpmegaOptions::default(), 1, 2).unwrap();
+            assert!(!a.same_hardware(&NodeSort::try_new_cpmega(0, CpmegaOptions::default(), 2, 1).unwrap()));
+        }
+
+        #[test]
+        fn node_type_differs() {
+            let a = NodeSort::try_new_usic(0, &[NodeCard::Input]).unwrap();
+            assert!(!a.same_hardware(&NodeSort::try_new_susic(0, &[NodeCard::Input]).unwrap()));
+            assert!(!a.same_hardware(&NodeSort::try_new_smini(0, [0; 6]).unwrap()));
+        }
+
+        #[cfg(feature = "experimenter")]
+        #[test]
+        fn unknown() {
+            let a = NodeSort::try_new_unknown(&[b'A', 1]).unwrap();
+            assert!(a.same_hardware(&a));
+            assert!(!a.same_hardware(&NodeSort::try_new_unknown(&[b'A', 2]).unwrap()));
+        }
+    }
 }
@@ -19,6 +19,8 @@ macro_rules! common_implementation {
         mod $name {
             use log::trace;
             use crate::node_configuration::NodeConfiguration;
+            #[cfg(feature = "std")]
+            use crate::node_configuration::{BitInfo, Direction, card_bit_map};
             use crate::packet::{Data as PacketData, Error as PacketError};
             use super::{NodeCards, NodeCard, NodeCardsError};
             #[allow(unused_imports)]
@@ -56,6 +58,7 @@ macro_rules! common_implementation {
                 #[doc = ""]
                 #[doc = "# Errors"]
                 #[doc = ""]
+                #[doc = "* [`PacketError::TooShort`] if the slice is shorter than the 4 bytes needed."]
                 #[doc = concat!("* [`PacketError::InvalidNodeType`] if the NDP byte isn't valid for a ", stringify!($name), " node.")]
                 #[doc = "* [`PacketError::InvalidConfiguration`]:"]
                 #[doc = "  * [`NodeCardsError::InvalidCardType`] if there's an invalid card type in the card types sequence."]
@@ -63,6 +66,9 @@ macro_rules! common_implementation {
                 #[doc = "  * [`NodeCardsError::TooManyCards`] if there's more than 64 input/output cards."]
                 pub(in super::super) fn decode(raw: &[u8]) -> Result<Self, PacketError> {
                     trace!(concat!(stringify!($name), "::decode({:?})"), raw);
+                    if raw.len() < 4 {
+                        return Err(PacketError::TooShort)
+                    }
                     if raw[0] != $ndp {
                         return Err(PacketError::InvalidNodeType(raw[0]))
                     }
@@ -118,6 +124,20 @@ macro_rules! common_implementation {
                 fn transmit_delay(&self) -> u16 { self.transmit_delay }
                 fn input_bytes(&self) -> u16 { u16::from(self.cards.input_cards()) * u16::from(Self::BPC) }
                 fn output_bytes(&self) -> u16 { u16::from(self.cards.output_cards()) * u16::from(Self::BPC) }
+
+                /// Cards are numbered by their position on the node.
+                #[cfg(feature = "std")]
+                #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "std")))]
+                #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature std only.**\n\n")]
+                fn bit_map(&self) -> Vec<BitInfo> {
+                    let of_type = |card_type| self.cards.as_slice().iter()
+                        .zip(0..)
+                        .filter(move |(card, _)| **card == card_type)
+                        .map(|(_, position)| position);
+                    card_bit_map(Direction::Input, of_type(NodeCard::Input), Self::BPC)
+                        .chain(card_bit_map(Direction::Output, of_type(NodeCard::Output), Self::BPC))
+                        .collect()
+                }
             }
 
             #[cfg(feature = "serde")]
@@ -305,6 +325,14 @@ mod tests {
                     Err(PacketError::InvalidNodeType(90))
                 );
             }
+
+            #[test]
+            fn too_short() {
+                assert_eq!(UsicConfiguration::decode(&[]), Err(PacketError::TooShort));
+                assert_eq!(UsicConfiguration::decode(b"N"), Err(PacketError::TooShort));
+                assert_eq!(UsicConfiguration::decode(&[b'N', 0]), Err(PacketError::TooShort));
+                assert_eq!(UsicConfiguration::decode(&[b'N', 0, 0]), Err(PacketError::TooShort));
+            }
         }
 
         mod encode {
@@ -360,6 +388,23 @@ mod tests {
             assert_eq!(configuration.cards(), cards.as_slice());
         }
 
+        #[cfg(feature = "std")]
+        #[test]
+        fn bit_map() {
+            use crate::node_configuration::{BitInfo, Direction};
+            let configuration = UsicConfiguration::try_new(0, &[NodeCard::Output, NodeCard::Input, NodeCard::Output, NodeCard::Input]).unwrap();
+            let bit_map = configuration.bit_map();
+            assert_eq!(bit_map.len(), 96);
+            assert_eq!(bit_map[0], BitInfo { direction: Direction::Input, index: 0, card: Some(1), byte: 0, bit: 0 });
+            assert_eq!(bit_map[23], BitInfo { direction: Direction::Input, index: 23, card: Some(1), byte: 2, bit: 7 });
+            assert_eq!(bit_map[24], BitInfo { direction: Direction::Input, index: 24, card: Some(3), byte: 0, bit: 0 });
+            assert_eq!(bit_map[47], BitInfo { direction: Direction::Input, index: 47, card: Some(3), byte: 2, bit: 7 });
+            assert_eq!(bit_map[48], BitInfo { direction: Direction::Output, index: 0, card: Some(0), byte: 0, bit: 0 });
+            assert_eq!(bit_map[58], BitInfo { direction: Direction::Output, index: 10, card: Some(0), byte: 1, bit: 2 });
+            assert_eq!(bit_map[72], BitInfo { direction: Direction::Output, index: 24, card: Some(2), byte: 0, bit: 0 });
+            assert_eq!(bit_map[95], BitInfo { direction: Direction::Output, index: 47, card: Some(2), byte: 2, bit: 7 });
+        }
+
         #[cfg(feature = "serde")]
         mod serde {
             use super::*;
@@ -656,6 +701,14 @@ mod tests {
                     Err(PacketError::InvalidNodeType(90))
                 );
             }
+
+            #[test]
+            fn too_short() {
+                assert_eq!(SusicConfiguration::decode(&[]), Err(PacketError::TooShort));
+                assert_eq!(SusicConfiguration::decode(b"X"), Err(PacketError::TooShort));
+                assert_eq!(SusicConfiguration::decode(&[b'X', 0]), Err(PacketError::TooShort));
+                assert_eq!(SusicConfiguration::decode(&[b'X', 0, 0]), Err(PacketError::TooShort));
+            }
         }
 
         mod encode {
@@ -1,7 +1,7 @@
 //! Work with the I/O cards (both individual and as a collection) in a USIC/SUSIC.
 
 /// Errors which can happen when decoding/creating a `CpnodeConfiguration` or `CpmegaConfiguration`.
-#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error {
     /// Too many cards (must be 64 or less) for a classic USIC or a SUSIC.
@@ -89,6 +89,33 @@ impl NodeCards {
         Ok(())
     }
 
+    /// Replace the cards starting at index start with cards, the collection
+    /// is left unchanged if the result wouldn't be a valid `NodeCards`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::CardAfterNone`] if there'd be an Input or Output card after a None card.
+    /// * [`Error::TooManyCards`] if the cards would go past the 64th.
+    ///
+    /// # Example
+    /// ```
+    /// use cmri::node_configuration::node_cards::{NodeCards, NodeCard};
+    /// let mut cards = NodeCards::try_new(&[NodeCard::Input, NodeCard::Input, NodeCard::Input]).unwrap();
+    /// cards.overlay(1, &[NodeCard::Output]).unwrap();
+    /// assert_eq!(cards.as_slice(), &[NodeCard::Input, NodeCard::Output, NodeCard::Input]);
+    /// ```
+    pub fn overlay(&mut self, start: usize, cards: &[NodeCard]) -> Result<(), Error> {
+        let end = start.checked_add(cards.len()).ok_or(Error::TooManyCards)?;
+        if end > self.cards.len() {
+            return Err(Error::TooManyCards)
+        }
+
+        let mut overlaid = self.cards;
+        overlaid[start..end].copy_from_slice(cards);
+        *self = Self::try_new(&overlaid)?;
+        Ok(())
+    }
+
     /// The number of `NodeCard`s currently stored.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -101,6 +128,25 @@ impl NodeCards {
         self.input_cards == 0 && self.output_cards == 0
     }
 
+    /// The number of `NodeCard`s which can still be added.
+    ///
+    /// # Example
+    /// ```
+    /// use cmri::node_configuration::node_cards::{NodeCards, NodeCard};
+    /// let cards = NodeCards::try_new(&[NodeCard::Input, NodeCard::Output]).unwrap();
+    /// assert_eq!(cards.remaining_capacity(), 62);
+    /// ```
+    #[must_use]
+    pub fn remaining_capacity(&self) -> usize {
+        self.cards.len() - self.len()
+    }
+
+    /// Check whether the `NodeCards` is full (no more cards can be added).
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.remaining_capacity() == 0
+    }
+
     /// Extracts a slice of the cards.
     #[must_use]
     pub fn as_slice(&self) -> &[NodeCard] {
@@ -353,6 +399,56 @@ mod tests {
         }
     }
 
+    mod overlay {
+        use super::*;
+
+        #[test]
+        fn middle() {
+            let mut node_cards = NodeCards::try_new(&[NodeCard::Input; 6]).unwrap();
+            node_cards.overlay(2, &[NodeCard::Output, NodeCard::Output]).unwrap();
+            assert_eq!(
+                node_cards.as_slice(),
+                &[NodeCard::Input, NodeCard::Input, NodeCard::Output, NodeCard::Output, NodeCard::Input, NodeCard::Input]
+            );
+            assert_eq!(node_cards.input_cards(), 4);
+            assert_eq!(node_cards.output_cards(), 2);
+        }
+
+        #[test]
+        fn extends() {
+            let mut node_cards = NodeCards::try_new(&[NodeCard::Input, NodeCard::Input]).unwrap();
+            node_cards.overlay(1, &[NodeCard::Output, NodeCard::Output]).unwrap();
+            assert_eq!(node_cards.as_slice(), &[NodeCard::Input, NodeCard::Output, NodeCard::Output]);
+        }
+
+        #[test]
+        fn truncates() {
+            let mut node_cards = NodeCards::try_new(&[NodeCard::Input; 4]).unwrap();
+            node_cards.overlay(2, &[NodeCard::None, NodeCard::None]).unwrap();
+            assert_eq!(node_cards.as_slice(), &[NodeCard::Input, NodeCard::Input]);
+        }
+
+        #[test]
+        fn card_after_none() {
+            let original = NodeCards::try_new(&[NodeCard::Input; 4]).unwrap();
+            let mut node_cards = original;
+            assert_eq!(node_cards.overlay(1, &[NodeCard::None]), Err(Error::CardAfterNone));
+            assert_eq!(node_cards.overlay(5, &[NodeCard::Output]), Err(Error::CardAfterNone));
+            assert_eq!(node_cards, original, "Should be unchanged");
+        }
+
+        #[test]
+        fn too_many_cards() {
+            let original = NodeCards::try_new(&[NodeCard::Input; 62]).unwrap();
+            let mut node_cards = original;
+            assert_eq!(node_cards.overlay(62, &[NodeCard::Output; 3]), Err(Error::TooManyCards));
+            assert_eq!(node_cards.overlay(usize::MAX, &[NodeCard::Output]), Err(Error::TooManyCards));
+            assert_eq!(node_cards, original, "Should be unchanged");
+            node_cards.overlay(62, &[NodeCard::Output; 2]).unwrap();
+            assert!(node_cards.is_full());
+        }
+    }
+
     mod try_push {
         use super::*;
 
@@ -424,6 +520,20 @@ mod tests {
         assert!(!NodeCards::try_new(&[NodeCard::Input]).unwrap().is_empty());
     }
 
+    #[test]
+    fn remaining_capacity(){
+        assert_eq!(NodeCards::try_new(&[]).unwrap().remaining_capacity(), 64);
+        assert_eq!(NodeCards::try_new(&[NodeCard::Input, NodeCard::Output, NodeCard::None]).unwrap().remaining_capacity(), 62);
+        assert_eq!(NodeCards::try_new(&[NodeCard::Output; 64]).unwrap().remaining_capacity(), 0);
+    }
+
+    #[test]
+    fn is_full(){
+        assert!(!NodeCards::try_new(&[]).unwrap().is_full());
+        assert!(!NodeCards::try_new(&[NodeCard::Input; 63]).unwrap().is_full());
+        assert!(NodeCards::try_new(&[NodeCard::Input; 64]).unwrap().is_full());
+    }
+
     #[test]
     fn as_slice(){
         let cards = [NodeCard::Input, NodeCard::Output, NodeCard::Output, NodeCard:: Input, NodeCard::Output];
@@ -5,9 +5,11 @@ use const_for::const_for;
 use super::NDP_SMINI;
 use crate::packet::{Data as PacketData, Error as PacketError};
 use crate::node_configuration::{NodeConfiguration, InvalidConfigurationError};
+#[cfg(feature = "std")]
+use crate::node_configuration::{BitInfo, Direction, card_bit_map};
 
 /// Errors which can happen when decoding/creating an `SminiConfiguration`.
-#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error {
     /// At least one pair of set bits aren't adjacent.
@@ -43,6 +45,42 @@ impl Configuration {
         &self.oscillating_pairs
     }
 
+    /// Get the oscillating pairs for the node as bits, the inverse of `try_from_bits`.
+    ///
+    /// Bit N of `oscillating_pairs()[B]` is at index (B * 8) + N.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::node_configuration::SminiConfiguration;
+    /// let configuration = SminiConfiguration::try_new(0, [0b0000_0110, 0, 0, 0, 0, 0]).unwrap();
+    /// let bits = configuration.oscillating_pairs_bits();
+    /// assert_eq!(bits[..4], [false, true, true, false]);
+    /// ```
+    #[must_use]
+    pub const fn oscillating_pairs_bits(&self) -> [bool; 48] {
+        let mut bits = [false; 48];
+        const_for!(i in 0..48 => {
+            bits[i] = self.oscillating_pairs[i / 8] & (1 << (i % 8)) > 0;
+        });
+        bits
+    }
+
+    /// Create a new `Configuration` from the oscillating pairs as bits (see `oscillating_pairs_bits`).
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NonAdjacent`] if `bits` has a pair of set bits which aren't adjacent.
+    pub const fn try_from_bits(transmit_delay: u16, bits: &[bool; 48]) -> Result<Self, Error> {
+        let mut oscillating_pairs = [0; 6];
+        const_for!(i in 0..48 => {
+            if bits[i] {
+                oscillating_pairs[i / 8] |= 1 << (i % 8);
+            }
+        });
+        Self::try_new(transmit_delay, oscillating_pairs)
+    }
+
     /// Create a new `Configuration`.
     ///
     /// # Errors
@@ -84,18 +122,21 @@ impl Configuration {
     ///
     /// # Errors
     ///
-    /// * [`PacketError::InvalidNodeType`] if the NDP byte isn't valid for an SMINI node.
     /// * [`PacketError::TooShort`] if the slice isn't long enough.
+    /// * [`PacketError::InvalidNodeType`] if the NDP byte isn't valid for an SMINI node.
     /// * [`PacketError::InvalidConfiguration`]:
     ///   * [`Error::NonAdjacent`] if `oscillating_pairs` has a pair of true bits which aren't adjacent.
     #[expect(clippy::missing_panics_doc)]
     pub(super) fn decode(raw: &[u8]) -> Result<Self, PacketError> {
         trace!("SminiConfiguration::decode({raw:?})");
+        if raw.len() < 4 {
+            return Err(PacketError::TooShort);
+        }
         if raw[0] != NDP_SMINI {
             return Err(PacketError::InvalidNodeType(raw[0]))
         }
 
-        if raw.len() < 4 || (raw[3] > 0 && raw.len() < 10) {
+        if raw[3] > 0 && raw.len() < 10 {
             return Err(PacketError::TooShort);
         }
 
@@ -134,6 +175,16 @@ impl NodeConfiguration for Configuration {
     fn transmit_delay(&self) -> u16 { self.transmit_delay }
     fn input_bytes(&self) -> u16 { 3 }
     fn output_bytes(&self) -> u16 { 6 }
+
+    /// Inputs are on card 2, outputs are on cards 0 and 1, each card has 3 ports.
+    #[cfg(feature = "std")]
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "std")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature std only.**\n\n")]
+    fn bit_map(&self) -> Vec<BitInfo> {
+        card_bit_map(Direction::Input, [2].into_iter(), 3)
+            .chain(card_bit_map(Direction::Output, [0, 1].into_iter(), 3))
+            .collect()
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -273,6 +324,42 @@ mod tests {
         }
     }
 
+    mod bits {
+        use super::*;
+
+        #[test]
+        fn all_false() {
+            let configuration = Configuration::try_from_bits(4, &[false; 48]).unwrap();
+            assert_eq!(configuration, Configuration::try_new(4, [0; 6]).unwrap());
+            assert_eq!(configuration.oscillating_pairs_bits(), [false; 48]);
+        }
+
+        #[test]
+        fn adjacent_pair() {
+            let mut bits = [false; 48];
+            bits[11] = true;
+            bits[12] = true;
+            let configuration = Configuration::try_from_bits(4, &bits).unwrap();
+            assert_eq!(configuration, Configuration::try_new(4, [0, 0b0001_1000, 0, 0, 0, 0]).unwrap());
+            assert_eq!(configuration.oscillating_count, 1);
+            assert_eq!(configuration.oscillating_pairs_bits(), bits);
+        }
+
+        #[test]
+        fn round_trip() {
+            let configuration = Configuration::try_new(4, [3, 6, 12, 24, 48, 96]).unwrap();
+            assert_eq!(Configuration::try_from_bits(4, &configuration.oscillating_pairs_bits()), Ok(configuration));
+        }
+
+        #[test]
+        fn not_adjacent() {
+            let mut bits = [false; 48];
+            bits[3] = true;
+            bits[5] = true;
+            assert_eq!(Configuration::try_from_bits(4, &bits), Err(Error::NonAdjacent));
+        }
+    }
+
     mod get_oscillating_pairs_count {
         use super::*;
 
@@ -363,6 +450,14 @@ mod tests {
         mod too_short {
             use super::*;
 
+            #[test]
+            fn truncated() {
+                assert_eq!(Configuration::decode(&[]), Err(PacketError::TooShort));
+                assert_eq!(Configuration::decode(b"M"), Err(PacketError::TooShort));
+                assert_eq!(Configuration::decode(&[b'M', 0]), Err(PacketError::TooShort));
+                assert_eq!(Configuration::decode(&[b'M', 0, 0]), Err(PacketError::TooShort));
+            }
+
             #[test]
             fn without_oscillating_pairs() {
                 assert!(Configuration::decode(&[b'M', 0, 0, 0]).is_ok());
@@ -435,6 +530,20 @@ mod tests {
         assert_eq!(configuration.output_bytes(), 6);
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn bit_map() {
+        use crate::node_configuration::{BitInfo, Direction};
+        let bit_map = Configuration::try_new(0, [0; 6]).unwrap().bit_map();
+        assert_eq!(bit_map.len(), 72);
+        assert_eq!(bit_map[0], BitInfo { direction: Direction::Input, index: 0, card: Some(2), byte: 0, bit: 0 });
+        assert_eq!(bit_map[23], BitInfo { direction: Direction::Input, index: 23, card: Some(2), byte: 2, bit: 7 });
+        assert_eq!(bit_map[24], BitInfo { direction: Direction::Output, index: 0, card: Some(0), byte: 0, bit: 0 });
+        assert_eq!(bit_map[33], BitInfo { direction: Direction::Output, index: 9, card: Some(0), byte: 1, bit: 1 });
+        assert_eq!(bit_map[48], BitInfo { direction: Direction::Output, index: 24, card: Some(1), byte: 0, bit: 0 });
+        assert_eq!(bit_map[71], BitInfo { direction: Direction::Output, index: 47, card: Some(1), byte: 2, bit: 7 });
+    }
+
     #[cfg(feature = "serde")]
     mod serde {
         use super::*;
@@ -0,0 +1,56 @@
+/// Compute the block check character (BCC) for a packet,
+/// the XOR of the message type and every (unescaped) data byte.
+///
+/// Nodes with the `USE_BCC` option (see [`crate::node_configuration::CpnodeOptions`])
+/// expect this after the data, see also [`super::Packet::bcc`].
+///
+/// # Example
+///
+/// ```
+/// use cmri::packet::compute_bcc;
+/// assert_eq!(compute_bcc(b'P', &[]), b'P');
+/// assert_eq!(compute_bcc(b'T', &[0x01, 0x02]), b'T' ^ 0x03);
+/// ```
+#[must_use]
+pub const fn compute_bcc(message_type: u8, data: &[u8]) -> u8 {
+    let mut bcc = message_type;
+    let mut index = 0;
+    while index < data.len() {
+        bcc ^= data[index];
+        index += 1;
+    }
+    bcc
+}
+
+
+#[allow(clippy::missing_panics_doc, reason = "tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_data() {
+        assert_eq!(compute_bcc(b'P', &[]), 0x50);
+        assert_eq!(compute_bcc(b'R', &[]), 0x52);
+    }
+
+    #[test]
+    fn with_data() {
+        assert_eq!(compute_bcc(b'T', &[0x00]), 0x54);
+        assert_eq!(compute_bcc(b'T', &[0xFF]), 0xAB);
+        assert_eq!(compute_bcc(b'R', &[0x01, 0x02, 0x04, 0x08]), 0x5D);
+        assert_eq!(compute_bcc(b'T', &[0x54]), 0x00);
+    }
+
+    #[test]
+    fn framing_bytes() {
+        // The BCC is of the unescaped data
+        assert_eq!(compute_bcc(b'T', &[0x02, 0x03, 0x10]), 0x45);
+    }
+
+    #[test]
+    fn initialization() {
+        // SMINI with no oscillating pairs and a transmit delay of 10
+        assert_eq!(compute_bcc(b'I', &[b'M', 0x00, 0x0A, 0x00]), 0x0E);
+    }
+}
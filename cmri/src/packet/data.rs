@@ -39,6 +39,25 @@ impl Data {
         }
     }
 
+    /// Create from the states of bits, packed lowest bit first (matching [`Self::get_bit`]),
+    /// the length is the number of bytes needed to hold all the bits.
+    ///
+    /// # Errors
+    ///
+    /// [`super::Error::TooLong`] if there's more than 2048 bits (256 bytes).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::from_bits(&[true, false, false, false, false, false, false, false, true]).unwrap();
+    /// assert_eq!(data, [0x01, 0x01]);
+    /// ```
+    pub fn from_bits(bits: &[bool]) -> Result<Self, super::Error> {
+        if bits.len() > Self::MAX_LEN * 8 { return Err(super::Error::TooLong) }
+        Ok(bits.iter().copied().collect())
+    }
+
     /// Tests if a given bit in the data is true or false.
     ///
     /// # Panics
@@ -109,6 +128,232 @@ impl Data {
             Err(self.available())
         }
     }
+
+    /// Read a big endian (most significant byte first) `u16` from the two bytes starting at `byte_index`.
+    ///
+    /// Returns `None` if either byte doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0x12, 0x34, 0x56]).unwrap();
+    /// assert_eq!(data.read_u16_be(1), Some(0x3456));
+    /// assert_eq!(data.read_u16_be(2), None);
+    /// ```
+    #[must_use]
+    pub fn read_u16_be(&self, byte_index: usize) -> Option<u16> {
+        self.pair(byte_index).map(u16::from_be_bytes)
+    }
+
+    /// Read a little endian (least significant byte first) `u16` from the two bytes starting at `byte_index`.
+    ///
+    /// Returns `None` if either byte doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0x12, 0x34, 0x56]).unwrap();
+    /// assert_eq!(data.read_u16_le(1), Some(0x5634));
+    /// assert_eq!(data.read_u16_le(2), None);
+    /// ```
+    #[must_use]
+    pub fn read_u16_le(&self, byte_index: usize) -> Option<u16> {
+        self.pair(byte_index).map(u16::from_le_bytes)
+    }
+
+    /// Write a big endian (most significant byte first) `u16` to the two bytes starting at `byte_index`.
+    ///
+    /// Returns `false` (leaving the data unchanged) if either byte doesn't exist,
+    /// unlike `set_bit` the data isn't grown to fit.
+    pub fn write_u16_be(&mut self, byte_index: usize, value: u16) -> bool {
+        let Some(pair) = self.pair_mut(byte_index) else {
+            return false;
+        };
+        pair.copy_from_slice(&value.to_be_bytes());
+        true
+    }
+
+    /// Write a little endian (least significant byte first) `u16` to the two bytes starting at `byte_index`.
+    ///
+    /// Returns `false` (leaving the data unchanged) if either byte doesn't exist,
+    /// unlike `set_bit` the data isn't grown to fit.
+    pub fn write_u16_le(&mut self, byte_index: usize, value: u16) -> bool {
+        let Some(pair) = self.pair_mut(byte_index) else {
+            return false;
+        };
+        pair.copy_from_slice(&value.to_le_bytes());
+        true
+    }
+
+    /// Whether this and other hold the same bytes, once any trailing zero bytes
+    /// are ignored (E.G. when a node pads its response).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0x12, 0x34]).unwrap();
+    /// assert!(data.eq_ignoring_trailing_zeros(&Data::try_from(&[0x12, 0x34, 0, 0]).unwrap()));
+    /// assert!(!data.eq_ignoring_trailing_zeros(&Data::try_from(&[0x12, 0x34, 0, 1]).unwrap()));
+    /// ```
+    #[must_use]
+    pub fn eq_ignoring_trailing_zeros(&self, other: &Self) -> bool {
+        fn trimmed(slice: &[u8]) -> &[u8] {
+            let len = slice.iter().rposition(|byte| *byte != 0).map_or(0, |index| index + 1);
+            &slice[..len]
+        }
+        trimmed(self.as_slice()) == trimmed(other.as_slice())
+    }
+
+    /// The bits which differ between this and other (the XOR of them),
+    /// the shorter is treated as padded with zeros so the result has the longer length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0b0000_1111]).unwrap();
+    /// assert_eq!(data.diff(&Data::try_from(&[0b0011_1100, 0x01]).unwrap()), [0b0011_0011, 0x01]);
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Self {
+        let mut diff = Self::new(self.len.max(other.len));
+        for (index, byte) in diff.as_mut_slice().iter_mut().enumerate() {
+            *byte = self.as_slice().get(index).unwrap_or(&0) ^ other.as_slice().get(index).unwrap_or(&0);
+        }
+        diff
+    }
+
+    /// The indexes of the bits which differ between this and other, lowest first
+    /// (the shorter is treated as padded with zeros, see [`Self::diff`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0b0000_0001]).unwrap();
+    /// let other = Data::try_from(&[0b1000_0000, 0b0000_0010]).unwrap();
+    /// assert_eq!(data.changed_bits(&other).collect::<Vec<_>>(), [0, 7, 9]);
+    /// ```
+    pub fn changed_bits(&self, other: &Self) -> impl Iterator<Item = usize> {
+        let diff = self.diff(other);
+        (0..(diff.len * 8)).filter(move |index| diff.get_bit(*index))
+    }
+
+    /// The indexes of the bits which are set, lowest first
+    /// (bit N of byte B is index (B * 8) + N, matching [`Self::get_bit`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0b0000_0101, 0b1000_0000]).unwrap();
+    /// assert!(data.iter_set_bits().eq([0, 2, 15]));
+    /// ```
+    pub fn iter_set_bits(&self) -> impl Iterator<Item = usize> + '_ {
+        self.as_slice().iter().enumerate().flat_map(|(byte_index, byte)|
+            (0..8).filter(move |bit| byte & (1 << bit) > 0).map(move |bit| (byte_index * 8) + bit)
+        )
+    }
+
+    /// The number of bits which are set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0b0000_0101, 0b1000_0000]).unwrap();
+    /// assert_eq!(data.count_set_bits(), 3);
+    /// ```
+    #[must_use]
+    pub fn count_set_bits(&self) -> u32 {
+        self.as_slice().iter().map(|byte| byte.count_ones()).sum()
+    }
+
+    /// A cheap (32 bit FNV-1a) hash of the bytes, E.G. so a controller can store it
+    /// and only compare/transmit the whole data when it changes.
+    ///
+    /// This isn't cryptographically secure, and different data can have the same hash.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0x12, 0x34]).unwrap();
+    /// assert_eq!(data.fast_hash(), Data::try_from(&[0x12, 0x34]).unwrap().fast_hash());
+    /// assert_ne!(data.fast_hash(), Data::try_from(&[0x12, 0x35]).unwrap().fast_hash());
+    /// ```
+    #[must_use]
+    pub const fn fast_hash(&self) -> u32 {
+        const OFFSET_BASIS: u32 = 0x811C_9DC5;
+        const PRIME: u32 = 0x0100_0193;
+        let mut hash = OFFSET_BASIS;
+        let mut index = 0;
+        while index < self.len {
+            hash ^= self.raw[index] as u32;
+            hash = hash.wrapping_mul(PRIME);
+            index += 1;
+        }
+        hash
+    }
+
+    /// Whether this differs from previous (E.G. the outputs last sent to a node).
+    ///
+    /// This is the same as `!=`, to keep a hash rather than the previous data use `fast_hash`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0x12, 0x34]).unwrap();
+    /// assert!(!data.has_changed(&Data::try_from(&[0x12, 0x34]).unwrap()));
+    /// assert!(data.has_changed(&Data::try_from(&[0x12, 0x35]).unwrap()));
+    /// assert!(data.has_changed(&Data::try_from(&[0x12, 0x34, 0x00]).unwrap()));
+    /// ```
+    #[must_use]
+    pub fn has_changed(&self, previous: &Self) -> bool {
+        self != previous
+    }
+
+    fn pair(&self, byte_index: usize) -> Option<[u8; 2]> {
+        self.as_slice().get(byte_index..(byte_index.checked_add(2)?))?.try_into().ok()
+    }
+
+    fn pair_mut(&mut self, byte_index: usize) -> Option<&mut [u8]> {
+        self.as_mut_slice().get_mut(byte_index..(byte_index.checked_add(2)?))
+    }
+}
+
+#[cfg(feature = "json")]
+#[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "json")))]
+#[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature json only.**\n\n")]
+impl Data {
+    /// Render as a JSON object of each bit's label to its state,
+    /// bits without a label are named "bit N".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use cmri::packet::Data;
+    /// let data = Data::try_from(&[0b0000_0001]).unwrap();
+    /// let labels = HashMap::from([(0, String::from("Turnout 1"))]);
+    /// let json = data.to_labeled_json(&labels);
+    /// assert_eq!(json["Turnout 1"], true);
+    /// assert_eq!(json["bit 1"], false);
+    /// ```
+    #[must_use]
+    pub fn to_labeled_json<H: core::hash::BuildHasher>(&self, labels: &std::collections::HashMap<usize, String, H>) -> serde_json::Value {
+        let map = (0..(self.len * 8))
+            .map(|index| {
+                let label = labels.get(&index).cloned().unwrap_or_else(|| format!("bit {index}"));
+                (label, serde_json::Value::Bool(self.get_bit(index)))
+            })
+            .collect();
+        serde_json::Value::Object(map)
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -149,6 +394,21 @@ impl<'de> serde::Deserialize<'de> for Data {
 }
 
 
+impl FromIterator<bool> for Data {
+    /// Create from the states of bits, see [`Data::from_bits`].
+    ///
+    /// # Panics
+    ///
+    /// If there's more than 2048 bits (256 bytes).
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let mut data = Self::default();
+        for (index, bit) in iter.into_iter().enumerate() {
+            data.set_bit(index, bit);
+        }
+        data
+    }
+}
+
 impl core::default::Default for Data {
     fn default() -> Self {
         Self::new(0)
@@ -240,7 +500,10 @@ mod tests {
 
         #[test]
         fn good_size() {
+            #[cfg(not(feature = "json"))]
             assert_eq!(Data::new(0).as_slice(), [].as_slice());
+            #[cfg(feature = "json")] // serde_json's `impl PartialEq<Value> for u8` makes the element type of `[]` ambiguous
+            assert_eq!(Data::new(0).as_slice(), [0_u8; 0].as_slice());
             assert_eq!(Data::new(5).as_slice(), [0; 5].as_slice());
             assert_eq!(Data::new(256).as_slice(), [0; 256].as_slice());
         }
@@ -394,6 +657,252 @@ mod tests {
         }
     }
 
+    mod u16 {
+        use super::*;
+
+        #[test]
+        fn read() {
+            let data = Data::try_from(&[0x12, 0x34, 0x56]).unwrap();
+            assert_eq!(data.read_u16_be(0), Some(0x1234));
+            assert_eq!(data.read_u16_le(0), Some(0x3412));
+            assert_eq!(data.read_u16_be(1), Some(0x3456));
+            assert_eq!(data.read_u16_le(1), Some(0x5634));
+        }
+
+        #[test]
+        fn read_out_of_range() {
+            let data = Data::try_from(&[0x12, 0x34, 0x56]).unwrap();
+            assert_eq!(data.read_u16_be(2), None);
+            assert_eq!(data.read_u16_le(2), None);
+            assert_eq!(data.read_u16_be(3), None);
+            assert_eq!(data.read_u16_le(usize::MAX), None);
+        }
+
+        #[test]
+        fn write() {
+            let mut data = Data::new(3);
+            assert!(data.write_u16_be(0, 0x1234));
+            assert_eq!(data.as_slice(), &[0x12, 0x34, 0]);
+            assert!(data.write_u16_le(1, 0x1234));
+            assert_eq!(data.as_slice(), &[0x12, 0x34, 0x12]);
+        }
+
+        #[test]
+        fn write_out_of_range() {
+            let mut data = Data::new(3);
+            assert!(!data.write_u16_be(2, 0x1234));
+            assert!(!data.write_u16_le(2, 0x1234));
+            assert!(!data.write_u16_be(usize::MAX, 0x1234));
+            assert_eq!(data.as_slice(), &[0, 0, 0]);
+        }
+    }
+
+    mod eq_ignoring_trailing_zeros {
+        use super::*;
+
+        #[test]
+        fn equal_with_padding() {
+            let data = Data::try_from(&[1, 0, 2]).unwrap();
+            assert!(data.eq_ignoring_trailing_zeros(&Data::try_from(&[1, 0, 2, 0, 0]).unwrap()));
+            assert!(Data::try_from(&[1, 0, 2, 0]).unwrap().eq_ignoring_trailing_zeros(&data));
+            assert!(data.eq_ignoring_trailing_zeros(&data));
+        }
+
+        #[test]
+        fn empty_equals_zeros() {
+            assert!(Data::default().eq_ignoring_trailing_zeros(&Data::new(3)));
+            assert!(Data::new(3).eq_ignoring_trailing_zeros(&Data::new(1)));
+        }
+
+        #[test]
+        fn different() {
+            let data = Data::try_from(&[1, 0, 2]).unwrap();
+            assert!(!data.eq_ignoring_trailing_zeros(&Data::try_from(&[1, 0, 2, 0, 3]).unwrap()));
+            assert!(!data.eq_ignoring_trailing_zeros(&Data::try_from(&[1, 2]).unwrap()));
+            assert!(!data.eq_ignoring_trailing_zeros(&Data::try_from(&[0, 1, 0, 2]).unwrap())); // Leading zeros count
+        }
+    }
+
+    mod diff {
+        use super::*;
+
+        #[test]
+        fn same_length() {
+            let data = Data::try_from(&[0x0F, 0xFF]).unwrap();
+            let other = Data::try_from(&[0xFF, 0xFF]).unwrap();
+            assert_eq!(data.diff(&other), [0xF0, 0x00]);
+            assert!(data.changed_bits(&other).eq(4..8));
+        }
+
+        #[test]
+        fn nothing_changed() {
+            let data = Data::try_from(&[0x12, 0x34]).unwrap();
+            assert_eq!(data.diff(&data), [0, 0]);
+            assert_eq!(data.changed_bits(&data).next(), None);
+            assert_eq!(Data::default().diff(&Data::default()), Data::default());
+        }
+
+        #[test]
+        fn different_lengths() {
+            let short = Data::try_from(&[0x01]).unwrap();
+            let long = Data::try_from(&[0x01, 0x00, 0x80]).unwrap();
+            assert_eq!(short.diff(&long), [0x00, 0x00, 0x80]);
+            assert_eq!(long.diff(&short), [0x00, 0x00, 0x80]);
+            assert!(short.changed_bits(&long).eq([23]));
+            assert!(long.changed_bits(&short).eq([23]));
+        }
+
+        #[test]
+        fn padding_with_zeros_is_unchanged() {
+            let short = Data::try_from(&[0x01]).unwrap();
+            let long = Data::try_from(&[0x01, 0x00]).unwrap();
+            assert_eq!(short.diff(&long), [0x00, 0x00]);
+            assert_eq!(short.changed_bits(&long).next(), None);
+        }
+    }
+
+    mod set_bits {
+        use super::*;
+
+        #[test]
+        fn some_set() {
+            let data = Data::try_from(&[0b0000_0101, 0b1000_0000]).unwrap();
+            assert!(data.iter_set_bits().eq([0, 2, 15]));
+            assert_eq!(data.count_set_bits(), 3);
+        }
+
+        #[test]
+        fn none_set() {
+            let data = Data::new(3);
+            assert_eq!(data.iter_set_bits().next(), None);
+            assert_eq!(data.count_set_bits(), 0);
+        }
+
+        #[test]
+        fn all_set() {
+            let data = Data::try_from(&[0xFF; 256]).unwrap();
+            assert!(data.iter_set_bits().eq(0..2048));
+            assert_eq!(data.count_set_bits(), 2048);
+        }
+
+        #[test]
+        fn matches_get_bit() {
+            let data = Data::try_from(&[0x12, 0x34, 0x56]).unwrap();
+            assert!(data.iter_set_bits().eq((0..24).filter(|index| data.get_bit(*index))));
+        }
+    }
+
+    mod fast_hash {
+        use super::*;
+
+        #[test]
+        fn equal_data() {
+            let data = Data::try_from(&[1, 2, 3, 4]).unwrap();
+            assert_eq!(data.fast_hash(), Data::try_from(&[1, 2, 3, 4]).unwrap().fast_hash());
+        }
+
+        #[test]
+        fn known_values() {
+            // Standard FNV-1a test vectors
+            assert_eq!(Data::default().fast_hash(), 0x811C_9DC5);
+            assert_eq!(Data::try_from(b"a").unwrap().fast_hash(), 0xE40C_292C);
+            assert_eq!(Data::try_from(b"foobar").unwrap().fast_hash(), 0xBF9C_F968);
+        }
+
+        #[test]
+        fn single_bit_changed() {
+            let data = Data::try_from(&[0; 8]).unwrap();
+            for index in 0..64 {
+                let mut changed = data;
+                changed.toggle_bit(index);
+                assert_ne!(data.fast_hash(), changed.fast_hash(), "bit {index}");
+            }
+        }
+
+        #[test]
+        fn length_changed() {
+            assert_ne!(Data::new(1).fast_hash(), Data::new(2).fast_hash());
+            assert_ne!(Data::default().fast_hash(), Data::new(1).fast_hash());
+        }
+
+        #[test]
+        fn ignores_unused_bytes() {
+            let mut data = Data::try_from(&[1, 2, 3]).unwrap();
+            data.clear();
+            data.push_all(&[1]).unwrap();
+            assert_eq!(data.fast_hash(), Data::try_from(&[1]).unwrap().fast_hash());
+        }
+    }
+
+    mod has_changed {
+        use super::*;
+
+        #[test]
+        fn unchanged() {
+            let data = Data::try_from(&[1, 2, 3]).unwrap();
+            assert!(!data.has_changed(&data));
+            assert!(!Data::default().has_changed(&Data::default()));
+        }
+
+        #[test]
+        fn single_bit_changed() {
+            let data = Data::try_from(&[0; 4]).unwrap();
+            for index in 0..32 {
+                let mut changed = data;
+                changed.toggle_bit(index);
+                assert!(changed.has_changed(&data), "bit {index}");
+            }
+        }
+
+        #[test]
+        fn length_changed() {
+            assert!(Data::new(2).has_changed(&Data::new(1)));
+            assert!(Data::new(1).has_changed(&Data::new(2)));
+        }
+    }
+
+    mod from_bits {
+        use super::*;
+
+        const BITS: [bool; 20] = [
+            true, false, false, false, false, false, false, true,   // 0x81
+            false, true, false, true, false, true, false, true,     // 0xAA
+            true, true, false, false                                // 0x03
+        ];
+
+        #[test]
+        fn packs_lowest_bit_first() {
+            let data = Data::from_bits(&BITS).unwrap();
+            assert_eq!(data.len(), 3);
+            assert_eq!(data, [0x81, 0xAA, 0x03]);
+        }
+
+        #[test]
+        fn round_trip() {
+            let data = Data::from_bits(&BITS).unwrap();
+            for (index, bit) in BITS.iter().enumerate() {
+                assert_eq!(data.get_bit(index), *bit, "bit {index}");
+            }
+        }
+
+        #[test]
+        fn trailing_false_bits_count() {
+            assert_eq!(Data::from_bits(&[false; 9]).unwrap(), [0x00, 0x00]);
+            assert_eq!(Data::from_bits(&[]).unwrap(), Data::default());
+        }
+
+        #[test]
+        fn maximum() {
+            assert_eq!(Data::from_bits(&[true; 2048]).unwrap(), [0xFF; 256]);
+            assert_eq!(Data::from_bits(&[true; 2049]), Err(Error::TooLong));
+        }
+
+        #[test]
+        fn from_iterator() {
+            assert_eq!(BITS.into_iter().collect::<Data>(), Data::from_bits(&BITS).unwrap());
+        }
+    }
+
     mod try_from_slice_u8 {
         use super::*;
 
@@ -426,6 +935,39 @@ mod tests {
         assert_eq!(*Data::from(raw_packet), [1, 2, 3, 4, 5]);
     }
 
+    #[cfg(feature = "json")]
+    mod to_labeled_json {
+        use std::collections::HashMap;
+        use super::*;
+
+        #[test]
+        fn partial_labels() {
+            let data = Data::try_from(&[0b0000_0101, 0b1000_0000]).unwrap();
+            let labels = HashMap::from([
+                (0, String::from("Turnout 1")),
+                (1, String::from("Turnout 2")),
+                (15, String::from("Signal"))
+            ]);
+            let json = data.to_labeled_json(&labels);
+
+            let object = json.as_object().unwrap();
+            assert_eq!(object.len(), 16);
+            assert_eq!(object["Turnout 1"], true);
+            assert_eq!(object["Turnout 2"], false);
+            assert_eq!(object["bit 2"], true);
+            assert_eq!(object["bit 3"], false);
+            assert_eq!(object["bit 14"], false);
+            assert_eq!(object["Signal"], true);
+            assert!(!object.contains_key("bit 0"));
+        }
+
+        #[test]
+        fn empty() {
+            let json = Data::default().to_labeled_json(&HashMap::<usize, String>::new());
+            assert_eq!(json, serde_json::json!({}));
+        }
+    }
+
     #[cfg(feature = "serde")]
     mod serde {
         use super::*;
@@ -1,5 +1,5 @@
 /// The errors which can occur when decoding/creating a packet.
-#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error {
     /// The node address is outside the valid range of 0-127.
@@ -19,6 +19,17 @@ pub enum Error {
     #[error("Too short")]
     TooShort,
 
+    /// The initialization packet's body is too short for its node type.
+    #[error("Initialization too short for node type {:?} ({actual} of {expected} bytes)", char::from(*node_type))]
+    InitializationTooShort {
+        /// The node type (NDP byte) from the start of the body.
+        node_type: u8,
+        /// The minimum number of bytes the node type needs.
+        expected: usize,
+        /// The number of bytes in the body.
+        actual: usize
+    },
+
     /// The packet is too long.
     #[error("Too long")]
     TooLong,
@@ -27,6 +38,22 @@ pub enum Error {
     #[error("Body too long")]
     BodyTooLong,
 
+    #[cfg(feature = "experimenter")]
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "experimenter")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature experimenter only.**\n\n")]
+    /// The text (for a text packet) contains non ASCII characters.
+    #[error("Not ASCII")]
+    NotAscii,
+
+    /// The data is a different length to the node's configured inputs/outputs.
+    #[error("Data is {actual} bytes, but the node has {expected}")]
+    DataLengthMismatch {
+        /// The number of bytes the node has.
+        expected: u16,
+        /// The number of bytes in the data.
+        actual: usize
+    },
+
     /// The node type (within an initialization packet) is not of a known type.
     #[error("Invalid Node Type: {0:?}")]
     InvalidNodeType(u8),
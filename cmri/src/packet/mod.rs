@@ -1,5 +1,6 @@
 //! Handling of CMRInet packets.
 
+mod bcc;
 mod data;
 mod error;
 #[expect(clippy::module_inception)]
@@ -7,8 +8,9 @@ mod packet;
 mod payload;
 mod raw;
 
+pub use bcc::compute_bcc;
 pub use data::Data;
 pub use error::Error;
-pub use packet::Packet;
+pub use packet::{Packet, PacketBuilder};
 pub use payload::Payload;
 pub use raw::Raw;
@@ -1,11 +1,11 @@
 use log::trace;
-use crate::{Address, NodeSort, frame::Raw as RawFrame};
-use super::{Payload, Data, Raw};
-#[cfg(feature = "experimenter")]
-use super::Error;
+use crate::{Address, NodeSort, NodeConfiguration, frame::{Raw as RawFrame, DecodeError}};
+use super::{Payload, Data, Raw, Error};
 
 /// A CMRInet packet.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+///
+/// Packets are ordered by address, then message type, then body.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Packet {
     address: Address,
@@ -13,6 +13,20 @@ pub struct Packet {
 }
 
 impl Packet {
+    /// Start building a packet for address, by default a poll request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{Address, packet::{Data, Packet}};
+    /// let address = Address::try_from_node_address(1).unwrap();
+    /// let packet = Packet::builder(address).transmit_data().set_bit(3).set_byte(2, 0xAA).build().unwrap();
+    /// assert_eq!(packet, Packet::new_transmit_data(address, Data::try_from(&[0x08, 0x00, 0xAA]).unwrap()));
+    /// ```
+    pub fn builder(address: Address) -> PacketBuilder {
+        PacketBuilder { address, kind: BuilderKind::PollRequest, data: Data::default(), too_long: false }
+    }
+
     /// Create a new initialization packet.
     #[must_use]
     pub const fn new_initialization(address: Address, node_sort: NodeSort) -> Self {
@@ -41,6 +55,24 @@ impl Packet {
         Self { address, payload }
     }
 
+    /// Create a new set (controller → node parameters) packet.
+    #[must_use]
+    pub const fn new_set(address: Address, data: Data) -> Self {
+        let payload = Payload::Set { data };
+        Self { address, payload }
+    }
+
+    /// Create a new transmit data (controller → node outputs) packet,
+    /// checking the data is the right length for the node's configuration.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::DataLengthMismatch`] if the data isn't the same length as the node's output bytes.
+    pub fn try_new_transmit_data_for(address: Address, data: Data, config: &dyn NodeConfiguration) -> Result<Self, Error> {
+        config.validate_output_data(&data)?;
+        Ok(Self::new_transmit_data(address, data))
+    }
+
     #[cfg(feature = "experimenter")]
     #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "experimenter")))]
     #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature experimenter only.**\n\n")]
@@ -59,6 +91,62 @@ impl Packet {
         Ok(Self { address, payload })
     }
 
+    #[cfg(feature = "experimenter")]
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "experimenter")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature experimenter only.**\n\n")]
+    /// Create a new reset packet.
+    ///
+    /// The CMRInet specification doesn't define a reset message, a node is
+    /// re-initialized by sending it another initialization packet.
+    /// This creates an [`Payload::Unknown`] packet with a message type of
+    /// [`Self::RESET_MESSAGE_TYPE`] and an empty body, so only nodes which
+    /// have been programmed to understand it will act upon it.
+    #[must_use]
+    pub fn new_reset(address: Address) -> Self {
+        let payload = Payload::Unknown { message_type: Self::RESET_MESSAGE_TYPE, body: Data::default() };
+        Self { address, payload }
+    }
+
+    #[cfg(feature = "experimenter")]
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "experimenter")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature experimenter only.**\n\n")]
+    /// The message type used by [`Self::new_reset`] ('Z').
+    pub const RESET_MESSAGE_TYPE: u8 = b'Z';
+
+    #[cfg(feature = "experimenter")]
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "experimenter")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature experimenter only.**\n\n")]
+    /// Create a new text packet, E.G. for leaving commissioning notes on the wire.
+    ///
+    /// The CMRInet specification doesn't define a text message,
+    /// this creates an [`Payload::Unknown`] packet with a message type of
+    /// [`Self::TEXT_MESSAGE_TYPE`] and the text as the body,
+    /// which can be read back using [`Payload::as_text`].
+    ///
+    /// # Errors
+    ///
+    ///   * [`Error::NotAscii`] if the text contains non ASCII characters.
+    ///   * [`Error::BodyTooLong`] if the text is over 256 characters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{Address, packet::Packet};
+    /// let packet = Packet::new_text(Address::try_from_node_address(0).unwrap(), "Hello").unwrap();
+    /// assert_eq!(packet.payload().as_text(), Some("Hello"));
+    /// ```
+    pub fn new_text(address: Address, text: &str) -> Result<Self, Error> {
+        if !text.is_ascii() { return Err(Error::NotAscii) }
+        let payload = Payload::Unknown { message_type: Self::TEXT_MESSAGE_TYPE, body: text.as_bytes().try_into()? };
+        Ok(Self { address, payload })
+    }
+
+    #[cfg(feature = "experimenter")]
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "experimenter")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature experimenter only.**\n\n")]
+    /// The message type used by [`Self::new_text`] ('Y').
+    pub const TEXT_MESSAGE_TYPE: u8 = b'Y';
+
     /// Encode into a `RawPacket` (without escaping or framing).
     ///
     /// # Example
@@ -97,6 +185,23 @@ impl Packet {
         self.encode_packet().try_into().expect("An always valid Packet, will always produce a valid RawPacket, which will always produce a valid RawFrame.")
     }
 
+    /// Whether the packet survives being encoded into a frame and decoded back unchanged,
+    /// E.G. for asserting that escaping works at an integration boundary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{Address, packet::Packet};
+    /// let address = Address::try_from_node_address(0).unwrap();
+    /// // Data containing SYN, STX, ETX & DLE bytes which need escaping in the frame.
+    /// let packet = Packet::new_transmit_data(address, [0xFF, 0x02, 0x03, 0x10].try_into().unwrap());
+    /// assert!(packet.roundtrip_check());
+    /// ```
+    #[must_use]
+    pub fn roundtrip_check(&self) -> bool {
+        self.encode_frame().try_as_packet().is_ok_and(|packet| packet == *self)
+    }
+
     /// The address this packet is being sent to / has been received from.
     #[must_use]
     pub const fn address(&self) -> Address {
@@ -108,6 +213,186 @@ impl Packet {
     pub const fn payload(&self) -> &Payload {
         &self.payload
     }
+
+    /// Whether the packet is being sent to / has been received from address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{Address, packet::Packet};
+    /// let packet = Packet::new_poll_request(Address::try_from_node_address(5).unwrap());
+    /// assert!(packet.is_for(Address::try_from_node_address(5).unwrap()));
+    /// assert!(!packet.is_for(Address::try_from_node_address(6).unwrap()));
+    /// ```
+    #[must_use]
+    pub const fn is_for(&self, address: Address) -> bool {
+        self.address.as_node_address() == address.as_node_address()
+    }
+
+    /// Whether the packet is an initialization.
+    #[must_use]
+    pub const fn is_initialization(&self) -> bool {
+        matches!(self.payload, Payload::Initialization { .. })
+    }
+
+    /// Whether the packet is a poll request.
+    #[must_use]
+    pub const fn is_poll_request(&self) -> bool {
+        matches!(self.payload, Payload::PollRequest)
+    }
+
+    /// Whether the packet is receive data.
+    #[must_use]
+    pub const fn is_receive_data(&self) -> bool {
+        matches!(self.payload, Payload::ReceiveData { .. })
+    }
+
+    /// Whether the packet is transmit data.
+    #[must_use]
+    pub const fn is_transmit_data(&self) -> bool {
+        matches!(self.payload, Payload::TransmitData { .. })
+    }
+
+    /// Whether the packet is a set.
+    #[must_use]
+    pub const fn is_set(&self) -> bool {
+        matches!(self.payload, Payload::Set { .. })
+    }
+
+    /// The number of bytes in the packet's body (excluding the address and message type).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{Address, packet::Packet};
+    /// let address = Address::try_from_node_address(0).unwrap();
+    /// assert_eq!(Packet::new_poll_request(address).payload_len(), 0);
+    /// assert_eq!(Packet::new_receive_data(address, [1, 2, 3].try_into().unwrap()).payload_len(), 3);
+    /// ```
+    #[must_use]
+    pub fn payload_len(&self) -> usize {
+        match &self.payload {
+            Payload::Initialization { node_sort } => node_sort.encode().len(),
+            Payload::PollRequest => 0,
+            Payload::ReceiveData { data } | Payload::TransmitData { data } | Payload::Set { data } => data.len(),
+            #[cfg(feature = "experimenter")]
+            Payload::Unknown { body, .. } => body.len()
+        }
+    }
+
+    /// The block check character (BCC) for the packet, see [`super::compute_bcc`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{Address, packet::Packet};
+    /// let address = Address::try_from_node_address(0).unwrap();
+    /// let packet = Packet::new_transmit_data(address, [0x01, 0x02].try_into().unwrap());
+    /// assert_eq!(packet.bcc(), b'T' ^ 0x01 ^ 0x02);
+    /// ```
+    #[must_use]
+    pub fn bcc(&self) -> u8 {
+        let raw = self.encode_packet();
+        super::compute_bcc(raw[1], raw.body())
+    }
+
+    /// Estimate how long the packet takes to transmit on a CMRInet network running at baud,
+    /// this is based on the length of the encoded (escaped) frame at 10 bits per byte
+    /// (start bit, 8 data bits, stop bit).
+    ///
+    /// A baud of 0 will never finish transmitting, so gives `Duration::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cmri::{Address, packet::Packet};
+    /// let address = Address::try_from_node_address(0).unwrap();
+    /// let packet = Packet::new_poll_request(address); // A 6 byte frame
+    /// assert_eq!(packet.transmit_time(9600), core::time::Duration::from_micros(6_250));
+    /// ```
+    #[must_use]
+    pub fn transmit_time(&self, baud: u32) -> core::time::Duration {
+        let bits = self.encode_frame().len() as u64 * 10;
+        (bits * 1_000_000_000)
+            .checked_div(u64::from(baud))
+            .map_or(core::time::Duration::MAX, core::time::Duration::from_nanos)
+    }
+}
+
+
+/// Builds a [`Packet`] a piece at a time (E.G. setting output bits one by one),
+/// see [`Packet::builder`].
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct PacketBuilder {
+    address: Address,
+    kind: BuilderKind,
+    data: Data,
+    too_long: bool
+}
+
+#[derive(Copy, Clone, Debug)]
+enum BuilderKind {
+    PollRequest,
+    ReceiveData,
+    TransmitData
+}
+
+impl PacketBuilder {
+    /// Build a poll request packet, any data set is ignored.
+    pub const fn poll_request(mut self) -> Self {
+        self.kind = BuilderKind::PollRequest;
+        self
+    }
+
+    /// Build a receive data (node inputs → controller) packet.
+    pub const fn receive_data(mut self) -> Self {
+        self.kind = BuilderKind::ReceiveData;
+        self
+    }
+
+    /// Build a transmit data (controller → node outputs) packet.
+    pub const fn transmit_data(mut self) -> Self {
+        self.kind = BuilderKind::TransmitData;
+        self
+    }
+
+    /// Set a bit in the data, bytes will be added as needed.
+    pub fn set_bit(mut self, index: usize) -> Self {
+        if index < Data::MAX_LEN * 8 {
+            self.data.set_bit(index, true);
+        } else {
+            self.too_long = true;
+        }
+        self
+    }
+
+    /// Set a byte in the data, bytes will be added as needed.
+    pub fn set_byte(mut self, index: usize, value: u8) -> Self {
+        if index < Data::MAX_LEN {
+            while self.data.len() <= index {
+                let _ = self.data.push(0);
+            }
+            self.data.as_mut_slice()[index] = value;
+        } else {
+            self.too_long = true;
+        }
+        self
+    }
+
+    /// Build the packet.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::TooLong`] if a bit or byte was set beyond the maximum length of the data (256 bytes).
+    pub const fn build(self) -> Result<Packet, Error> {
+        if self.too_long { return Err(Error::TooLong) }
+        Ok(match self.kind {
+            BuilderKind::PollRequest => Packet::new_poll_request(self.address),
+            BuilderKind::ReceiveData => Packet::new_receive_data(self.address, self.data),
+            BuilderKind::TransmitData => Packet::new_transmit_data(self.address, self.data)
+        })
+    }
 }
 
 
@@ -122,6 +407,39 @@ impl TryFrom<Raw> for Packet {
     }
 }
 
+impl From<&Packet> for RawFrame {
+    /// Encode the packet into a frame, see [`Packet::encode_frame`].
+    fn from(packet: &Packet) -> Self {
+        packet.encode_frame()
+    }
+}
+
+impl TryFrom<&[u8]> for Packet {
+    type Error = DecodeError;
+
+    /// Decode the bytes of a frame (as read from the wire) into a packet,
+    /// this is the same as [`RawFrame::try_from`] followed by [`RawFrame::try_as_packet`].
+    ///
+    /// ```
+    /// use cmri::{Address, packet::Packet, frame::DecodeError};
+    /// let packet = Packet::try_from(&[0xFF, 0xFF, 0x02, 65, b'P', 0x03][..]).unwrap();
+    /// assert_eq!(packet, Packet::new_poll_request(Address::try_from_node_address(0).unwrap()));
+    /// assert_eq!(Packet::try_from(&[0xFF, 0xFF][..]), Err(DecodeError::TooShort));
+    /// ```
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        RawFrame::try_from(value)?.try_as_packet()
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8; N]> for Packet {
+    type Error = DecodeError;
+
+    /// Decode the bytes of a frame into a packet, as for a slice.
+    fn try_from(value: &[u8; N]) -> Result<Self, Self::Error> {
+        TryFrom::try_from(&value[..])
+    }
+}
+
 
 #[allow(clippy::missing_panics_doc, reason = "tests")]
 #[cfg(test)]
@@ -140,6 +458,151 @@ mod tests {
         assert_eq!(packet.payload(), &payload);
     }
 
+    mod predicates {
+        use super::*;
+
+        fn address(node_address: u8) -> Address {
+            Address::try_from_node_address(node_address).unwrap()
+        }
+
+        #[test]
+        fn is_for() {
+            let packet = Packet::new_poll_request(address(10));
+            assert!(packet.is_for(address(10)));
+            assert!(!packet.is_for(address(11)));
+            assert!(!packet.is_for(address(0)));
+        }
+
+        #[test]
+        fn initialization() {
+            let packet = Packet::new_initialization(address(1), NodeSort::try_new_smini(0, [0; 6]).unwrap());
+            assert!(packet.is_initialization());
+            assert!(!packet.is_poll_request());
+            assert!(!packet.is_receive_data());
+            assert!(!packet.is_transmit_data());
+        }
+
+        #[test]
+        fn poll_request() {
+            let packet = Packet::new_poll_request(address(1));
+            assert!(!packet.is_initialization());
+            assert!(packet.is_poll_request());
+            assert!(!packet.is_receive_data());
+            assert!(!packet.is_transmit_data());
+        }
+
+        #[test]
+        fn receive_data() {
+            let packet = Packet::new_receive_data(address(1), Data::default());
+            assert!(!packet.is_initialization());
+            assert!(!packet.is_poll_request());
+            assert!(packet.is_receive_data());
+            assert!(!packet.is_transmit_data());
+        }
+
+        #[test]
+        fn transmit_data() {
+            let packet = Packet::new_transmit_data(address(1), Data::default());
+            assert!(!packet.is_initialization());
+            assert!(!packet.is_poll_request());
+            assert!(!packet.is_receive_data());
+            assert!(packet.is_transmit_data());
+            assert!(!packet.is_set());
+        }
+
+        #[test]
+        fn set() {
+            let packet = Packet::new_set(address(1), Data::default());
+            assert!(!packet.is_initialization());
+            assert!(!packet.is_poll_request());
+            assert!(!packet.is_receive_data());
+            assert!(!packet.is_transmit_data());
+            assert!(packet.is_set());
+        }
+
+        #[test]
+        #[cfg(feature = "experimenter")]
+        fn unknown() {
+            let packet = Packet::try_new_unknown(address(1), b'X', Data::default()).unwrap();
+            assert!(!packet.is_initialization());
+            assert!(!packet.is_poll_request());
+            assert!(!packet.is_receive_data());
+            assert!(!packet.is_transmit_data());
+        }
+    }
+
+    mod payload_len {
+        use super::*;
+
+        #[test]
+        fn initialization() {
+            let configuration = CpnodeConfiguration::try_new(0, CpnodeOptions::default(), 1, 1).unwrap();
+            let packet = Packet::new_initialization(Address::try_from_node_address(1).unwrap(), NodeSort::Cpnode { configuration });
+            assert_eq!(packet.payload_len(), 13); // NDP, DL (2 bytes), Options (2 bytes), NI, NO, 6 spare bytes
+            assert_eq!(packet.payload_len(), packet.encode_packet().len() - 2);
+        }
+
+        #[test]
+        fn poll_request() {
+            let packet = Packet::new_poll_request(Address::try_from_node_address(1).unwrap());
+            assert_eq!(packet.payload_len(), 0);
+        }
+
+        #[test]
+        fn receive_data() {
+            let packet = Packet::new_receive_data(Address::try_from_node_address(1).unwrap(), [1, 2, 3].try_into().unwrap());
+            assert_eq!(packet.payload_len(), 3);
+        }
+
+        #[test]
+        fn transmit_data() {
+            let packet = Packet::new_transmit_data(Address::try_from_node_address(1).unwrap(), [1, 2, 3, 4, 5].try_into().unwrap());
+            assert_eq!(packet.payload_len(), 5);
+        }
+
+        #[test]
+        fn set() {
+            let packet = Packet::new_set(Address::try_from_node_address(1).unwrap(), [1, 2, 3].try_into().unwrap());
+            assert_eq!(packet.payload_len(), 3);
+        }
+
+        #[test]
+        #[cfg(feature = "experimenter")]
+        fn unknown() {
+            let packet = Packet::try_new_unknown(Address::try_from_node_address(1).unwrap(), b'A', [1, 2].try_into().unwrap()).unwrap();
+            assert_eq!(packet.payload_len(), 2);
+        }
+    }
+
+    mod transmit_time {
+        use core::time::Duration;
+        use super::*;
+
+        #[test]
+        fn poll_vs_full_transmit() {
+            let address = Address::try_from_node_address(1).unwrap();
+            let poll = Packet::new_poll_request(address);
+            let transmit = Packet::new_transmit_data(address, Data::new(64 * 3)); // USIC with 64 24-bit output cards
+
+            assert_eq!(poll.transmit_time(19_200), Duration::from_micros(3_125)); // 6 bytes
+            assert_eq!(transmit.transmit_time(19_200), Duration::from_micros(103_125)); // 198 bytes
+            assert_eq!(transmit.transmit_time(19_200), poll.transmit_time(19_200) * 33);
+        }
+
+        #[test]
+        fn includes_escaping() {
+            let address = Address::try_from_node_address(1).unwrap();
+            let packet = Packet::new_transmit_data(address, [0x10; 4].try_into().unwrap());
+            assert_eq!(packet.transmit_time(10_000), Duration::from_millis(14)); // 14 bytes (4 are DLE escapes)
+        }
+
+        #[test]
+        fn zero_baud() {
+            let packet = Packet::new_poll_request(Address::try_from_node_address(1).unwrap());
+            assert_eq!(packet.transmit_time(0), Duration::MAX);
+        }
+    }
+
     mod constructors {
         use super::*;
 
@@ -205,6 +668,58 @@ mod tests {
             );
         }
 
+        #[test]
+        fn set() {
+            let packet = Packet::new_set(Address::try_from_node_address(1).unwrap(), [1, 2].try_into().unwrap());
+            assert_eq!(
+                packet,
+                Packet {
+                    address: Address::try_from_node_address(1).unwrap(),
+                    payload: Payload::Set {
+                        data: Data::try_from(&[1, 2]).unwrap()
+                    }
+                }
+            );
+            assert_eq!(*packet.encode_frame(), [0xFF, 0xFF, 0x02, 66, b'S', 1, 0x10, 2, 0x03]);
+            assert_eq!(packet.encode_frame().try_as_packet(), Ok(packet));
+        }
+
+        mod transmit_data_for {
+            use super::*;
+
+            fn configuration() -> CpnodeConfiguration {
+                CpnodeConfiguration::try_new(0, CpnodeOptions::default(), 1, 2).unwrap()
+            }
+
+            #[test]
+            fn correct_size() {
+                let address = Address::try_from_node_address(1).unwrap();
+                let data = Data::try_from(&[1, 2]).unwrap();
+                assert_eq!(
+                    Packet::try_new_transmit_data_for(address, data, &configuration()),
+                    Ok(Packet::new_transmit_data(address, data))
+                );
+            }
+
+            #[test]
+            fn oversized() {
+                let address = Address::try_from_node_address(1).unwrap();
+                assert_eq!(
+                    Packet::try_new_transmit_data_for(address, Data::try_from(&[1, 2, 3]).unwrap(), &configuration()),
+                    Err(Error::DataLengthMismatch { expected: 2, actual: 3 })
+                );
+            }
+
+            #[test]
+            fn undersized() {
+                let address = Address::try_from_node_address(1).unwrap();
+                assert_eq!(
+                    Packet::try_new_transmit_data_for(address, Data::try_from(&[1]).unwrap(), &configuration()),
+                    Err(Error::DataLengthMismatch { expected: 2, actual: 1 })
+                );
+            }
+        }
+
         #[cfg(feature = "experimenter")]
         mod unknown {
             use super::*;
@@ -228,6 +743,59 @@ mod tests {
                 );
             }
 
+            #[test]
+            fn reset() {
+                let packet = Packet::new_reset(Address::try_from_node_address(12).unwrap());
+                assert_eq!(
+                    packet,
+                    Packet {
+                        address: Address::try_from_node_address(12).unwrap(),
+                        payload: Payload::Unknown {
+                            message_type: b'Z',
+                            body: Data::default()
+                        }
+                    }
+                );
+                assert_eq!(packet.encode_frame(), [0xFF, 0xFF, 0x02, 77, b'Z', 0x03]);
+                assert_eq!(packet.encode_frame().try_as_packet(), Ok(packet));
+            }
+
+            #[test]
+            fn text() {
+                let packet = Packet::new_text(Address::try_from_node_address(12).unwrap(), "Hi!").unwrap();
+                assert_eq!(
+                    packet,
+                    Packet {
+                        address: Address::try_from_node_address(12).unwrap(),
+                        payload: Payload::Unknown {
+                            message_type: b'Y',
+                            body: Data::try_from(b"Hi!").unwrap()
+                        }
+                    }
+                );
+                assert_eq!(packet.encode_frame(), [0xFF, 0xFF, 0x02, 77, b'Y', b'H', b'i', b'!', 0x03]);
+
+                let decoded = packet.encode_frame().try_as_packet().unwrap();
+                assert_eq!(decoded, packet);
+                assert_eq!(decoded.payload().as_text(), Some("Hi!"));
+            }
+
+            #[test]
+            fn text_not_ascii() {
+                assert_eq!(
+                    Packet::new_text(Address::try_from_node_address(12).unwrap(), "Caf\u{e9}"),
+                    Err(Error::NotAscii)
+                );
+            }
+
+            #[test]
+            fn text_too_long() {
+                assert_eq!(
+                    Packet::new_text(Address::try_from_node_address(12).unwrap(), &"A".repeat(257)),
+                    Err(Error::BodyTooLong)
+                );
+            }
+
             #[test]
             fn invalid_message_type() {
                 assert_eq!(
@@ -242,6 +810,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ordering() {
+        let address = |a| Address::try_from_node_address(a).unwrap();
+        let mut packets = [
+            Packet::new_transmit_data(address(2), [1].try_into().unwrap()),
+            Packet::new_poll_request(address(2)),
+            Packet::new_receive_data(address(1), [2].try_into().unwrap()),
+            Packet::new_transmit_data(address(2), [0, 5].try_into().unwrap()),
+            Packet::new_initialization(address(2), NodeSort::try_new_smini(0, [0; 6]).unwrap()),
+            Packet::new_receive_data(address(1), [1, 9].try_into().unwrap()),
+            Packet::new_poll_request(address(0))
+        ];
+        packets.sort();
+        assert_eq!(
+            packets,
+            [
+                Packet::new_poll_request(address(0)),
+                Packet::new_receive_data(address(1), [1, 9].try_into().unwrap()),
+                Packet::new_receive_data(address(1), [2].try_into().unwrap()),
+                Packet::new_initialization(address(2), NodeSort::try_new_smini(0, [0; 6]).unwrap()),
+                Packet::new_poll_request(address(2)),
+                Packet::new_transmit_data(address(2), [0, 5].try_into().unwrap()),
+                Packet::new_transmit_data(address(2), [1].try_into().unwrap())
+            ]
+        );
+    }
+
+    #[cfg(feature = "experimenter")]
+    #[test]
+    fn ordering_unknown_after_known() {
+        let address = Address::try_from_node_address(3).unwrap();
+        let known = Packet::new_poll_request(address);
+        let unknown = Packet::try_new_unknown(address, b'P', Data::default()).unwrap();
+        assert_eq!(known.encode_packet(), unknown.encode_packet());
+        assert!(known < unknown);
+        assert_ne!(known.cmp(&unknown), core::cmp::Ordering::Equal);
+    }
+
+    #[cfg(feature = "experimenter")]
+    #[test]
+    fn ordering_unknown_initialization() {
+        let address = Address::try_from_node_address(3).unwrap();
+        let body = Data::try_from(b"Z").unwrap();
+        let initialization = Packet::new_initialization(address, NodeSort::Unknown { body });
+        let unknown = Packet::try_new_unknown(address, b'I', body).unwrap();
+        assert_eq!(initialization.encode_packet(), unknown.encode_packet());
+        assert_ne!(initialization, unknown);
+        assert!(initialization < unknown);
+        assert_ne!(initialization.cmp(&unknown), core::cmp::Ordering::Equal);
+    }
+
     #[test]
     fn encode_packet() {
         assert_eq!(
@@ -308,6 +927,306 @@ mod tests {
         }
     }
 
+    mod bcc {
+        use super::*;
+
+        #[test]
+        fn poll_request() {
+            assert_eq!(Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).bcc(), b'P');
+        }
+
+        #[test]
+        fn receive_data() {
+            let packet = Packet::new_receive_data(Address::try_from_node_address(1).unwrap(), [0x01, 0x02, 0x04, 0x08].try_into().unwrap());
+            assert_eq!(packet.bcc(), 0x5D);
+        }
+
+        #[test]
+        fn initialization() {
+            let packet = Packet::new_initialization(Address::try_from_node_address(1).unwrap(), NodeSort::try_new_smini(10, [0; 6]).unwrap());
+            assert_eq!(packet.bcc(), 0x0E);
+        }
+    }
+
+    mod builder {
+        use super::*;
+
+        fn address() -> Address {
+            Address::try_from_node_address(7).unwrap()
+        }
+
+        #[test]
+        fn default_is_poll_request() {
+            assert_eq!(Packet::builder(address()).build(), Ok(Packet::new_poll_request(address())));
+            assert_eq!(
+                Packet::builder(address()).transmit_data().set_bit(1).poll_request().build(),
+                Ok(Packet::new_poll_request(address()))
+            );
+        }
+
+        #[test]
+        fn transmit_data_scattered_bits() {
+            let packet = Packet::builder(address())
+                .transmit_data()
+                .set_bit(0)
+                .set_bit(9)
+                .set_bit(23)
+                .set_byte(4, 0x10)
+                .build()
+                .unwrap();
+            let expected = Packet::new_transmit_data(address(), [0x01, 0x02, 0x80, 0x00, 0x10].try_into().unwrap());
+            assert_eq!(packet.encode_frame(), expected.encode_frame());
+        }
+
+        #[test]
+        fn receive_data() {
+            let packet = Packet::builder(address()).receive_data().set_byte(1, 0xFF).set_bit(0).build().unwrap();
+            assert_eq!(packet, Packet::new_receive_data(address(), [0x01, 0xFF].try_into().unwrap()));
+        }
+
+        #[test]
+        fn set_byte_overwrites() {
+            let packet = Packet::builder(address()).transmit_data().set_bit(0).set_byte(0, 0xF0).build().unwrap();
+            assert_eq!(packet, Packet::new_transmit_data(address(), [0xF0].try_into().unwrap()));
+        }
+
+        #[test]
+        fn maximum_length() {
+            let packet = Packet::builder(address()).transmit_data().set_bit(2047).set_byte(255, 0xFF).build().unwrap();
+            assert_eq!(packet.payload_len(), 256);
+        }
+
+        #[test]
+        fn too_long() {
+            assert_eq!(Packet::builder(address()).transmit_data().set_bit(2048).build(), Err(Error::TooLong));
+            assert_eq!(Packet::builder(address()).receive_data().set_byte(256, 1).build(), Err(Error::TooLong));
+        }
+    }
+
+    /// Every byte value must survive framing, these sweep the bytes which are special within a frame
+    /// (SYN 0xFF, STX 0x02, ETX 0x03 and DLE 0x10) through each message type:
+    ///   * Each special byte at the first, second, middle, second last and last positions,
+    ///     of data from 1 byte upto the maximum 256, surrounded by 0x00, 0x55 or another special byte.
+    ///   * Each ordered pair of special bytes at the start and end of the data (E.G. DLE ETX).
+    ///   * Maximum length data made entirely of one special byte (the most escaping possible).
+    ///   * Every byte value on its own, and all of them together.
+    ///   * Initialization packets whose node configuration encodes to special bytes.
+    mod roundtrip_check {
+        use super::*;
+        use crate::node_configuration::{CpmegaOptions, CpnodeOptions, node_cards::NodeCard};
+
+        const SPECIAL: [u8; 4] = [0xFF, 0x02, 0x03, 0x10];
+        const LENGTHS: [usize; 8] = [1, 2, 3, 4, 5, 128, 255, 256];
+
+        /// Check a packet of each message type which has data.
+        fn check(data: &[u8]) {
+            let address = Address::try_from_node_address(0).unwrap();
+            let data = Data::try_from(data).unwrap();
+            for packet in [
+                Packet::new_receive_data(address, data),
+                Packet::new_transmit_data(address, data),
+                Packet::new_set(address, data),
+                #[cfg(feature = "experimenter")]
+                Packet::try_new_unknown(address, b'Z', data).unwrap()
+            ] {
+                assert!(packet.roundtrip_check(), "{packet:?}");
+            }
+        }
+
+        #[test]
+        fn without_data() {
+            for node_address in [0, 1, 126, 127] {
+                assert!(Packet::new_poll_request(Address::try_from_node_address(node_address).unwrap()).roundtrip_check());
+            }
+        }
+
+        #[test]
+        fn special_byte_positions() {
+            for len in LENGTHS {
+                for byte in SPECIAL {
+                    for background in [0x00, 0x55].into_iter().chain(SPECIAL) {
+                        for position in [0, 1, len / 2, len.saturating_sub(2), len - 1] {
+                            let mut data = [background; 256];
+                            data[position] = byte;
+                            check(&data[..len]);
+                        }
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn special_byte_pairs() {
+            for len in LENGTHS.into_iter().filter(|len| *len >= 2) {
+                for first in SPECIAL {
+                    for second in SPECIAL {
+                        let mut data = [0; 256];
+                        data[0] = first;
+                        data[1] = second;
+                        check(&data[..len]);
+
+                        let mut data = [0; 256];
+                        data[len - 2] = first;
+                        data[len - 1] = second;
+                        check(&data[..len]);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn all_special() {
+            for byte in SPECIAL {
+                check(&[byte; 256]);
+            }
+        }
+
+        #[test]
+        fn every_byte() {
+            let mut all = [0; 256];
+            for (index, byte) in (0..=u8::MAX).enumerate() {
+                check(&[byte]);
+                all[index] = byte;
+            }
+            check(&all);
+            all.reverse();
+            check(&all);
+        }
+
+        #[test]
+        fn initialization() {
+            let check = |node_sort| {
+                let packet = Packet::new_initialization(Address::try_from_node_address(0).unwrap(), node_sort);
+                assert!(packet.roundtrip_check(), "{packet:?}");
+            };
+            for high in SPECIAL {
+                for low in SPECIAL {
+                    let transmit_delay = u16::from_be_bytes([high, low]);
+                    check(NodeSort::try_new_usic(transmit_delay, &[NodeCard::Input, NodeCard::Output, NodeCard::Input]).unwrap());
+                    check(NodeSort::try_new_susic(transmit_delay, &[NodeCard::Output; 64]).unwrap());
+                    check(NodeSort::try_new_smini(transmit_delay, [0xFF, 0x03, 0xFF, 0x03, 0xFF, 0x03]).unwrap());
+                    check(NodeSort::try_new_cpnode(transmit_delay, CpnodeOptions::from_bits_retain(transmit_delay), 0x02, 0x03).unwrap());
+                    check(NodeSort::try_new_cpmega(transmit_delay, CpmegaOptions::from_bits_retain(transmit_delay), 0x10, 0x02).unwrap());
+                }
+            }
+            // A lone output card encodes to STX, and the count of card sets reaches STX, ETX & DLE
+            for len in 1..=64 {
+                check(NodeSort::try_new_usic(0, &[NodeCard::Output; 64][..len]).unwrap());
+                check(NodeSort::try_new_susic(0, &[NodeCard::Input; 64][..len]).unwrap());
+            }
+        }
+
+        #[test]
+        fn random() {
+            use rand::{SeedableRng, rngs::SmallRng};
+            let mut rng = SmallRng::seed_from_u64(0x0203_10FF);
+            for _ in 0..10_000 {
+                let packet = Packet::random(&mut rng);
+                assert!(packet.roundtrip_check(), "{packet:?}");
+            }
+        }
+    }
+
+    mod try_from_bytes {
+        use super::*;
+
+        #[test]
+        fn valid() {
+            let address = Address::try_from_node_address(1).unwrap();
+            assert_eq!(
+                Packet::try_from(&[0xFF, 0xFF, 0x02, 66, b'T', 0x10, 0x02, 0x10, 0x03, 0x03]),
+                Ok(Packet::new_transmit_data(address, [0x02, 0x03].try_into().unwrap()))
+            );
+            assert_eq!(
+                Packet::try_from(&[0xFF, 0xFF, 0x02, 66, b'P', 0x03][..]),
+                Ok(Packet::new_poll_request(address))
+            );
+        }
+
+        #[test]
+        fn matches_two_step() {
+            let bytes = [0xFF, 0xFF, 0x02, 65, b'R', 0x10, 0x10, 0x00, 0x03];
+            assert_eq!(Packet::try_from(&bytes), RawFrame::try_from(&bytes).unwrap().try_as_packet());
+        }
+
+        #[test]
+        fn too_short() {
+            assert_eq!(Packet::try_from(&[]), Err(DecodeError::TooShort));
+            assert_eq!(Packet::try_from(&[0xFF, 0xFF, 0x02]), Err(DecodeError::TooShort));
+        }
+
+        #[test]
+        fn too_long() {
+            assert_eq!(Packet::try_from(&[0x00; RawFrame::MAX_LEN + 1]), Err(DecodeError::TooLong));
+        }
+
+        #[test]
+        fn invalid_frame() {
+            assert_eq!(Packet::try_from(&[0xFF, 0xFF, 0x02, 65, b'P']), Err(DecodeError::MissingEnd));
+        }
+
+        #[test]
+        fn invalid_packet() {
+            assert_eq!(
+                Packet::try_from(&[0xFF, 0xFF, 0x02, 65, b'q', 0x03]),
+                Err(DecodeError::InvalidPacket { source: Error::InvalidMessageType(b'q') })
+            );
+            assert_eq!(
+                Packet::try_from(&[0xFF, 0xFF, 0x02, 200, b'P', 0x03]),
+                Err(DecodeError::InvalidPacket { source: Error::InvalidUnitAddress(200) })
+            );
+        }
+    }
+
+    mod into_raw_frame {
+        use super::*;
+
+        fn assert_round_trip(packet: &Packet) {
+            let frame = RawFrame::from(packet);
+            assert_eq!(frame, packet.encode_frame());
+            assert_eq!(frame.try_as_packet().as_ref(), Ok(packet));
+        }
+
+        #[test]
+        fn initialization() {
+            let configuration = CpnodeConfiguration::try_new(0, CpnodeOptions::default(), 1, 1).unwrap();
+            assert_round_trip(&Packet::new_initialization(Address::try_from_node_address(1).unwrap(), NodeSort::Cpnode { configuration }));
+        }
+
+        #[test]
+        fn poll_request() {
+            assert_round_trip(&Packet::new_poll_request(Address::try_from_node_address(2).unwrap()));
+        }
+
+        #[test]
+        fn receive_data() {
+            // Includes bytes which need escaping
+            assert_round_trip(&Packet::new_receive_data(Address::try_from_node_address(3).unwrap(), [0x02, 0x03, 0x10].try_into().unwrap()));
+        }
+
+        #[test]
+        fn transmit_data() {
+            assert_round_trip(&Packet::new_transmit_data(Address::try_from_node_address(4).unwrap(), [1, 2, 3].try_into().unwrap()));
+        }
+
+        #[test]
+        fn set() {
+            assert_round_trip(&Packet::new_set(Address::try_from_node_address(6).unwrap(), [4, 5].try_into().unwrap()));
+        }
+
+        #[test]
+        #[cfg(feature = "experimenter")]
+        fn unknown() {
+            assert_round_trip(&Packet::try_new_unknown(Address::try_from_node_address(5).unwrap(), b'A', [1, 2].try_into().unwrap()).unwrap());
+        }
+
+        #[test]
+        #[cfg(feature = "experimenter")]
+        fn text() {
+            assert_round_trip(&Packet::new_text(Address::try_from_node_address(7).unwrap(), "Hello").unwrap());
+        }
+    }
+
     mod try_from_raw_packet {
         use super::*;
 
@@ -1,5 +1,5 @@
 use crate::NodeSort;
-use super::{Error, Data};
+use super::{Error, Data, Raw};
 use log::trace;
 
 /// The payload within a CMRInet `Packet`.
@@ -38,6 +38,15 @@ pub enum Payload {
         message_type: u8,
         /// The packet's body.
         body: Data
+    },
+
+    /// A set packet is sent to a node to set its parameters,
+    /// it isn't used by all layouts and the meaning of the data is node specific.
+    ///
+    /// With the experimenter feature, 'S' packets used to decode as [`Self::Unknown`].
+    Set {
+        /// The parameters being set.
+        data: Data
     }
 }
 
@@ -47,7 +56,7 @@ impl Payload{
     /// # Errors
     ///
     ///   * [`Error::InvalidMessageType`] if the MT byte is invalid.
-    ///   * For `ReceiveData` and `TransmitData` packets:
+    ///   * For `ReceiveData`, `TransmitData` and `Set` packets:
     ///     * [`Error::BodyTooLong`] if the raw data is too long to be a packet.
     ///   * For `Initialization` packets
     ///   *   Anything from [`NodeSort::try_decode`]
@@ -66,6 +75,11 @@ impl Payload{
                     Ok(Self::TransmitData { data })
                 )
             },
+            b'S' => {
+                Data::try_from(&raw[1..(raw.len())]).map_or(Err(Error::BodyTooLong), |data|
+                    Ok(Self::Set { data })
+                )
+            },
             _ => {
                 #[cfg(feature = "experimenter")]
                 if raw[0].is_ascii_uppercase() {
@@ -76,10 +90,41 @@ impl Payload{
         }
     }
 
+    #[cfg(feature = "experimenter")]
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "experimenter")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature experimenter only.**\n\n")]
+    /// Get the text from a text packet (see [`super::Packet::new_text`]),
+    /// `None` if this isn't a text packet or the text isn't ASCII.
+    #[must_use]
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Unknown { message_type: super::Packet::TEXT_MESSAGE_TYPE, body } if body.as_slice().is_ascii() => {
+                core::str::from_utf8(body.as_slice()).ok()
+            },
+            _ => None
+        }
+    }
+
+    #[cfg(feature = "experimenter")]
+    /// The position of the variant (an initialization of an unknown node type counting as a variant of its own)
+    /// when ordering payloads which encode the same, known ones go first.
+    const fn rank(&self) -> u8 {
+        match self {
+            Self::Initialization { node_sort: NodeSort::Unknown { .. } } => 1,
+            Self::Initialization { .. } => 0,
+            Self::PollRequest => 2,
+            Self::ReceiveData { .. } => 3,
+            Self::TransmitData { .. } => 4,
+            Self::Set { .. } => 5,
+            Self::Unknown { .. } => 6
+        }
+    }
+
     /// Encode to an unescaped packet payload
-    pub(super) fn encode(&self) -> Data {
+    /// (a `Raw` rather than `Data` so there's room for the message type as well as a full body).
+    pub(super) fn encode(&self) -> Raw {
         trace!("Payload.encode({self:?})");
-        let mut raw = Data::default();
+        let mut raw = Raw::new();
         match self {
             Self::Initialization { node_sort } => {
                 let _ = raw.push(b'I');
@@ -102,6 +147,12 @@ impl Payload{
                     let _ = raw.push(item);
                 }
             },
+            Self::Set { data } => {
+                let _ = raw.push(b'S');
+                for &item in data {
+                    let _ = raw.push(item);
+                }
+            },
             #[cfg(feature = "experimenter")]
             Self::Unknown { message_type, body } => {
                 let _ = raw.push(*message_type);
@@ -114,6 +165,23 @@ impl Payload{
     }
 }
 
+/// Payloads are ordered by message type and then body.
+impl Ord for Payload {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let ordering = self.encode().as_slice().cmp(other.encode().as_slice());
+        // An unknown payload may encode the same as a different variant.
+        #[cfg(feature = "experimenter")]
+        let ordering = ordering.then_with(|| self.rank().cmp(&other.rank()));
+        ordering
+    }
+}
+
+impl PartialOrd for Payload {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 
 #[allow(clippy::missing_panics_doc, reason = "tests")]
 #[cfg(test)]
@@ -163,6 +231,24 @@ mod tests {
             );
         }
 
+        #[test]
+        fn maximum_length_data() {
+            let data = Data::try_from(&[7; 256]).unwrap();
+            let encoded = Payload::TransmitData { data }.encode();
+            assert_eq!(encoded.len(), 257);
+            assert_eq!(encoded[0], b'T');
+            assert_eq!(encoded[256], 7);
+        }
+
+        #[test]
+        fn set() {
+            let payload = Payload::Set { data: Data::try_from(&[1,2,3]).unwrap() };
+            assert_eq!(
+                payload.encode(),
+                [b'S', 1, 2, 3]
+            );
+        }
+
         #[cfg(feature = "experimenter")]
         #[test]
         fn unknown() {
@@ -215,6 +301,14 @@ mod tests {
             );
         }
 
+        #[test]
+        fn set() {
+            assert_eq!(
+                Payload::try_decode(&[b'S', 1, 2, 3]),
+                Ok(Payload::Set { data: Data::try_from(&[1,2,3]).unwrap() })
+            );
+        }
+
         #[cfg(feature = "experimenter")]
         #[test]
         fn unknown() {
@@ -248,6 +342,33 @@ mod tests {
                 Payload::try_decode(&data),
                 Err(Error::BodyTooLong)
             );
+
+            data[0] = b'S';
+            assert_eq!(
+                Payload::try_decode(&data),
+                Err(Error::BodyTooLong)
+            );
+        }
+
+        #[test]
+        fn initialization_too_short() {
+            assert_eq!(
+                Payload::try_decode(b"I"),
+                Err(Error::TooShort)
+            );
+            assert_eq!(
+                Payload::try_decode(&[b'I', b'C', 0, 0, 0, 0, 1]),
+                Err(Error::InitializationTooShort { node_type: b'C', expected: 7, actual: 6 })
+            );
+        }
+
+        #[cfg(feature = "experimenter")]
+        #[test]
+        fn as_text() {
+            assert_eq!(Payload::Unknown { message_type: b'Y', body: Data::try_from(b"Text").unwrap() }.as_text(), Some("Text"));
+            assert_eq!(Payload::Unknown { message_type: b'Y', body: Data::try_from(&[0xE9]).unwrap() }.as_text(), None);
+            assert_eq!(Payload::Unknown { message_type: b'A', body: Data::try_from(b"Text").unwrap() }.as_text(), None);
+            assert_eq!(Payload::PollRequest.as_text(), None);
         }
 
         #[cfg(not(feature = "experimenter"))]
@@ -260,6 +381,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn set_round_trip() {
+        for data in [&[][..], &[0], &[1, 2, 3], &[0x02, 0x03, 0x10]] {
+            let payload = Payload::Set { data: Data::try_from(data).unwrap() };
+            assert_eq!(Payload::try_decode(payload.encode().as_slice()), Ok(payload));
+        }
+    }
+
     #[cfg(feature = "serde")]
     mod serde {
         use super::*;
@@ -340,6 +469,22 @@ mod tests {
             );
         }
 
+        #[test]
+        fn set() {
+            let data = &[1, 2];
+            let payload =  Payload::Set { data: Data::try_from(data).unwrap() };
+
+            assert_tokens(
+                &payload,
+                &[
+                    Token::StructVariant { name: "Payload", variant: "Set", len: 1 },
+                    Token::Str("data"),
+                    Token::Bytes(data),
+                    Token::StructVariantEnd
+                ]
+            );
+        }
+
         #[cfg(feature = "experimenter")]
         #[test]
         fn unknown() {
@@ -362,9 +507,9 @@ mod tests {
         #[test]
         fn invalid_variant() {
             #[cfg(feature = "experimenter")]
-            let error = "unknown variant `Zzzz`, expected one of `Initialization`, `PollRequest`, `ReceiveData`, `TransmitData`, `Unknown`";
+            let error = "unknown variant `Zzzz`, expected one of `Initialization`, `PollRequest`, `ReceiveData`, `TransmitData`, `Unknown`, `Set`";
             #[cfg(not(feature = "experimenter"))]
-            let error = "unknown variant `Zzzz`, expected one of `Initialization`, `PollRequest`, `ReceiveData`, `TransmitData`";
+            let error = "unknown variant `Zzzz`, expected one of `Initialization`, `PollRequest`, `ReceiveData`, `TransmitData`, `Set`";
 
             let tokens = &[Token::StructVariant { name: "Payload", variant: "Zzzz", len: 0 }];
 
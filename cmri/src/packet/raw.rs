@@ -160,7 +160,7 @@ impl<const N: usize> TryFrom<[u8; N]> for Raw {
 #[cfg(not(feature = "experimenter"))]
 #[expect(clippy::missing_errors_doc)]
 fn check_message_type(byte: u8) -> Result<u8, Error> {
-    if [b'I', b'P', b'R', b'T'].contains(&byte) {
+    if [b'I', b'P', b'R', b'S', b'T'].contains(&byte) {
         Ok(byte)
     } else {
         Err(Error::InvalidMessageType(byte))
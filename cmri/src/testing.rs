@@ -0,0 +1,230 @@
+//! Helpers for writing tests which send/receive CMRInet frames.
+
+use rand_core::RngCore;
+use crate::{Address, NodeSort, frame::Raw, Packet, packet::Data};
+use crate::node_configuration::{CpmegaOptions, CpnodeOptions, node_cards::NodeCard};
+
+/// Whether the frame `a` decodes to the packet `b`,
+/// so tests can assert on received bytes without building the expected frame by hand.
+///
+/// Returns false if `a` isn't a valid frame.
+///
+/// # Example
+///
+/// ```
+/// use cmri::{Address, Packet, testing::packets_equal_ignoring_framing};
+///
+/// let packet = Packet::new_poll_request(Address::try_from_node_address(1).unwrap());
+/// let received = [0xFF, 0xFF, 0x02, 0x42, b'P', 0x03];
+/// assert!(packets_equal_ignoring_framing(&received, &packet));
+/// ```
+#[must_use]
+pub fn packets_equal_ignoring_framing(a: &[u8], b: &Packet) -> bool {
+    Raw::try_from(a)
+        .and_then(|frame| frame.try_as_packet())
+        .is_ok_and(|packet| packet == *b)
+}
+
+
+impl Packet {
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "testing")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature testing only.**\n\n")]
+    /// Generate a random valid packet, E.G. for fuzzing or property testing.
+    ///
+    /// The address, message type (initialization, poll request, receive data, transmit data or set)
+    /// and data are all random, initialization packets get a random `NodeSort` (see [`NodeSort::random`]).
+    /// The packet only depends on the numbers from `rng`, so a seeded `rng` always gives the same packets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::{SeedableRng, rngs::SmallRng};
+    /// use cmri::Packet;
+    ///
+    /// let mut rng = SmallRng::seed_from_u64(1);
+    /// let packet = Packet::random(&mut rng);
+    /// assert_eq!(packet.encode_frame().try_as_packet(), Ok(packet));
+    /// ```
+    #[expect(clippy::missing_panics_doc, reason = "The node address is always valid")]
+    #[must_use]
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        let address = Address::try_from_node_address(below(rng, 128)).expect("Always less than 128");
+        match below(rng, 5) {
+            0 => Self::new_initialization(address, NodeSort::random(rng)),
+            1 => Self::new_poll_request(address),
+            2 => Self::new_receive_data(address, random_data(rng, Data::MAX_LEN)),
+            3 => Self::new_transmit_data(address, random_data(rng, Data::MAX_LEN)),
+            _ => Self::new_set(address, random_data(rng, Data::MAX_LEN))
+        }
+    }
+}
+
+impl NodeSort {
+    #[cfg_attr(any(docsrs, toolchain = "nightly"), doc(cfg(feature = "testing")))]
+    #[cfg_attr(not(toolchain = "nightly"), doc = "**Available on crate feature testing only.**\n\n")]
+    /// Generate a random valid `NodeSort` (USIC, SUSIC, SMINI, CPNODE or CPMEGA), E.G. for fuzzing or property testing.
+    ///
+    /// The transmit delay and the sort's own configuration (cards, oscillating pairs, options and I/O bytes) are all random.
+    #[expect(clippy::missing_panics_doc, reason = "Only valid configurations are generated")]
+    #[must_use]
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        #[expect(clippy::cast_possible_truncation, reason = "Deliberately only the low bits")]
+        let transmit_delay = rng.next_u32() as u16;
+        match below(rng, 5) {
+            sic @ (0 | 1) => {
+                let mut cards = [NodeCard::None; 64];
+                let len = usize::from(below(rng, 65));
+                for card in &mut cards[..len] {
+                    *card = if below(rng, 2) == 0 { NodeCard::Input } else { NodeCard::Output };
+                }
+                if sic == 0 {
+                    Self::try_new_usic(transmit_delay, &cards[..len]).expect("Cards are never after a none")
+                } else {
+                    Self::try_new_susic(transmit_delay, &cards[..len]).expect("Cards are never after a none")
+                }
+            },
+            2 => {
+                // Each of the 24 pairs of bits is either both set or both clear, so set bits are always adjacent.
+                let pairs = rng.next_u32() & 0x00FF_FFFF;
+                let bits = (0..24).filter(|pair| pairs & (1 << pair) != 0).fold(0_u64, |bits, pair| bits | (0b11 << (pair * 2)));
+                let bytes = bits.to_be_bytes();
+                let oscillating_pairs = [bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]];
+                Self::try_new_smini(transmit_delay, oscillating_pairs).expect("Set bits are always in adjacent pairs")
+            },
+            3 => {
+                let (input_bytes, output_bytes) = random_io_bytes(rng, 2, 18);
+                #[expect(clippy::cast_possible_truncation, reason = "Deliberately only the low bits")]
+                let options = CpnodeOptions::from_bits_retain(rng.next_u32() as u16);
+                Self::try_new_cpnode(transmit_delay, options, input_bytes, output_bytes).expect("Between 16 and 144 bits")
+            },
+            _ => {
+                let (input_bytes, output_bytes) = random_io_bytes(rng, 0, 24);
+                #[expect(clippy::cast_possible_truncation, reason = "Deliberately only the low bits")]
+                let options = CpmegaOptions::from_bits_retain(rng.next_u32() as u16);
+                Self::try_new_cpmega(transmit_delay, options, input_bytes, output_bytes).expect("Between 0 and 192 bits")
+            }
+        }
+    }
+}
+
+/// A random number from 0 to `n - 1` (`n` must be at most 256).
+///
+/// There's a slight bias towards lower numbers, which doesn't matter for generating test data.
+#[expect(clippy::cast_possible_truncation, reason = "n is at most 256 so the result always fits")]
+fn below<R: RngCore>(rng: &mut R, n: u16) -> u8 {
+    (rng.next_u32() % u32::from(n)) as u8
+}
+
+/// Random data of a random length (upto `max_len` bytes).
+fn random_data<R: RngCore>(rng: &mut R, max_len: usize) -> Data {
+    let len = usize::try_from(rng.next_u32()).map_or(0, |len| len % (max_len + 1));
+    let mut data = Data::new(len);
+    rng.fill_bytes(data.as_mut_slice());
+    data
+}
+
+/// A random split of between `min` and `max` (inclusive) bytes into inputs and outputs.
+fn random_io_bytes<R: RngCore>(rng: &mut R, min: u8, max: u8) -> (u8, u8) {
+    let total = min + below(rng, u16::from(max - min) + 1);
+    let input_bytes = below(rng, u16::from(total) + 1);
+    (input_bytes, total - input_bytes)
+}
+
+
+#[allow(clippy::missing_panics_doc, reason = "tests")]
+#[cfg(test)]
+mod tests {
+    use crate::Address;
+    use super::*;
+
+    fn poll_request(node_address: u8) -> Packet {
+        Packet::new_poll_request(Address::try_from_node_address(node_address).unwrap())
+    }
+
+    #[test]
+    fn equal() {
+        let packet = poll_request(1);
+        assert!(packets_equal_ignoring_framing(packet.encode_frame().as_slice(), &packet));
+    }
+
+    #[test]
+    fn different_packet() {
+        let frame = poll_request(1).encode_frame();
+        assert!(!packets_equal_ignoring_framing(frame.as_slice(), &poll_request(2)));
+    }
+
+    mod random {
+        use rand::{SeedableRng, rngs::SmallRng};
+        use crate::packet::Payload;
+        use super::*;
+
+        #[test]
+        fn round_trips() {
+            let mut rng = SmallRng::seed_from_u64(0x00C0_FFEE);
+            for _ in 0..1000 {
+                let packet = Packet::random(&mut rng);
+                assert_eq!(packet.encode_frame().try_as_packet(), Ok(packet), "{packet:?}");
+            }
+        }
+
+        #[test]
+        fn deterministic() {
+            let mut a = SmallRng::seed_from_u64(42);
+            let mut b = SmallRng::seed_from_u64(42);
+            for _ in 0..100 {
+                assert_eq!(Packet::random(&mut a), Packet::random(&mut b));
+            }
+        }
+
+        #[test]
+        fn covers_every_kind() {
+            let mut rng = SmallRng::seed_from_u64(7);
+            let mut payloads = [0; 5];
+            let mut sorts = [0; 5];
+            let mut addresses = [false; 128];
+            for _ in 0..1000 {
+                let packet = Packet::random(&mut rng);
+                addresses[usize::from(packet.address().as_node_address())] = true;
+                match packet.payload() {
+                    Payload::Initialization { node_sort } => {
+                        payloads[0] += 1;
+                        match node_sort {
+                            NodeSort::Usic { .. } => sorts[0] += 1,
+                            NodeSort::Susic { .. } => sorts[1] += 1,
+                            NodeSort::Smini { .. } => sorts[2] += 1,
+                            NodeSort::Cpnode { .. } => sorts[3] += 1,
+                            NodeSort::Cpmega { .. } => sorts[4] += 1,
+                            #[cfg(feature = "experimenter")]
+                            NodeSort::Unknown { .. } => unreachable!()
+                        }
+                    },
+                    Payload::PollRequest => payloads[1] += 1,
+                    Payload::ReceiveData { .. } => payloads[2] += 1,
+                    Payload::TransmitData { .. } => payloads[3] += 1,
+                    Payload::Set { .. } => payloads[4] += 1,
+                    #[cfg(feature = "experimenter")]
+                    Payload::Unknown { .. } => unreachable!()
+                }
+            }
+            assert!(payloads.iter().all(|count| *count > 0), "{payloads:?}");
+            assert!(sorts.iter().all(|count| *count > 0), "{sorts:?}");
+            assert!(addresses.iter().filter(|seen| **seen).count() > 100);
+        }
+
+        #[test]
+        fn node_sort_round_trips() {
+            let mut rng = SmallRng::seed_from_u64(0x5EED);
+            for _ in 0..1000 {
+                let node_sort = NodeSort::random(&mut rng);
+                assert_eq!(NodeSort::from_init_bytes(&node_sort.to_init_bytes()), Ok(node_sort), "{node_sort:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn not_a_frame() {
+        let packet = poll_request(1);
+        assert!(!packets_equal_ignoring_framing(&[], &packet));
+        assert!(!packets_equal_ignoring_framing(&[0xFF, 0xFF, 0x02, 0x42, b'P'], &packet)); // No ETX
+    }
+}
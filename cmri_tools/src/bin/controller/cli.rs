@@ -22,16 +22,71 @@ pub fn command() -> clap::Command {
         )
 
         .arg(common::load_nodes())
+
+        .arg(
+            clap::Arg::new("quiet-poll")
+                .long("quiet-poll")
+                .help("Only send outputs to a node when they've changed, reducing the load on the CMRInet")
+                .action(clap::ArgAction::SetTrue)
+        )
+
+        .arg(
+            clap::Arg::new("poll-order")
+                .long("poll-order")
+                .value_name("ADDRESS,...")
+                .value_parser(clap::value_parser!(cmri::Address))
+                .value_delimiter(',')
+                .action(clap::ArgAction::Append)
+                .help("The order to poll nodes in, any not included are polled afterwards in ascending order (e.g. \"5,2,10\")")
+        )
+
+        .arg(
+            clap::Arg::new("poll-weight")
+                .long("poll-weight")
+                .value_name("ADDRESS=WEIGHT")
+                .value_parser(parse_poll_weight)
+                .action(clap::ArgAction::Append)
+                .help("Poll a node WEIGHT times (1-255, default 1) each cycle, e.g. for critical nodes (e.g. \"5=3\")")
+        )
+}
+
+/// Parse an "ADDRESS=WEIGHT" argument.
+///
+/// # Errors
+///
+/// If there's no '=', the address isn't a valid node address (0-127), or the weight isn't a valid `u8`.
+fn parse_poll_weight(value: &str) -> Result<(cmri::Address, u8), String> {
+    let (address, weight) = value.split_once('=').ok_or_else(|| String::from("expected ADDRESS=WEIGHT"))?;
+    let address = address.parse().map_err(|error| format!("{error}: {address:?}"))?;
+    let weight = weight.parse().map_err(|error| format!("invalid weight: {error}"))?;
+    Ok((address, weight))
 }
 
 mod common {
     include!("../../cli/args.rs");
 }
 
+#[allow(clippy::missing_panics_doc, reason="tests")]
 #[cfg(test)]
 mod tests {
     #[test]
     fn verify_command() {
         super::command().debug_assert();
     }
+
+    #[test]
+    fn poll_order() {
+        let matches = super::command().try_get_matches_from(["controller", "--poll-order", "5,2", "--poll-order", "10"]).unwrap();
+        let order = matches.get_many::<cmri::Address>("poll-order").unwrap().map(cmri::Address::as_node_address).collect::<Vec<_>>();
+        assert_eq!(order, [5, 2, 10]);
+        assert!(super::command().try_get_matches_from(["controller", "--poll-order", "128"]).is_err());
+    }
+
+    #[test]
+    fn parse_poll_weight() {
+        assert_eq!(super::parse_poll_weight("5=3"), Ok((cmri::Address::try_from_node_address(5).unwrap(), 3)));
+        assert!(super::parse_poll_weight("5").is_err());
+        assert!(super::parse_poll_weight("128=3").is_err());
+        assert!(super::parse_poll_weight("5=256").is_err());
+    }
 }
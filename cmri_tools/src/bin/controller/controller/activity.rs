@@ -0,0 +1,111 @@
+use std::time::{Duration, Instant};
+use cmri::packet::Data;
+use super::Node;
+
+/// Tracks changes to the nodes' inputs and outputs, so the GUI is only repainted
+/// when something has changed (or at least every interval, to pick up anything else).
+#[derive(Debug)]
+pub struct Activity {
+    interval: Duration,
+    last_repaint: Option<Instant>,
+    last_data: Vec<Option<(Data, Data)>>
+}
+
+impl Activity {
+    #[must_use]
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_repaint: None,
+            last_data: vec![None; 128]
+        }
+    }
+
+    /// Whether a repaint is needed, because a node's inputs or outputs have changed
+    /// (or a node has been added/removed) or it's been interval since the last repaint.
+    pub fn should_repaint(&mut self, nodes: &[Option<Node>; 128], now: Instant) -> bool {
+        let mut changed = false;
+        for (last, node) in self.last_data.iter_mut().zip(nodes) {
+            let data = node.as_ref().map(|node| (node.inputs, node.outputs));
+            let node_changed = match (&*last, &data) {
+                (Some((last_inputs, last_outputs)), Some((inputs, outputs))) => inputs.has_changed(last_inputs) || outputs.has_changed(last_outputs),
+                (None, None) => false,
+                _ => true
+            };
+            if node_changed {
+                *last = data;
+                changed = true;
+            }
+        }
+
+        let due = self.last_repaint.map_or(true, |last| now.duration_since(last) >= self.interval);
+        if changed || due {
+            self.last_repaint = Some(now);
+        }
+        changed || due
+    }
+}
+
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+mod tests {
+    use cmri::{Address, NodeSort};
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(1);
+
+    fn nodes() -> [Option<Node>; 128] {
+        let mut nodes: [Option<Node>; 128] = std::array::from_fn(|_| None);
+        nodes[5] = Some(Node::new(Address::try_from_node_address(5).unwrap(), NodeSort::try_new_smini(0, [0; 6]).unwrap(), None));
+        nodes
+    }
+
+    fn started(nodes: &[Option<Node>; 128]) -> (Activity, Instant) {
+        let mut activity = Activity::new(INTERVAL);
+        let now = Instant::now();
+        assert!(activity.should_repaint(nodes, now), "The first check should always repaint");
+        (activity, now)
+    }
+
+    #[test]
+    fn no_change_within_interval() {
+        let nodes = nodes();
+        let (mut activity, start) = started(&nodes);
+        assert!(!activity.should_repaint(&nodes, start + Duration::from_millis(250)));
+        assert!(!activity.should_repaint(&nodes, start + Duration::from_millis(999)));
+    }
+
+    #[test]
+    fn no_change_after_interval() {
+        let nodes = nodes();
+        let (mut activity, start) = started(&nodes);
+        assert!(activity.should_repaint(&nodes, start + INTERVAL));
+        assert!(!activity.should_repaint(&nodes, start + INTERVAL + Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn inputs_changed() {
+        let mut nodes = nodes();
+        let (mut activity, start) = started(&nodes);
+        nodes[5].as_mut().unwrap().inputs.set_bit(1, true);
+        assert!(activity.should_repaint(&nodes, start + Duration::from_millis(250)));
+        assert!(!activity.should_repaint(&nodes, start + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn outputs_changed() {
+        let mut nodes = nodes();
+        let (mut activity, start) = started(&nodes);
+        nodes[5].as_mut().unwrap().outputs.set_bit(1, true);
+        assert!(activity.should_repaint(&nodes, start + Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn node_removed() {
+        let mut nodes = nodes();
+        let (mut activity, start) = started(&nodes);
+        nodes[5] = None;
+        assert!(activity.should_repaint(&nodes, start + Duration::from_millis(250)));
+    }
+}
@@ -10,65 +10,470 @@ pub use state::State;
 mod node;
 pub use node::Node;
 
+mod poll_policy;
+pub use poll_policy::{PollPolicy, PollAction};
+
+mod activity;
+use activity::Activity;
+
 const PERIOD: std::time::Duration = std::time::Duration::from_millis(250);
 const TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+/// The GUI is repainted at least this often, even if no inputs/outputs have changed.
+const REPAINT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
 
 /// Run a connection - updating nodes with received packets and responding to poll requests.
 ///
+/// When `quiet` is true a node's outputs are only sent when they've changed since last being sent.
+///
 /// # Panics
 ///
 /// If another controller node is detected.
-pub fn run_connection(mut connection: Connection, state: Arc<Mutex<State>>, tokio_handle: &tokio::runtime::Handle) -> tokio::task::JoinHandle<std::io::Result<()>> {
+pub fn run_connection(mut connection: Connection, state: Arc<Mutex<State>>, quiet: bool, tokio_handle: &tokio::runtime::Handle) -> tokio::task::JoinHandle<std::io::Result<()>> {
     tokio_handle.spawn(async move {
         let handle_error = |error: &std::io::Error| {
             error!("{error:?}");
             std::process::exit(1);
         };
 
+        if let Err(error) = initialise_all(&mut connection, &state).await { handle_error(&error); }
+
         let mut period = tokio::time::interval(PERIOD);
+        let mut activity = Activity::new(REPAINT_INTERVAL);
         loop {
             period.tick().await;
+            let cycle = state.lock().await.poll_cycle();
             #[expect(clippy::significant_drop_in_scrutinee)]
-            for i in 0..128 {
+            for i in cycle {
                 if let Some(node) = state.lock().await.nodes[i].as_mut() {
-                    // Initialise if required
-                    if node.to_initialise {
-                        let packet = Packet::new_initialization(node.address, node.sort);
-                        if let Err(error) = connection.send(&packet.encode_frame()).await { handle_error(&error); }
-                    }
+                    update_node(&mut connection, node, quiet, handle_error).await;
+                }
+            }
+            {
+                let state = state.lock().await;
+                if activity.should_repaint(&state.nodes, std::time::Instant::now()) {
+                    state.egui_ctx.request_repaint();
+                }
+            }
+        }
+    })
+}
 
-                    // Poll inputs
-                    let packet = Packet::new_poll_request(node.address);
-                    if let Err(error) = connection.send(&packet.encode_frame()).await { handle_error(&error); }
-                    match tokio::time::timeout(TIMEOUT, connection.receive()).await {
-                        Err(_) => {
-                            warn!("Poll request to node {} timed out after {:?}.", node.address, TIMEOUT);
-                            node.to_initialise = true;
-                        },
-                        Ok(Err(error)) => handle_error(&error),
-                        Ok(Ok(frame)) => {
-                            match frame.try_as_packet() {
-                                Err(error) => warn!("Bad frame received: {error:?}"),
-                                Ok(packet) => {
-                                    if packet.address() == node.address {
-                                        if let Payload::ReceiveData { data } = packet.payload() {
-                                            node.to_initialise = false;
-                                            node.inputs = *data;
-                                        }
-                                    } else {
-                                        panic!("Another controller exists on the CMRInet.");
-                                    }
-                                }
+/// Send the initialization of every node which requires it, before any are polled,
+/// so a whole layout starts up quicker. Progress is reported in the state.
+///
+/// # Errors
+///
+/// If sending to the connection fails.
+async fn initialise_all(connection: &mut Connection, state: &Mutex<State>) -> std::io::Result<()> {
+    let indexes = state.lock().await.nodes.iter()
+        .enumerate()
+        .filter(|(_, node)| node.as_ref().is_some_and(|node| node.to_initialise))
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    let total = indexes.len();
+
+    for (sent, index) in indexes.into_iter().enumerate() {
+        let mut state = state.lock().await;
+        state.initialising = Some((sent, total));
+        if let Some(node) = state.nodes[index].as_mut() {
+            initialise_node(connection, node).await?;
+            node.to_initialise = false;
+        }
+        state.egui_ctx.request_repaint();
+    }
+    state.lock().await.initialising = None;
+    Ok(())
+}
+
+/// Send a node its initialization.
+///
+/// # Errors
+///
+/// If sending to the connection fails.
+async fn initialise_node(connection: &mut Connection, node: &mut Node) -> std::io::Result<()> {
+    let packet = Packet::new_initialization(node.address, node.sort);
+    connection.send(&packet.encode_frame()).await?;
+    node.sent_outputs = None; // Initialising resets the node's outputs
+    Ok(())
+}
+
+/// Initialise (if required), poll, and set the outputs of a node.
+///
+/// Outputs whose length doesn't match the node's output bytes aren't sent,
+/// a warning is logged each time the mismatched length changes.
+///
+/// Errors sending to, or receiving from, the connection are passed to `handle_error`
+/// and the rest of the update is still attempted.
+///
+/// # Panics
+///
+/// If another controller node is detected.
+async fn update_node(connection: &mut Connection, node: &mut Node, quiet: bool, handle_error: impl Fn(&std::io::Error)) {
+    // Initialise if required
+    if node.to_initialise {
+        if let Err(error) = initialise_node(connection, node).await { handle_error(&error); }
+    }
+
+    // Poll inputs
+    let packet = Packet::new_poll_request(node.address);
+    if let Err(error) = connection.send(&packet.encode_frame()).await { handle_error(&error); }
+    match tokio::time::timeout(TIMEOUT, connection.receive()).await {
+        Err(_) => {
+            warn!("Poll request to node {} timed out after {:?}.", node.address, TIMEOUT);
+            if node.poll_policy.on_timeout() == PollAction::Reinitialise {
+                node.to_initialise = true;
+            }
+        },
+        Ok(Err(error)) => handle_error(&error),
+        Ok(Ok(frame)) => {
+            let expected = match frame.try_as_packet() {
+                Err(error) => {
+                    warn!("Bad frame received: {error:?}");
+                    false
+                },
+                Ok(packet) => {
+                    assert!(packet.address() == node.address, "Another controller exists on the CMRInet.");
+                    match packet.payload() {
+                        Payload::ReceiveData { data } if node.sort.accepts_receive_data(data) => {
+                            if !data.is_empty() { // Empty means the inputs haven't changed
+                                node.inputs = *data;
                             }
+                            true
+                        },
+                        Payload::ReceiveData { data } => {
+                            warn!("Node {} sent {} bytes of data, but has {} input bytes.", node.address, data.len(), node.inputs.len());
+                            false
+                        },
+                        payload => {
+                            warn!("Node {} responded to a poll request with {payload:?}.", node.address);
+                            false
                         }
                     }
+                }
+            };
+            match node.poll_policy.on_response(expected) {
+                PollAction::Reinitialise => node.to_initialise = true,
+                PollAction::Continue => if expected {
+                    node.to_initialise = false;
+                }
+            }
+        }
+    }
+
+    // Set outputs, withholding them whilst their length doesn't match the node's
+    if let Some(Err(error)) = node.sort.map_configuration(|configuration| configuration.validate_output_data(&node.outputs)) {
+        if node.mismatched_outputs != Some(node.outputs.len()) {
+            warn!("Not sending outputs to node {} until their length matches: {error}.", node.address);
+            node.mismatched_outputs = Some(node.outputs.len());
+        }
+    } else {
+        node.mismatched_outputs = None;
+        if !quiet || node.sent_outputs.map_or(true, |sent| node.outputs.has_changed(&sent)) {
+            let packet = Packet::new_transmit_data(node.address, node.outputs);
+            if let Err(error) = connection.send(&packet.encode_frame()).await { handle_error(&error); }
+            node.sent_outputs = Some(node.outputs);
+        }
+    }
+}
+
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+mod tests {
+    use cmri::{Address, NodeSort};
+    use super::*;
+
+    fn panic_on_error(error: &std::io::Error) {
+        panic!("{error:?}");
+    }
 
-                    // Set outputs
-                    let packet = Packet::new_transmit_data(node.address, node.outputs);
-                    if let Err(error) = connection.send(&packet.encode_frame()).await { handle_error(&error); }
+    mod initialise_all {
+        use super::*;
+
+        #[tokio::test]
+        #[allow(clippy::significant_drop_tightening)]
+        async fn before_first_poll() {
+            let state = Mutex::new(State::default());
+            let nodes = [5, 10].map(|address| Node::new(Address::try_from_node_address(address).unwrap(), NodeSort::try_new_smini(0, [0; 6]).unwrap(), None));
+            let init = |node: &Node| Packet::new_initialization(node.address, node.sort).encode_frame();
+            let poll = |node: &Node| Packet::new_poll_request(node.address).encode_frame();
+            let receive = |node: &Node| Packet::new_receive_data(node.address, node.inputs).encode_frame();
+            let transmit = |node: &Node| Packet::new_transmit_data(node.address, node.outputs).encode_frame();
+            let stream = tokio_test::io::Builder::new()
+                .write(&init(&nodes[0])).write(&init(&nodes[1]))
+                .write(&poll(&nodes[0])).read(&receive(&nodes[0])).write(&transmit(&nodes[0]))
+                .write(&poll(&nodes[1])).read(&receive(&nodes[1])).write(&transmit(&nodes[1]))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            {
+                let mut state = state.lock().await;
+                for node in nodes {
+                    let index = usize::from(node.address.as_node_address());
+                    state.nodes[index] = Some(node);
                 }
             }
-            state.lock().await.egui_ctx.request_repaint();
+
+            initialise_all(&mut connection, &state).await.unwrap();
+            let mut state = state.lock().await;
+            assert_eq!(state.initialising, None);
+            for index in [5, 10] {
+                let node = state.nodes[index].as_mut().unwrap();
+                assert!(!node.to_initialise);
+                update_node(&mut connection, node, false, panic_on_error).await;
+            }
         }
-    })
+
+        #[tokio::test]
+        async fn skips_initialised() {
+            let state = Mutex::new(State::default());
+            let mut node = Node::new(Address::try_from_node_address(5).unwrap(), NodeSort::try_new_smini(0, [0; 6]).unwrap(), None);
+            node.to_initialise = false;
+            state.lock().await.nodes[5] = Some(node);
+            let mut connection = Connection::new("connection", Box::new(tokio_test::io::Builder::new().build()));
+            initialise_all(&mut connection, &state).await.unwrap();
+        }
+    }
+
+    mod update_node {
+        use super::*;
+
+        fn new_node() -> Node {
+            let mut node = Node::new(Address::try_from_node_address(5).unwrap(), NodeSort::try_new_smini(0, [0; 6]).unwrap(), None);
+            node.to_initialise = false;
+            node
+        }
+
+        fn poll(node: &Node) -> cmri::Frame {
+            Packet::new_poll_request(node.address).encode_frame()
+        }
+
+        fn receive(node: &Node) -> cmri::Frame {
+            Packet::new_receive_data(node.address, node.inputs).encode_frame()
+        }
+
+        fn transmit(node: &Node) -> cmri::Frame {
+            Packet::new_transmit_data(node.address, node.outputs).encode_frame()
+        }
+
+        #[tokio::test]
+        async fn not_quiet() {
+            let mut node = new_node();
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+        }
+
+        #[tokio::test]
+        async fn continues_after_error() {
+            let mut node = new_node();
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read_error(std::io::Error::other("error")).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            let errors = std::cell::Cell::new(0);
+            update_node(&mut connection, &mut node, false, |_| errors.set(errors.get() + 1)).await;
+            assert_eq!(errors.get(), 1);
+            assert_eq!(node.sent_outputs, Some(node.outputs));
+        }
+
+        #[tokio::test]
+        async fn quiet() {
+            let mut node = new_node();
+            let mut changed = new_node();
+            changed.outputs.set_bit(3, true);
+            let stream = tokio_test::io::Builder::new()
+                // First cycle sends outputs
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                // Second cycle has unchanged outputs
+                .write(&poll(&node)).read(&receive(&node))
+                // Third cycle has changed outputs
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&changed))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            update_node(&mut connection, &mut node, true, panic_on_error).await;
+            update_node(&mut connection, &mut node, true, panic_on_error).await;
+            node.outputs.set_bit(3, true);
+            update_node(&mut connection, &mut node, true, panic_on_error).await;
+        }
+
+        #[tokio::test]
+        async fn staged_outputs_sent_on_commit() {
+            let mut node = new_node();
+            let mut changed = new_node();
+            changed.outputs.set_bit(3, true);
+            changed.outputs.set_bit(5, true);
+            let stream = tokio_test::io::Builder::new()
+                // Staged changes aren't sent
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                // Committed changes are sent together
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&changed))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            node.stage_bit(3, true);
+            assert!(node.has_staged_changes());
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            node.stage_bit(5, true);
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            node.commit();
+            assert!(!node.has_staged_changes());
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+        }
+
+        #[tokio::test]
+        async fn commit_keeps_unstaged_output_changes() {
+            let mut node = new_node();
+            let mut changed = new_node();
+            changed.outputs.set_bit(3, true);
+            changed.outputs.set_bit(5, true);
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&changed))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            node.stage_bit(3, true);
+            node.outputs.set_bit(5, true); // As edited in the main window
+            assert!(node.has_staged_changes());
+            node.commit();
+            assert!(!node.has_staged_changes());
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+        }
+
+        #[tokio::test]
+        async fn discarded_outputs_not_sent() {
+            let mut node = new_node();
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            node.stage_bit(3, true);
+            node.discard();
+            assert!(!node.has_staged_changes());
+            node.commit();
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+        }
+
+        #[tokio::test]
+        async fn wrong_length_outputs_not_sent() {
+            let mut node = new_node();
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&receive(&node))
+                .write(&poll(&node)).read(&receive(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            let len = node.outputs.len();
+            node.outputs = cmri::packet::Data::new(len - 1);
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert_eq!(node.mismatched_outputs, Some(len - 1));
+            node.outputs = cmri::packet::Data::new(len + 1);
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert_eq!(node.mismatched_outputs, Some(len + 1));
+            assert_eq!(node.sent_outputs, None);
+        }
+
+        #[tokio::test]
+        async fn outputs_sent_once_length_fixed() {
+            let mut node = new_node();
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&receive(&node))
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            let outputs = node.outputs;
+            node.outputs = cmri::packet::Data::new(outputs.len() - 1);
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            node.outputs = outputs;
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert_eq!(node.mismatched_outputs, None);
+            assert_eq!(node.sent_outputs, Some(outputs));
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn timeout_reinitialises() {
+            let mut node = new_node();
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).wait(TIMEOUT * 2).write(&transmit(&node))
+                .write(&Packet::new_initialization(node.address, node.sort).encode_frame())
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert!(node.to_initialise);
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert!(!node.to_initialise);
+        }
+
+        #[tokio::test]
+        async fn updates_inputs() {
+            let mut node = new_node();
+            let mut reply = new_node();
+            reply.inputs.set_bit(2, true);
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&receive(&reply)).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert_eq!(node.inputs, reply.inputs);
+        }
+
+        #[tokio::test]
+        async fn ignores_wrong_length_inputs() {
+            let mut node = new_node();
+            let reply = Packet::new_receive_data(node.address, [0xFF; 2].try_into().unwrap()).encode_frame(); // A SMINI has 3 input bytes
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&reply).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert_eq!(node.inputs.as_slice(), &[0, 0, 0]);
+        }
+
+        #[tokio::test]
+        async fn unexpected_responses_reinitialise() {
+            let mut node = new_node();
+            node.poll_policy = PollPolicy::new(2);
+            let reply = Packet::new_receive_data(node.address, [0xFF; 2].try_into().unwrap()).encode_frame(); // A SMINI has 3 input bytes
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&reply).write(&transmit(&node))
+                .write(&poll(&node)).read(&reply).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert!(!node.to_initialise);
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert!(node.to_initialise);
+        }
+
+        #[tokio::test]
+        async fn empty_inputs_when_eot_allowed() {
+            let sort = NodeSort::try_new_cpnode(0, cmri::node_configuration::CpnodeOptions::CAN_SEND_EOT_ON_NO_INPUTS_CHANGED, 1, 1).unwrap();
+            let mut node = Node::new(Address::try_from_node_address(5).unwrap(), sort, None);
+            node.to_initialise = false;
+            node.inputs.set_bit(0, true);
+            let reply = Packet::new_receive_data(node.address, cmri::packet::Data::new(0)).encode_frame();
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&reply).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            update_node(&mut connection, &mut node, false, panic_on_error).await;
+            assert_eq!(node.inputs.as_slice(), &[1]); // Unchanged
+        }
+
+        #[tokio::test]
+        async fn quiet_resends_after_initialization() {
+            let mut node = new_node();
+            let stream = tokio_test::io::Builder::new()
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                .write(&Packet::new_initialization(node.address, node.sort).encode_frame())
+                .write(&poll(&node)).read(&receive(&node)).write(&transmit(&node))
+                .build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            update_node(&mut connection, &mut node, true, panic_on_error).await;
+            node.to_initialise = true;
+            update_node(&mut connection, &mut node, true, panic_on_error).await;
+        }
+    }
 }
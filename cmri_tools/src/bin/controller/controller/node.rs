@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
 use cmri::{Address, NodeSort, packet::Data};
 use cmri_tools::file;
+use super::PollPolicy;
 
 #[derive(Eq, PartialEq)]
 pub struct Node {
@@ -10,6 +12,12 @@ pub struct Node {
     pub(crate) to_initialise: bool,
     pub(crate) inputs: Data,
     pub(crate) outputs: Data,
+    /// Bits changed in the node's window, by index, which haven't been committed to outputs yet.
+    pub(crate) staged: BTreeMap<usize, bool>,
+    pub(crate) sent_outputs: Option<Data>,
+    /// The length of outputs last withheld for not matching the node's output bytes, so it's only warned about once.
+    pub(crate) mismatched_outputs: Option<usize>,
+    pub(crate) poll_policy: PollPolicy
 }
 
 impl Node {
@@ -23,9 +31,47 @@ impl Node {
             labels: file::Labels::default(),
             to_initialise: true,
             inputs: Data::new(sort.configuration().input_bytes() as usize),
-            outputs: Data::new(sort.configuration().output_bytes() as usize)
+            outputs: Data::new(sort.configuration().output_bytes() as usize),
+            staged: BTreeMap::new(),
+            sent_outputs: None,
+            mismatched_outputs: None,
+            poll_policy: PollPolicy::default()
         }
     }
+
+    /// Change a bit of the staged outputs, it isn't sent to the node until committed.
+    pub fn stage_bit(&mut self, index: usize, value: bool) {
+        self.staged.insert(index, value);
+    }
+
+    /// Whether the staged outputs differ from those being sent to the node.
+    #[must_use]
+    pub fn has_staged_changes(&self) -> bool {
+        self.staged.iter().any(|(&index, &value)| index < self.outputs.len() * 8 && self.outputs.get_bit(index) != value)
+    }
+
+    /// The outputs which would be sent to the node if the staged changes were committed.
+    #[must_use]
+    pub fn staged_outputs(&self) -> Data {
+        let mut outputs = self.outputs;
+        for (&index, &value) in &self.staged {
+            if index < outputs.len() * 8 {
+                outputs.set_bit(index, value);
+            }
+        }
+        outputs
+    }
+
+    /// Apply all the staged changes at once, they're sent to the node on its next update.
+    pub fn commit(&mut self) {
+        self.outputs = self.staged_outputs();
+        self.staged.clear();
+    }
+
+    /// Throw away the staged changes.
+    pub fn discard(&mut self) {
+        self.staged.clear();
+    }
 }
 
 impl std::fmt::Debug for Node {
@@ -38,6 +84,10 @@ impl std::fmt::Debug for Node {
          .field("to_initialise", &self.to_initialise)
          .field("inputs", &self.inputs.as_slice())
          .field("outputs", &self.outputs.as_slice())
+         .field("staged", &self.staged)
+         .field("sent_outputs", &self.sent_outputs.as_ref().map(Data::as_slice))
+         .field("mismatched_outputs", &self.mismatched_outputs)
+         .field("poll_policy", &self.poll_policy)
          .finish()
     }
 }
@@ -51,7 +101,11 @@ impl From<file::Node> for Node {
             labels: value.labels,
             to_initialise: true,
             inputs: Data::new(value.sort.configuration().input_bytes() as usize),
-            outputs: Data::new(value.sort.configuration().output_bytes() as usize)
+            outputs: Data::new(value.sort.configuration().output_bytes() as usize),
+            staged: BTreeMap::new(),
+            sent_outputs: None,
+            mismatched_outputs: None,
+            poll_policy: PollPolicy::default()
         }
     }
 }
@@ -0,0 +1,104 @@
+/// What should happen to a node after it's been polled.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PollAction {
+    /// The node should be sent an initialization packet before it's next polled.
+    Reinitialise,
+    /// Carry on polling the node.
+    Continue
+}
+
+/// Decides when a node needs reinitialising, based upon how it responds to being polled.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PollPolicy {
+    failures_allowed: u8,
+    failures: u8
+}
+
+impl PollPolicy {
+    /// Create a new policy which reinitialises a node after `failures_allowed` consecutive
+    /// timeouts or unexpected responses.
+    #[must_use]
+    pub const fn new(failures_allowed: u8) -> Self {
+        Self { failures_allowed, failures: 0 }
+    }
+
+    /// The node responded to a poll request, expected is false if the response
+    /// wasn't the node's inputs (E.G. a bad frame or the wrong number of input bytes).
+    pub const fn on_response(&mut self, expected: bool) -> PollAction {
+        if expected {
+            self.failures = 0;
+            PollAction::Continue
+        } else {
+            self.on_failure()
+        }
+    }
+
+    /// The node didn't respond to a poll request in time.
+    pub const fn on_timeout(&mut self) -> PollAction {
+        self.on_failure()
+    }
+
+    const fn on_failure(&mut self) -> PollAction {
+        self.failures = self.failures.saturating_add(1);
+        if self.failures >= self.failures_allowed {
+            self.failures = 0;
+            PollAction::Reinitialise
+        } else {
+            PollAction::Continue
+        }
+    }
+}
+
+impl Default for PollPolicy {
+    /// Reinitialise a node as soon as it fails to respond as expected.
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_reinitialises_on_first_timeout() {
+        let mut policy = PollPolicy::default();
+        assert_eq!(policy.on_response(true), PollAction::Continue);
+        assert_eq!(policy.on_timeout(), PollAction::Reinitialise);
+    }
+
+    #[test]
+    fn timeout_then_reinitialise() {
+        let mut policy = PollPolicy::new(3);
+        assert_eq!(policy.on_timeout(), PollAction::Continue);
+        assert_eq!(policy.on_timeout(), PollAction::Continue);
+        assert_eq!(policy.on_timeout(), PollAction::Reinitialise);
+
+        // Count starts again after reinitialising
+        assert_eq!(policy.on_timeout(), PollAction::Continue);
+    }
+
+    #[test]
+    fn recovery() {
+        let mut policy = PollPolicy::new(2);
+        assert_eq!(policy.on_timeout(), PollAction::Continue);
+        assert_eq!(policy.on_response(true), PollAction::Continue);
+
+        // Response resets the count of timeouts
+        assert_eq!(policy.on_timeout(), PollAction::Continue);
+        assert_eq!(policy.on_timeout(), PollAction::Reinitialise);
+    }
+
+    #[test]
+    fn unexpected_response_then_reinitialise() {
+        let mut policy = PollPolicy::new(2);
+        assert_eq!(policy.on_response(false), PollAction::Continue);
+        assert_eq!(policy.on_response(false), PollAction::Reinitialise);
+
+        // Unexpected responses and timeouts count together
+        assert_eq!(policy.on_response(false), PollAction::Continue);
+        assert_eq!(policy.on_timeout(), PollAction::Reinitialise);
+    }
+}
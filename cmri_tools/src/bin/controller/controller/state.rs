@@ -1,9 +1,15 @@
+use std::collections::HashMap;
+use cmri::Address;
 use cmri_tools::file;
 use super::Node;
 
 pub struct State {
     pub(crate) nodes: [Option<Node>; 128],
-    pub(crate) egui_ctx: egui::Context
+    /// (sent, total) whilst every node's initialization is being sent at startup.
+    pub(crate) initialising: Option<(usize, usize)>,
+    pub(crate) egui_ctx: egui::Context,
+    poll_order: Vec<Address>,
+    poll_weights: HashMap<Address, u8>
 }
 
 impl State {
@@ -33,12 +39,48 @@ impl State {
     pub fn available_node_addresses(&self) -> Vec<u8> {
         (0..128).filter(|i| self.nodes[*i as usize].is_none()).collect()
     }
+
+    /// Set the order nodes are polled in, any not included are polled afterwards
+    /// in ascending order (the default is ascending order).
+    pub fn set_poll_order(&mut self, order: Vec<Address>) {
+        self.poll_order = order;
+    }
+
+    /// Set how many times a node is polled each cycle (the default is 1, 0 is treated as 1),
+    /// E.G. so critical nodes are polled more often.
+    pub fn set_poll_weight(&mut self, address: Address, weight: u8) {
+        self.poll_weights.insert(address, weight.max(1));
+    }
+
+    /// The indexes of the nodes to poll in a cycle, in order.
+    ///
+    /// A node with a weight of N is polled in each of the first N passes through the poll order,
+    /// so its polls are spread through the cycle.
+    pub fn poll_cycle(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(128);
+        for address in self.poll_order.iter().copied().chain(Address::iter()) {
+            if !order.contains(&address) {
+                order.push(address);
+            }
+        }
+        order.retain(|address| self.nodes[usize::from(address.as_node_address())].is_some());
+
+        let weight = |address: &Address| self.poll_weights.get(address).copied().unwrap_or(1);
+        let passes = order.iter().map(weight).max().unwrap_or_default();
+        (0..passes)
+            .flat_map(|pass| order.iter().filter(move |address| weight(address) > pass))
+            .map(|address| usize::from(address.as_node_address()))
+            .collect()
+    }
 }
 
 impl std::fmt::Debug for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("State")
          .field("nodes", &self.nodes)
+         .field("initialising", &self.initialising)
+         .field("poll_order", &self.poll_order)
+         .field("poll_weights", &self.poll_weights)
          .finish_non_exhaustive()
     }
 }
@@ -47,7 +89,10 @@ impl Default for State {
     fn default() -> Self {
         Self {
             nodes: std::array::from_fn(|_| None),
-            egui_ctx: egui::Context::default()
+            initialising: None,
+            egui_ctx: egui::Context::default(),
+            poll_order: Vec::new(),
+            poll_weights: HashMap::new()
         }
     }
 }
@@ -56,7 +101,8 @@ impl Default for State {
 #[allow(clippy::missing_panics_doc, reason="tests")]
 #[cfg(test)]
 mod tests {
-    use cmri::{Address, NodeSort, packet::Data};
+    use cmri::{NodeSort, packet::Data};
+    use super::super::PollPolicy;
     use super::*;
 
     #[test]
@@ -78,13 +124,66 @@ mod tests {
             labels: file::Labels::default(),
             to_initialise: false,
             inputs: Data::default(),
-            outputs: Data::default()
+            outputs: Data::default(),
+            staged: std::collections::BTreeMap::new(),
+            sent_outputs: None,
+            mismatched_outputs: None,
+            poll_policy: PollPolicy::default()
         });
 
         state.reset();
         assert_eq!(state.nodes, State::default().nodes);
     }
 
+    mod poll_cycle {
+        use super::*;
+
+        fn state(addresses: &[u8]) -> State {
+            let mut state = State::default();
+            for address in addresses {
+                let address = Address::try_from_node_address(*address).unwrap();
+                state.nodes[usize::from(address.as_node_address())] = Some(Node::new(address, NodeSort::try_new_smini(0, [0; 6]).unwrap(), None));
+            }
+            state
+        }
+
+        fn address(address: u8) -> Address {
+            Address::try_from_node_address(address).unwrap()
+        }
+
+        #[test]
+        fn default_ascending() {
+            assert_eq!(state(&[10, 2, 127, 0]).poll_cycle(), [0, 2, 10, 127]);
+            assert!(State::default().poll_cycle().is_empty());
+        }
+
+        #[test]
+        fn custom_order() {
+            let mut state = state(&[1, 2, 3, 4]);
+            state.set_poll_order(vec![address(3), address(50), address(1), address(3)]);
+            assert_eq!(state.poll_cycle(), [3, 1, 2, 4]); // Missing nodes skipped, unlisted nodes last
+        }
+
+        #[test]
+        fn weighted_polled_more_often() {
+            let mut state = state(&[1, 2, 3]);
+            state.set_poll_weight(address(2), 3);
+            let cycle = state.poll_cycle();
+            assert_eq!(cycle, [1, 2, 3, 2, 2]);
+            let count = |index| cycle.iter().filter(|i| **i == index).count();
+            assert_eq!(count(2), 3);
+            assert_eq!(count(1), 1);
+            assert_eq!(count(3), 1);
+        }
+
+        #[test]
+        fn zero_weight_still_polled() {
+            let mut state = state(&[1, 2]);
+            state.set_poll_weight(address(2), 0);
+            assert_eq!(state.poll_cycle(), [1, 2]);
+        }
+    }
+
     #[test]
     fn load_nodes() {
         let sort = cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap();
@@ -110,7 +209,11 @@ mod tests {
             labels: file::Labels::default(),
             to_initialise: true,
             inputs: Data::try_from(&[1]).unwrap(),
-            outputs: Data::try_from(&[2]).unwrap()
+            outputs: Data::try_from(&[2]).unwrap(),
+            staged: std::collections::BTreeMap::new(),
+            sent_outputs: None,
+            mismatched_outputs: None,
+            poll_policy: PollPolicy::default()
         });
         state.nodes[20] = Some(Node {
             address: Address::try_from_node_address(10).unwrap(),
@@ -119,7 +222,11 @@ mod tests {
             labels: file::Labels::default(),
             to_initialise: true,
             inputs: Data::new(3),
-            outputs: Data::new(6)
+            outputs: Data::new(6),
+            staged: std::collections::BTreeMap::new(),
+            sent_outputs: None,
+            mismatched_outputs: None,
+            poll_policy: PollPolicy::default()
         });
         state.load_nodes(nodes);
 
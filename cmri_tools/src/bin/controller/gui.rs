@@ -6,6 +6,7 @@ use cmri_tools::{file, gui};
 use crate::controller::{State, Node, run_connection};
 
 pub const APP_TITLE: &str = "CMRInet Controller";
+const NODES_PER_ROW: usize = 1;
 
 #[expect(clippy::unwrap_used, clippy::missing_panics_doc)]
 pub fn run(cli_args: &clap::ArgMatches, tokio_handle: tokio::runtime::Handle) {
@@ -23,7 +24,16 @@ pub fn run(cli_args: &clap::ArgMatches, tokio_handle: tokio::runtime::Handle) {
     }
     let connection_state = tokio_handle.block_on(async { gui::connection::State::new(cli_args) });
     let file_path = cli_args.get_one::<std::path::PathBuf>("load-nodes").cloned();
+    let quiet_poll = cli_args.get_flag("quiet-poll");
     let state = Arc::new(Mutex::new(State::default()));
+    if let Some(order) = cli_args.get_many::<cmri::Address>("poll-order") {
+        state.blocking_lock().set_poll_order(order.copied().collect());
+    }
+    if let Some(weights) = cli_args.get_many::<(cmri::Address, u8)>("poll-weight") {
+        for (address, weight) in weights {
+            state.blocking_lock().set_poll_weight(*address, *weight);
+        }
+    }
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1200.0, 600.0]),
@@ -46,6 +56,7 @@ pub fn run(cli_args: &clap::ArgMatches, tokio_handle: tokio::runtime::Handle) {
                 show_nodes,
                 connection_state,
                 file_path,
+                quiet_poll,
                 add_node: AddNode::default(),
                 modal,
                 tokio_handle
@@ -60,6 +71,7 @@ struct App {
     show_nodes: [bool; 128],
     connection_state: gui::connection::State,
     file_path: Option<std::path::PathBuf>,
+    quiet_poll: bool,
     add_node: AddNode,
     modal: egui_modal::Modal,
     tokio_handle: tokio::runtime::Handle
@@ -106,36 +118,44 @@ impl eframe::App for App {
 
             if running {
                 let mut state = self.state.blocking_lock();
+                if let Some((sent, total)) = state.initialising {
+                    #[expect(clippy::cast_precision_loss, reason = "There's at most 128 nodes")]
+                    let progress = sent as f32 / total as f32;
+                    ui.add(egui::ProgressBar::new(progress).text(format!("Initialising nodes {sent}/{total}")));
+                }
                 egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
                     let per_row = 4;
+                    let present = std::array::from_fn(|index| state.nodes[index].is_some());
                     egui::Grid::new("list")
                         .spacing([0.0, 64.0])
                         .show(ui, |ui| {
-                            for node in state.nodes.iter_mut().filter_map(|a| a.as_mut()) {
-                                let title = format!(
-                                    "Node {:3}{} [{}]",
-                                    node.address.as_node_address(),
-                                    node.name.as_ref().map(|name| format!(" ({name})")).unwrap_or_default(),
-                                    node.sort
-                                );
-                                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
-                                    if ui.heading(title).on_hover_text("Click to open in new window").clicked() {
-                                        let index = usize::from(node.address.as_node_address());
-                                        self.show_nodes[index] = true;
-                                    }
-                                    egui::Grid::new(node.address)
-                                        .spacing([32.0, 0.0])
-                                        .show(ui, |ui| {
-                                            ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
-                                                gui::list_of_bytes(ui, per_row, "Inputs", gui::ReadOnly(&node.inputs), &node.labels.inputs);
+                            for row in gui::node_grid::layout(&present, NODES_PER_ROW) {
+                                for index in row {
+                                    let Some(node) = state.nodes[index].as_mut() else { continue };
+                                    let title = format!(
+                                        "Node {:3}{} [{}]",
+                                        node.address.as_node_address(),
+                                        node.name.as_ref().map(|name| format!(" ({name})")).unwrap_or_default(),
+                                        node.sort
+                                    );
+                                    ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                                        if ui.heading(title).on_hover_text("Click to open in new window").clicked() {
+                                            self.show_nodes[index] = true;
+                                        }
+                                        egui::Grid::new(node.address)
+                                            .spacing([32.0, 0.0])
+                                            .show(ui, |ui| {
+                                                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                                                    gui::list_of_bytes(ui, per_row, "Inputs", gui::ReadOnly(&node.inputs), &node.labels.inputs);
+                                                });
+                                                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                                                    gui::list_of_bytes(ui, per_row, "Outputs", gui::Mutable(&mut node.outputs), &node.labels.outputs);
+                                                });
                                             });
-                                            ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
-                                                gui::list_of_bytes(ui, per_row, "Outputs", gui::Mutable(&mut node.outputs), &node.labels.outputs);
-                                            });
-                                        });
-                                });
+                                    });
+                                }
                                 ui.end_row();
-                            };
+                            }
                         });
                 });
                 for (index, show) in self.show_nodes.iter_mut().enumerate().filter(|(_, b)| **b) {
@@ -146,13 +166,14 @@ impl eframe::App for App {
             } else if let Some(connection) = self.connection_state.try_get_connection() {
                 self.title = format!("{}: {}", APP_TITLE, connection.name());
                 ctx.send_viewport_cmd(egui::ViewportCommand::Title(self.title.clone()));
-                run_connection(connection, self.state.clone(), &self.tokio_handle);
                 if let Some(file_path) = self.file_path.as_ref() {
                     match file::load_nodes(file_path) {
                         Err(error) => gui::modal_error(&self.modal, &error),
                         Ok(nodes) => self.state.blocking_lock().load_nodes(nodes)
                     }
                 }
+                // After loading the nodes, so they're all initialised before polling starts
+                run_connection(connection, self.state.clone(), self.quiet_poll, &self.tokio_handle);
             }
         });
     }
@@ -179,7 +200,22 @@ impl App {
                     });
                     strip.cell(|ui| {
                         ui.heading("Outputs");
-                        gui::list_of_bits(ui, gui::Mutable(&mut node.outputs), &node.labels.outputs);
+                        ui.add_enabled_ui(node.has_staged_changes(), |ui| {
+                            ui.horizontal(|ui| {
+                                if ui.button("Commit").on_hover_text("Send all the changes to the node").clicked() {
+                                    node.commit();
+                                }
+                                if ui.button("Discard").clicked() {
+                                    node.discard();
+                                }
+                            });
+                        });
+                        let before = node.staged_outputs();
+                        let mut staged = before;
+                        gui::list_of_bits(ui, gui::Mutable(&mut staged), &node.labels.outputs);
+                        for index in staged.changed_bits(&before) {
+                            node.stage_bit(index, staged.get_bit(index));
+                        }
                     });
                 });
         });
@@ -277,7 +313,7 @@ impl AddNode {
                     let node_sort: Result<cmri::NodeSort, cmri::node_configuration::InvalidConfigurationError> = match self.config_index {
                         0 => cmri::NodeSort::try_new_usic(0, &self.configs.0).map_err(Into::into),
                         1 => cmri::NodeSort::try_new_usic(0, &self.configs.1).map_err(Into::into),
-                        2 => cmri::NodeSort::try_new_smini(0, Self::build_oscillating_pairs(&self.configs.2)).map_err(Into::into),
+                        2 => cmri::node_configuration::SminiConfiguration::try_from_bits(0, &self.configs.2).map(|configuration| cmri::NodeSort::Smini { configuration }).map_err(Into::into),
                         3 => cmri::NodeSort::try_new_cpnode(0, cmri::node_configuration::CpnodeOptions::default(), self.configs.3.0, self.configs.3.1).map_err(Into::into),
                         4 => cmri::NodeSort::try_new_cpmega(0, cmri::node_configuration::CpmegaOptions::default(), self.configs.4.0, self.configs.4.1).map_err(Into::into),
                         _ => unreachable!()
@@ -346,7 +382,7 @@ impl AddNode {
                     "{} input cards, {} output cards, {} slots available. ({} inputs and {} outputs)",
                     cards.input_cards(),
                     cards.output_cards(),
-                    64 - cards.len(),
+                    cards.remaining_capacity(),
                     cards.input_cards() * bits,
                     cards.output_cards() * bits
                 ));
@@ -450,8 +486,7 @@ impl AddNode {
                 });
             });
 
-        let pairs = Self::build_oscillating_pairs(config);
-        if let Err(error) = cmri::node_configuration::SminiConfiguration::get_oscillating_pairs_count(&pairs) {
+        if let Err(error) = cmri::node_configuration::SminiConfiguration::try_from_bits(0, config) {
             ui.label(egui::RichText::new(error.to_string()).color(egui::Color32::RED).strong());
         }
     }
@@ -487,17 +522,6 @@ impl AddNode {
             ui.label(egui::RichText::new(message).color(egui::Color32::RED).strong());
         }
     }
-
-    fn build_oscillating_pairs(bits: &[bool]) -> [u8; 6] {
-        let mut oscillating_pairs = [0; 6];
-        for (i, byte) in oscillating_pairs.iter_mut().enumerate() {
-            for j in 0..8 {
-                *byte >>= 1;
-                if bits[(i * 8) + j] { *byte += 0b1000_0000; }
-            }
-        }
-        oscillating_pairs
-    }
 }
 
 impl Default for AddNode {
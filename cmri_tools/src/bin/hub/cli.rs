@@ -7,34 +7,213 @@ pub fn command() -> clap::Command {
         .next_line_help(true)
         .group(
             clap::ArgGroup::new("connection")
-                .args(["serial", "network", "server"])
+                .args(["serial", "network", "server", "websocket", "replay"])
                 .multiple(true)
         )
-        .arg(common::serial().action(clap::ArgAction::Append))
-        .arg(common::network().action(clap::ArgAction::Append))
-        .arg(
-            clap::Arg::new("server")
-                .long("server")
-                .value_name("ADDRESS:PORT")
-                .value_hint(clap::ValueHint::Hostname)
-                .help("Start a TCP server and wait for connections on ADDRESS:PORT (e.g. \"127.0.0.1:7878\")")
-        )
-        .arg(
-            clap::Arg::new("gui")
-                .long("no-gui")
-                .help("Don't show the graphical user interface")
-                .action(clap::ArgAction::SetFalse)
-        )
+        .args(connection_args())
+        .args(routing_args())
+        .args(frame_args())
+        .args(output_args())
+}
+
+/// The connections to make, and servers to wait for connections on.
+fn connection_args() -> [clap::Arg; 7] {
+    [
+        common::serial().action(clap::ArgAction::Append),
+        common::network().action(clap::ArgAction::Append),
+        clap::Arg::new("reconnect")
+            .long("reconnect")
+            .requires("network")
+            .help("Keep trying to (re)connect to TCP servers (--network), waiting longer between each failed attempt")
+            .action(clap::ArgAction::SetTrue),
+        clap::Arg::new("server")
+            .long("server")
+            .value_name("ADDRESS:PORT")
+            .value_hint(clap::ValueHint::Hostname)
+            .help("Start a TCP server and wait for connections on ADDRESS:PORT (e.g. \"127.0.0.1:7878\")"),
+        clap::Arg::new("websocket")
+            .long("websocket")
+            .value_name("ADDRESS:PORT")
+            .value_hint(clap::ValueHint::Hostname)
+            .help("Start a WebSocket server and wait for connections on ADDRESS:PORT, each binary message carries one frame (e.g. \"127.0.0.1:7879\")"),
+        clap::Arg::new("replay")
+            .long("replay")
+            .value_name("FILE")
+            .value_hint(clap::ValueHint::FilePath)
+            .value_parser(clap::value_parser!(std::path::PathBuf))
+            .action(clap::ArgAction::Append)
+            .help("Replay a recording (see the monitor's --record) as a connection, which disconnects at the end of the recording"),
+        common::speed()
+    ]
+}
+
+/// Which connections each frame is sent to.
+fn routing_args() -> [clap::Arg; 4] {
+    [
+        clap::Arg::new("priority")
+            .long("priority")
+            .value_name("CONNECTION=PRIORITY")
+            .value_parser(parse_priority)
+            .action(clap::ArgAction::Append)
+            .help("Set the priority (0-255, default 0) of a connection, frames are sent to higher priority connections first (e.g. \"/dev/ttyACM0=10\")"),
+        clap::Arg::new("group")
+            .long("group")
+            .value_name("CONNECTION=GROUP")
+            .value_parser(parse_group)
+            .action(clap::ArgAction::Append)
+            .help("Put a connection in a group (0-255), its frames are only sent to connections in the same group or without a group (e.g. \"/dev/ttyACM0=1\")"),
+        clap::Arg::new("filter")
+            .long("filter")
+            .value_name("CONNECTION=FIRST-LAST")
+            .value_parser(parse_filter)
+            .action(clap::ArgAction::Append)
+            .help("Only send a connection frames for nodes with addresses FIRST to LAST (0-127), can be given more than once for a connection (e.g. \"/dev/ttyACM0=0-63\")"),
+        clap::Arg::new("echo")
+            .long("echo")
+            .value_name("CONNECTION")
+            .action(clap::ArgAction::Append)
+            .help("Send a connection the frames it sends, as well as to the other connections (e.g. for test loops)")
+    ]
+}
+
+/// How frames are read, and which are dropped.
+fn frame_args() -> [clap::Arg; 4] {
+    [
+        clap::Arg::new("read-buffer")
+            .long("read-buffer")
+            .value_name("BYTES")
+            .value_parser(clap::value_parser!(u16).range(1..))
+            .help("Size of the buffer used when reading from each connection (default 128), the bytes per read statistics help when tuning this"),
+        clap::Arg::new("replay-on-connect")
+            .long("replay-on-connect")
+            .help("Send the last transmit and receive data for each node to connections when they join, so they get the current state immediately")
+            .action(clap::ArgAction::SetTrue),
+        clap::Arg::new("drop-invalid-types")
+            .long("drop-invalid-types")
+            .help("Drop frames with an invalid message type, instead of sending them to the other connections")
+            .action(clap::ArgAction::SetTrue),
+        clap::Arg::new("loop-guard")
+            .long("loop-guard")
+            .value_name("MILLISECONDS")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Drop a frame if an identical one was sent within the last MILLISECONDS (e.g. to stop frames echoing between cross connected hubs)")
+    ]
+}
+
+/// How the hub is shown.
+fn output_args() -> [clap::Arg; 2] {
+    [
+        clap::Arg::new("json")
+            .long("json")
+            .requires("gui")
+            .help("When not showing the graphical user interface, print the statistics every second as a line of JSON")
+            .action(clap::ArgAction::SetTrue),
+        clap::Arg::new("gui")
+            .long("no-gui")
+            .help("Don't show the graphical user interface")
+            .action(clap::ArgAction::SetFalse)
+    ]
+}
+
+/// Parse a "CONNECTION=PRIORITY" argument.
+///
+/// # Errors
+///
+/// If there's no '=', or the priority isn't a valid `u8`.
+fn parse_priority(value: &str) -> Result<(String, u8), String> {
+    parse_connection_u8(value, "PRIORITY")
+}
+
+/// Parse a "CONNECTION=GROUP" argument.
+///
+/// # Errors
+///
+/// If there's no '=', or the group isn't a valid `u8`.
+fn parse_group(value: &str) -> Result<(String, u8), String> {
+    parse_connection_u8(value, "GROUP")
+}
+
+/// Parse a "CONNECTION=FIRST-LAST" argument, where FIRST & LAST are node addresses.
+///
+/// # Errors
+///
+/// If there's no '=' or '-', either address isn't valid (0-127), or FIRST is after LAST.
+fn parse_filter(value: &str) -> Result<(String, (cmri::Address, cmri::Address)), String> {
+    let (name, range) = value.rsplit_once('=').ok_or_else(|| String::from("expected CONNECTION=FIRST-LAST"))?;
+    let (first, last) = range.split_once('-').ok_or_else(|| String::from("expected CONNECTION=FIRST-LAST"))?;
+    let parse = |address: &str| address.parse::<cmri::Address>().map_err(|error| format!("{error}: {address}"));
+    let (first, last) = (parse(first)?, parse(last)?);
+    if first > last {
+        return Err(format!("{first} is after {last}"));
+    }
+    Ok((name.to_string(), (first, last)))
+}
+
+/// Parse a "CONNECTION=VALUE" argument, where value is a `u8`.
+///
+/// # Errors
+///
+/// If there's no '=', or the value isn't a valid `u8`.
+fn parse_connection_u8(value: &str, label: &str) -> Result<(String, u8), String> {
+    let (name, parsed) = value.rsplit_once('=').ok_or_else(|| format!("expected CONNECTION={label}"))?;
+    let parsed = parsed.parse().map_err(|error| format!("invalid {}: {error}", label.to_lowercase()))?;
+    Ok((name.to_string(), parsed))
 }
 
 mod common {
     include!("../../cli/args.rs");
 }
 
+#[allow(clippy::missing_panics_doc, reason="tests")]
 #[cfg(test)]
 mod tests {
     #[test]
     fn verify_command() {
         super::command().debug_assert();
     }
+
+    #[test]
+    fn json_requires_no_gui() {
+        assert!(super::command().try_get_matches_from(["hub", "--json"]).is_err());
+        assert!(super::command().try_get_matches_from(["hub", "--json", "--no-gui"]).is_ok_and(|matches| matches.get_flag("json")));
+    }
+
+    #[test]
+    fn reconnect_requires_network() {
+        assert!(super::command().try_get_matches_from(["hub", "--reconnect", "--server", "127.0.0.1:7878"]).is_err());
+        assert!(super::command().try_get_matches_from(["hub", "--reconnect", "--network", "127.0.0.1:7878"]).is_ok_and(|matches| matches.get_flag("reconnect")));
+    }
+
+    #[test]
+    fn speed_requires_replay() {
+        assert!(super::command().try_get_matches_from(["hub", "--speed", "2", "--server", "127.0.0.1:7878"]).is_err());
+        assert!(super::command().try_get_matches_from(["hub", "--replay", "file.rec", "--speed", "2"]).is_ok_and(|matches| matches.get_one::<f64>("speed") == Some(&2.0)));
+    }
+
+    #[test]
+    fn parse_priority() {
+        assert_eq!(super::parse_priority("/dev/ttyACM0=10"), Ok((String::from("/dev/ttyACM0"), 10)));
+        assert_eq!(super::parse_priority("127.0.0.1:7878=255"), Ok((String::from("127.0.0.1:7878"), 255)));
+        assert!(super::parse_priority("/dev/ttyACM0").is_err());
+        assert!(super::parse_priority("/dev/ttyACM0=256").is_err());
+    }
+
+    #[test]
+    fn parse_filter() {
+        let address = |address| cmri::Address::try_from_node_address(address).unwrap();
+        assert_eq!(super::parse_filter("/dev/ttyACM0=0-63"), Ok((String::from("/dev/ttyACM0"), (address(0), address(63)))));
+        assert_eq!(super::parse_filter("127.0.0.1:7878=64-127"), Ok((String::from("127.0.0.1:7878"), (address(64), address(127)))));
+        assert_eq!(super::parse_filter("/dev/ttyACM0=5-5"), Ok((String::from("/dev/ttyACM0"), (address(5), address(5)))));
+        assert_eq!(super::parse_filter("/dev/ttyACM0"), Err(String::from("expected CONNECTION=FIRST-LAST")));
+        assert_eq!(super::parse_filter("/dev/ttyACM0=5"), Err(String::from("expected CONNECTION=FIRST-LAST")));
+        assert_eq!(super::parse_filter("/dev/ttyACM0=0-128"), Err(String::from("Invalid Node Address (must be 0-127): 128")));
+        assert_eq!(super::parse_filter("/dev/ttyACM0=10-5"), Err(String::from("10 is after 5")));
+    }
+
+    #[test]
+    fn parse_group() {
+        assert_eq!(super::parse_group("/dev/ttyACM0=1"), Ok((String::from("/dev/ttyACM0"), 1)));
+        assert_eq!(super::parse_group("/dev/ttyACM0"), Err(String::from("expected CONNECTION=GROUP")));
+        assert!(super::parse_group("/dev/ttyACM0=x").is_err_and(|error| error.starts_with("invalid group: ")));
+    }
 }
@@ -2,14 +2,14 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::trace;
 use cmri_tools::gui;
-use super::hub::{Hub, state::{State, ConnectionState}};
+use super::hub::{Hub, PauseMode, state::{State, ConnectionState}};
 
 pub const APP_TITLE: &str = "CMRInet Hub";
 
 #[expect(clippy::unwrap_used, clippy::missing_panics_doc)]
 pub fn run(hub: Hub, state: Arc<Mutex<State>>, tokio_handle: tokio::runtime::Handle) {
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([800.0, 400.0]),
+        viewport: egui::ViewportBuilder::default().with_inner_size([920.0, 400.0]),
         ..Default::default()
     };
 
@@ -91,10 +91,18 @@ impl eframe::App for App {
             }
         }
 
+        let paused = {
+            let hub = self.hub.clone();
+            self.tokio_handle.block_on(async move { hub.paused().await })
+        };
+        let mut pause = None;
+        let mut disconnect = None;
+        let mut resume = false;
+
         let state = self.state.blocking_lock();
         egui::CentralPanel::default().show(ctx, |ui| {
             egui_extras::StripBuilder::new(ui)
-                .size(egui_extras::Size::exact(150.0))
+                .size(egui_extras::Size::exact(400.0))
                 .size(egui_extras::Size::remainder())
                 .horizontal(|mut strip| {
                     strip.cell(|ui| {
@@ -108,23 +116,63 @@ impl eframe::App for App {
                             self.new_connection = Some(gui::connection::State::default());
                         }
 
+                        if let Some(mode) = paused {
+                            ui.horizontal(|ui| {
+                                ui.label(match mode {
+                                    PauseMode::Drop => "Paused (dropping)",
+                                    PauseMode::Buffer => "Paused (buffering)"
+                                });
+                                resume = ui.button("Resume").clicked();
+                            });
+                        } else {
+                            ui.horizontal(|ui| {
+                                if ui.button("Pause").on_hover_text("Frames are dropped whilst paused").clicked() {
+                                    pause = Some(PauseMode::Drop);
+                                }
+                                if ui.button("Pause & buffer").on_hover_text("Frames are sent on resuming").clicked() {
+                                    pause = Some(PauseMode::Buffer);
+                                }
+                            });
+                        }
+
                         ui.heading("Connections");
                         egui_extras::TableBuilder::new(ui)
                             .column(egui_extras::Column::exact(100.0))
                             .column(egui_extras::Column::exact(40.0))
+                            .column(egui_extras::Column::exact(60.0))
+                            .column(egui_extras::Column::exact(60.0))
+                            .column(egui_extras::Column::exact(50.0))
+                            .column(egui_extras::Column::exact(50.0))
                             .header(15.0, |mut header| {
                                 header.col(|ui| { ui.label("Connection"); });
                                 header.col(|ui| { ui.label("State"); });
+                                header.col(|ui| { ui.label("Bytes/read"); });
+                                header.col(|ui| { ui.label("Frames/s"); });
+                                header.col(|ui| { ui.label("Rx"); });
+                                header.col(|ui| { ui.label("Tx"); });
                             })
                             .body(|mut body| {
-                                for (name, state) in state.connections() {
+                                for (name, connection_state) in state.connections() {
                                     body.row(10.0, |mut row| {
-                                        row.col(|ui| { ui.label(name); });
+                                        row.col(|ui| {
+                                            let mut response = ui.label(name);
+                                            if let Some(kind) = state.kind(name) {
+                                                response = response.on_hover_text(kind.to_string());
+                                            }
+                                            if *connection_state == ConnectionState::Connected {
+                                                response.context_menu(|ui| {
+                                                    if ui.button("Disconnect").clicked() {
+                                                        disconnect = Some(name.clone());
+                                                        ui.close_menu();
+                                                    }
+                                                });
+                                            }
+                                        });
                                         row.col(|ui| {
                                             let (rect, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::hover());
                                             let radius = rect.size().x.min(rect.size().y) * 0.5;
                                             let center = rect.left_center() + egui::Vec2::new(radius, 0.0);
-                                            match state {
+                                            match connection_state {
                                                 ConnectionState::Connected => {
                                                     ui.painter_at(rect).circle_filled(center, radius, egui::Color32::GREEN);
                                                     response.on_hover_text_at_pointer("OK");
@@ -139,6 +187,36 @@ impl eframe::App for App {
                                                 }
                                             }
                                         });
+                                        if let Some(statistics) = state.statistics().get(name) {
+                                            row.col(|ui| {
+                                                ui.label(format!("{:.1}", statistics.reads.bytes_per_read().unwrap_or_default()))
+                                                    .on_hover_text(format!("{} bytes in {} reads", statistics.reads.bytes(), statistics.reads.reads()));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{:.1}", statistics.receive_rates.frames_per_sec()))
+                                                    .on_hover_text(format!("{:.1} bytes/s", statistics.receive_rates.bytes_per_sec()));
+                                            });
+                                            row.col(|ui| {
+                                                let last_seen = statistics.last_seen.map_or_else(
+                                                    || String::from("never"),
+                                                    |instant| format!("{:.1}s ago", instant.elapsed().as_secs_f32())
+                                                );
+                                                ui.label(readable::num::Unsigned::from(statistics.frames_rx).to_string())
+                                                    .on_hover_text(format!(
+                                                        "{} frames received ({}), last {last_seen}",
+                                                        statistics.frames_rx,
+                                                        readable_byte::readable_byte::b(statistics.bytes_rx).to_string_as(true)
+                                                    ));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(readable::num::Unsigned::from(statistics.frames_tx).to_string())
+                                                    .on_hover_text(format!(
+                                                        "{} frames sent ({})",
+                                                        statistics.frames_tx,
+                                                        readable_byte::readable_byte::b(statistics.bytes_tx).to_string_as(true)
+                                                    ));
+                                            });
+                                        }
                                     });
                                 }
                             });
@@ -162,6 +240,21 @@ impl eframe::App for App {
                 });
         });
 
+        drop(state);
+
+        if let Some(mode) = pause {
+            let hub = self.hub.clone();
+            self.tokio_handle.block_on(async move { hub.pause(mode).await });
+        }
+        if resume {
+            let hub = self.hub.clone();
+            self.tokio_handle.block_on(async move { hub.resume().await });
+        }
+        if let Some(name) = disconnect {
+            let hub = self.hub.clone();
+            self.tokio_handle.block_on(async move { hub.disconnect_connection(&name).await });
+        }
+
         ctx.request_repaint_after(std::time::Duration::from_millis(250));
     }
 }
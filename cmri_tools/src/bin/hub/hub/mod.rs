@@ -1,21 +1,28 @@
 //! Link multiple CMRInet Networks.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use anyhow::Context;
 use tokio::net::TcpListener;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, Notify};
 use tracing::{debug, info, error};
-use cmri::frame::Raw as RawFrame;
-use cmri_tools::connection::Connection;
+use cmri::{AddressSet, frame::Raw as RawFrame};
+use cmri_tools::connection::{Connection, ConnectionKind, ReadStatistics, ReceiveRates};
 
 const CHANNEL_BUFFER: usize = 4;
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// The maximum number of frames buffered whilst paused, the oldest are dropped first.
+const PAUSE_BUFFER: usize = 1_000;
+/// The maximum number of recently broadcast frames remembered by the loop guard, the oldest are forgotten first.
+const LOOP_GUARD_CAPACITY: usize = 64;
 
 type ConnectionMessage = Arc<RawFrame>;
 type ConnectionTx = mpsc::Sender<ConnectionMessage>;
 type ConnectionRx = mpsc::Receiver<ConnectionMessage>;
 
 pub mod state;
+mod websocket;
 use state::State;
 
 pub async fn new() -> (Hub, Arc<Mutex<State>>) {
@@ -26,7 +33,7 @@ pub async fn new() -> (Hub, Arc<Mutex<State>>) {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum SubscriberMessage {
-    Connected(String),
+    Connected(String, ConnectionKind),
     Disconnected(String),
     Errored(String, String),
     ServerStarted(String),
@@ -35,16 +42,109 @@ pub enum SubscriberMessage {
 pub type SubscriberTx = mpsc::Sender<SubscriberMessage>;
 pub type SubscriberRx = mpsc::Receiver<SubscriberMessage>;
 
+/// What happens to frames received whilst the `Hub` is paused.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PauseMode {
+    /// The frames are discarded.
+    Drop,
+    /// The frames are kept (upto `PAUSE_BUFFER` of the latest) and sent when resumed.
+    Buffer
+}
+
+/// How long to wait before trying to reconnect a dropped connection,
+/// the delay doubles after each failed attempt (upto max).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BackoffPolicy {
+    /// The delay before the first attempt.
+    pub base: Duration,
+    /// The longest delay between attempts.
+    pub max: Duration
+}
+
+impl BackoffPolicy {
+    /// The delay before a (zero based) attempt.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.base.saturating_mul(2_u32.saturating_pow(attempt)).min(self.max)
+    }
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_millis(500),
+            max: Duration::from_secs(30)
+        }
+    }
+}
+
+/// Statistics about the traffic through a connection (since it last connected).
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ConnectionStatistics {
+    /// The number of frames received from the connection.
+    pub frames_rx: u64,
+    /// The number of bytes received from the connection.
+    pub bytes_rx: u64,
+    /// The number of frames sent to the connection.
+    pub frames_tx: u64,
+    /// The number of bytes sent to the connection.
+    pub bytes_tx: u64,
+    /// When a frame was last received from the connection.
+    pub last_seen: Option<Instant>,
+    /// Statistics about the reads made from the connection.
+    pub reads: ReadStatistics,
+    /// The rates frames and bytes are being received from the connection.
+    pub receive_rates: ReceiveRates
+}
+
+impl ConnectionStatistics {
+    fn received(&mut self, frame: &RawFrame, connection: &Connection) {
+        self.frames_rx += 1;
+        self.bytes_rx += frame.len() as u64;
+        self.last_seen = Some(Instant::now());
+        self.reads = connection.read_statistics();
+        self.receive_rates = connection.receive_rates();
+    }
+
+    fn sent(&mut self, frame: &RawFrame) {
+        self.frames_tx += 1;
+        self.bytes_tx += frame.len() as u64;
+    }
+}
+
 /// Distributes packets between a number of CMRInet connections.
 #[derive(Debug, Clone)]
 pub struct Hub {
     inner: Arc<Mutex<Inner>>
 }
 
+/// A connection the `Hub` is running, several may share a name (E.G. whilst reconnecting).
+#[derive(Debug)]
+struct ConnectionHandle {
+    name: String,
+    tx: ConnectionTx,
+    cancel: Arc<Notify>
+}
+
 #[derive(Debug)]
 struct Inner {
-    connections: HashMap<String, ConnectionTx>,
-    subscriptions: Vec<(String, SubscriberTx)>
+    connections: HashMap<u64, ConnectionHandle>,
+    next_connection_id: u64,
+    supervisors: HashMap<String, Arc<Notify>>,
+    priorities: HashMap<String, u8>,
+    echo: HashSet<String>,
+    groups: HashMap<String, u8>,
+    filters: HashMap<String, AddressSet>,
+    subscriptions: Vec<(String, SubscriberTx)>,
+    read_buffer: Option<usize>,
+    statistics: HashMap<String, ConnectionStatistics>,
+    replay_on_connect: bool,
+    drop_invalid_types: bool,
+    last_data: BTreeMap<(u8, char), ConnectionMessage>,
+    paused: Option<PauseMode>,
+    paused_frames: VecDeque<(String, ConnectionMessage)>,
+    loop_guard: Option<Duration>,
+    recent_frames: VecDeque<(u64, tokio::time::Instant)>
 }
 
 impl Hub {
@@ -53,7 +153,22 @@ impl Hub {
     fn new() -> Self {
         let inner = Inner {
             connections: HashMap::new(),
-            subscriptions: Vec::new()
+            next_connection_id: 0,
+            supervisors: HashMap::new(),
+            priorities: HashMap::new(),
+            echo: HashSet::new(),
+            groups: HashMap::new(),
+            filters: HashMap::new(),
+            subscriptions: Vec::new(),
+            read_buffer: None,
+            statistics: HashMap::new(),
+            replay_on_connect: false,
+            drop_invalid_types: false,
+            last_data: BTreeMap::new(),
+            paused: None,
+            paused_frames: VecDeque::new(),
+            loop_guard: None,
+            recent_frames: VecDeque::with_capacity(LOOP_GUARD_CAPACITY)
         };
         Self { inner: Arc::new(Mutex::new(inner)) }
     }
@@ -108,6 +223,65 @@ impl Hub {
         Ok(())
     }
 
+    /// Connect to a remote server, and add the connection to the `Hub`,
+    /// reconnecting (with backoff) whenever the connection fails or drops.
+    ///
+    /// The connection is named address (rather than the peer's address),
+    /// so its priority, group etc. are kept across reconnections.
+    /// Reconnecting stops once the connection is closed by `disconnect_connection`.
+    pub fn add_network_reconnecting(&self, address: &str, policy: BackoffPolicy) -> tokio::task::JoinHandle<()> {
+        let address = address.to_string();
+        self.supervise(address.clone(), policy, move || {
+            let address = address.clone();
+            async move {
+                let connection = tokio::net::TcpStream::connect(&address).await?;
+                let peer = connection.peer_addr()?;
+                info!("Connected to {address}");
+                Ok(Connection::new(address, Box::new(connection)).with_kind(ConnectionKind::Tcp { peer }))
+            }
+        })
+    }
+
+    /// Repeatedly make a connection using connect and run it,
+    /// waiting according to policy between failed (or dropped) attempts.
+    /// Stops when `disconnect_connection` is called for name.
+    fn supervise<F, Fut>(&self, name: String, policy: BackoffPolicy, mut connect: F) -> tokio::task::JoinHandle<()>
+    where F: FnMut() -> Fut + Send + 'static, Fut: std::future::Future<Output = std::io::Result<Connection>> + Send {
+        let hub = self.clone();
+        tokio::spawn(async move {
+            let stop = Arc::new(Notify::new());
+            hub.inner.lock().await.supervisors.insert(name.clone(), stop.clone());
+            let mut attempt = 0;
+            loop {
+                tokio::select! {
+                    () = stop.notified() => break,
+                    () = async {
+                        match connect().await {
+                            Err(error) => error!("Couldn't connect: {error}"),
+                            Ok(connection) => {
+                                attempt = 0;
+                                match hub.run_connection(connection).await {
+                                    Err(error) => error!("Connection task failed: {error}"),
+                                    Ok(Err(error)) => error!("Connection failed: {error}"),
+                                    Ok(Ok(())) => ()
+                                }
+                            }
+                        }
+                        let delay = policy.delay(attempt);
+                        info!("Reconnecting in {delay:?}");
+                        tokio::time::sleep(delay).await;
+                        attempt = attempt.saturating_add(1);
+                    } => ()
+                }
+            }
+            info!("Stopped reconnecting {name}");
+            let mut inner = hub.inner.lock().await;
+            if inner.supervisors.get(&name).is_some_and(|other| Arc::ptr_eq(other, &stop)) {
+                inner.supervisors.remove(&name);
+            }
+        })
+    }
+
     /// Connect to a serial port, and add the connection to the `Hub`.
     ///
     /// # Errors
@@ -126,10 +300,146 @@ impl Hub {
         #[cfg(unix)]
         connection.set_exclusive(true)?;
         info!("Connected to {port} at {}bps", readable::num::Unsigned::from(baud));
-        self.run_connection(Connection::new(port, Box::new(connection)));
+        self.run_connection(Connection::new(port, Box::new(connection)).with_kind(ConnectionKind::Serial { port: port.to_string(), baud }));
+        Ok(())
+    }
+
+    /// Replay a recording (see `cmri_tools::recording`), and add it to the `Hub` as a connection
+    /// which disconnects at the end of the recording, see `Connection::new_replay`.
+    ///
+    /// # Errors
+    ///
+    /// If the recording can't be read, or speed is invalid.
+    pub fn add_replay(&self, path: &std::path::Path, speed: f64) -> std::io::Result<()> {
+        self.run_connection(Connection::new_replay(path, speed)?);
         Ok(())
     }
 
+    /// Set the priority of a connection (the default is 0),
+    /// frames are sent to higher priority connections first.
+    pub async fn set_priority(&self, name: impl Into<String>, priority: u8) {
+        self.inner.lock().await.priorities.insert(name.into(), priority);
+    }
+
+    /// Set whether a connection is sent the frames it sends (the default is false),
+    /// E.G. for test loops.
+    pub async fn set_echo(&self, name: impl Into<String>, echo: bool) {
+        let name = name.into();
+        let mut inner = self.inner.lock().await;
+        if echo {
+            inner.echo.insert(name);
+        } else {
+            inner.echo.remove(&name);
+        }
+    }
+
+    /// Set the group of a connection (the default is `None`), frames from a grouped connection
+    /// are only sent to connections in the same group or without a group.
+    /// Frames from an ungrouped connection are sent to every connection.
+    pub async fn set_group(&self, name: impl Into<String>, group: Option<u8>) {
+        let name = name.into();
+        let mut inner = self.inner.lock().await;
+        match group {
+            Some(group) => inner.groups.insert(name, group),
+            None => inner.groups.remove(&name)
+        };
+    }
+
+    /// Set the node addresses a connection is sent frames for (the default is `None`, all of them),
+    /// E.G. to scope a connection to a segment of the layout.
+    /// Frames without a valid address are sent to every connection.
+    pub async fn set_filter(&self, name: impl Into<String>, filter: Option<AddressSet>) {
+        let name = name.into();
+        let mut inner = self.inner.lock().await;
+        match filter {
+            Some(filter) => inner.filters.insert(name, filter),
+            None => inner.filters.remove(&name)
+        };
+    }
+
+    /// Set the size of the read buffer for connections added from now on.
+    pub async fn set_read_buffer(&self, len: usize) {
+        self.inner.lock().await.read_buffer = Some(len);
+    }
+
+    /// Set whether the last transmit and receive data frames for each address are
+    /// sent to a connection when it joins, so it gets the current state immediately.
+    ///
+    /// Frames are only retained whilst this is enabled.
+    pub async fn set_replay_on_connect(&self, replay: bool) {
+        let mut inner = self.inner.lock().await;
+        inner.replay_on_connect = replay;
+        if !replay {
+            inner.last_data.clear();
+        }
+    }
+
+    /// Set whether frames with an invalid message type are dropped, instead of being
+    /// sent to the other connections and subscribers (E.G. to clean up a noisy bus).
+    pub async fn set_drop_invalid_types(&self, drop_invalid_types: bool) {
+        self.inner.lock().await.drop_invalid_types = drop_invalid_types;
+    }
+
+    /// Set how long a broadcast frame is remembered for, an identical frame received again within
+    /// this window is dropped (E.G. to stop frames echoing between cross connected hubs).
+    ///
+    /// Only whole frames are compared and the window should be kept short (a few milliseconds)
+    /// so legitimately repeated frames (E.G. polling the same node) aren't dropped.
+    /// A window of zero turns the loop guard off (the default).
+    pub async fn set_loop_guard(&self, window: Duration) {
+        let mut inner = self.inner.lock().await;
+        inner.loop_guard = (!window.is_zero()).then_some(window);
+        inner.recent_frames.clear();
+    }
+
+    /// Stop frames being sent between connections, the connections are kept open
+    /// and subscribers still receive the frames.
+    pub async fn pause(&self, mode: PauseMode) {
+        info!("Pausing ({mode:?})");
+        self.inner.lock().await.paused = Some(mode);
+    }
+
+    /// Start sending frames between connections again,
+    /// after first sending any which were buffered whilst paused.
+    ///
+    /// The frames are sent without holding the lock, so the `Hub` stays paused
+    /// (buffering any more frames received) until they've all been sent.
+    pub async fn resume(&self) {
+        info!("Resuming");
+        loop {
+            let frames = {
+                let mut inner = self.inner.lock().await;
+                if inner.paused_frames.is_empty() {
+                    inner.paused = None;
+                    break;
+                }
+                std::mem::take(&mut inner.paused_frames)
+            };
+            for (source, message) in frames {
+                let destinations = self.inner.lock().await.forward(&source, &message);
+                Self::send(destinations, &message).await;
+            }
+        }
+    }
+
+    /// Whether frames are currently not being sent between connections.
+    #[must_use]
+    pub async fn paused(&self) -> Option<PauseMode> {
+        self.inner.lock().await.paused
+    }
+
+    /// Get the statistics of each connection (including those which have disconnected),
+    /// they're reset when a connection (re)connects.
+    #[must_use]
+    pub async fn statistics(&self) -> HashMap<String, ConnectionStatistics> {
+        self.inner.lock().await.statistics.clone()
+    }
+
+    /// Record a frame having been sent to a connection.
+    async fn sent(&self, name: &str, frame: &RawFrame) {
+        self.inner.lock().await.statistics.entry(name.to_string()).or_default().sent(frame);
+    }
+
     /// Receive updates from `Hub`.
     #[must_use]
     pub async fn subscribe(&self, name: String) -> SubscriberRx {
@@ -138,18 +448,56 @@ impl Hub {
         rx
     }
 
-    async fn connect(&self, name: String) -> ConnectionRx {
+    /// Add a connection, returning the channel of frames to send to it, what's notified to cancel it and its id.
+    async fn connect(&self, name: String, kind: ConnectionKind) -> (ConnectionRx, Arc<Notify>, u64) {
         let (tx, rx) = mpsc::channel(CHANNEL_BUFFER);
-        self.inner.lock().await.connections.insert(name.clone(), tx);
-        self.publish(SubscriberMessage::Connected(name)).await;
-        rx
+        let cancel = Arc::new(Notify::new());
+        let id = {
+            let mut inner = self.inner.lock().await;
+            let id = inner.next_connection_id;
+            inner.next_connection_id += 1;
+            inner.connections.insert(id, ConnectionHandle { name: name.clone(), tx, cancel: cancel.clone() });
+            inner.statistics.insert(name.clone(), ConnectionStatistics::default());
+            id
+        };
+        self.publish(SubscriberMessage::Connected(name, kind)).await;
+        (rx, cancel, id)
     }
 
-    async fn disconnect(&self, name: String) {
-        self.inner.lock().await.connections.remove(&name);
+    async fn disconnect(&self, id: u64, name: String) {
+        {
+            let mut inner = self.inner.lock().await;
+            inner.connections.remove(&id);
+        }
         self.publish(SubscriberMessage::Disconnected(name)).await;
     }
 
+    /// Cleanly close a connection (E.G. from the GUI), returning whether there was a connection with that name.
+    ///
+    /// Frames already queued for the connection are sent before it's shutdown,
+    /// then its task finishes with `Ok` having published `SubscriberMessage::Disconnected`.
+    /// A connection added by `add_network_reconnecting` stops being reconnected.
+    pub async fn disconnect_connection(&self, name: &str) -> bool {
+        let (supervisor, cancels) = {
+            let inner = self.inner.lock().await;
+            (
+                inner.supervisors.get(name).cloned(),
+                inner.connections.values().filter(|connection| connection.name == name).map(|connection| connection.cancel.clone()).collect::<Vec<_>>()
+            )
+        };
+        if supervisor.is_none() && cancels.is_empty() {
+            return false;
+        }
+        info!("Disconnecting {name}");
+        if let Some(supervisor) = supervisor {
+            supervisor.notify_one();
+        }
+        for cancel in cancels {
+            cancel.notify_one();
+        }
+        true
+    }
+
     async fn errored(&self, name: String, error: String) {
         self.publish(SubscriberMessage::Errored(name, error)).await;
     }
@@ -157,20 +505,47 @@ impl Hub {
     async fn broadcast(&self, source: String, message: ConnectionMessage) {
         debug!("Broadcasting {message:?}");
 
-        // Send message to connections (except the one which received it).
         let mut inner = self.inner.lock().await;
-        for (destination, channel) in &mut inner.connections {
-            if destination != &source {
-                if let Err(error) = channel.send(message.clone()).await {
-                    error!("Couldn't enque for connection {:?}: {}", destination, error);
+        if inner.drop_invalid_types && message.message_type().is_none() {
+            debug!("Dropping {message:?} as it has an invalid message type");
+            return;
+        }
+        if inner.seen_recently(&message) {
+            debug!("Dropping {message:?} as it was recently broadcast");
+            return;
+        }
+        let destinations = match inner.paused {
+            None => inner.forward(&source, &message),
+            Some(PauseMode::Drop) => {
+                debug!("Paused, dropping {message:?}");
+                Vec::new()
+            },
+            Some(PauseMode::Buffer) => {
+                if inner.paused_frames.len() >= PAUSE_BUFFER {
+                    inner.paused_frames.pop_front();
                 }
+                inner.paused_frames.push_back((source.clone(), message.clone()));
+                Vec::new()
             }
-        }
+        };
         drop(inner);
 
+        // Sent without holding the lock, as a connection waiting for space in a full
+        // channel would otherwise stop the destination taking the lock to record what it sent.
+        Self::send(destinations, &message).await;
+
         self.publish(SubscriberMessage::Frame(source, message)).await;
     }
 
+    /// Send message to each of the destinations, in order.
+    async fn send(destinations: Vec<(String, ConnectionTx)>, message: &ConnectionMessage) {
+        for (destination, channel) in destinations {
+            if let Err(error) = channel.send(message.clone()).await {
+                error!("Couldn't enque for connection {:?}: {}", destination, error);
+            }
+        }
+    }
+
     async fn publish(&self, message: SubscriberMessage) {
         debug!("Publishing {message:?}");
         let mut inner = self.inner.lock().await;
@@ -182,13 +557,53 @@ impl Hub {
     }
 
     /// Run a connection as a tokio task.
+    ///
+    /// If the connection doesn't have a write timeout then one is set,
+    /// a connection which times out is treated as having disconnected.
+    /// If a read buffer size has been set then it's applied to the connection.
+    /// The task runs until the connection fails, drops or is closed by `disconnect_connection`.
     pub fn run_connection(&self, mut connection: Connection) -> tokio::task::JoinHandle<std::io::Result<()>> {
+        if connection.write_timeout().is_none() {
+            connection.set_write_timeout(WRITE_TIMEOUT);
+        }
         let hub = self.clone();
         tokio::spawn(async move {
+            let read_buffer = hub.inner.lock().await.read_buffer;
+            if let Some(len) = read_buffer {
+                connection = connection.with_read_buffer(len)?;
+            }
             let name = connection.name().to_string();
-            let mut rx = hub.connect(name.clone()).await;
+            let (mut rx, cancel, id) = hub.connect(name.clone(), connection.kind().clone()).await;
+            let replay = {
+                let inner = hub.inner.lock().await;
+                inner.last_data.values().filter(|frame| inner.accepts(&name, frame)).cloned().collect::<Vec<_>>()
+            };
+            for frame in replay {
+                debug!("Replaying {frame:?} to {}", name);
+                if let Err(error) = connection.send(&frame).await {
+                    error!("Write error on {name}: {error}");
+                    hub.errored(name.to_string(), error.to_string()).await;
+                    break;
+                }
+                hub.sent(&name, &frame).await;
+            }
             let result = loop {
                 tokio::select! {
+                    () = cancel.notified() => {
+                        // Drain anything already queued, a send in progress has already completed
+                        while let Ok(frame) = rx.try_recv() {
+                            debug!("Sending {frame:?} to {} before disconnecting", name);
+                            if let Err(error) = connection.send(&frame).await {
+                                debug!("Write error on {name} whilst disconnecting: {error}");
+                                break;
+                            }
+                            hub.sent(&name, &frame).await;
+                        }
+                        if let Err(error) = connection.shutdown().await {
+                            debug!("Couldn't shutdown {name}: {error}");
+                        }
+                        break Ok(())
+                    },
                     message = rx.recv() => match message {
                         None => {
                             // Channel was closed
@@ -198,11 +613,13 @@ impl Hub {
                             debug!("Sending {frame:?} to {}", name);
                             if let Err(error) = connection.send(&frame).await {
                                 error!("Write error on {name}: {error}");
-                                if matches!(error.kind(), std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::ConnectionReset | std::io::ErrorKind::ConnectionAborted | std::io::ErrorKind::UnexpectedEof) {
-                                    // Peer disconnected
+                                if matches!(error.kind(), std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::ConnectionReset | std::io::ErrorKind::ConnectionAborted | std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::TimedOut) {
+                                    // Peer disconnected (or stalled)
                                     break Ok(())
                                 }
                                 hub.errored(name.to_string(), error.to_string()).await;
+                            } else {
+                                hub.sent(&name, &frame).await;
                             }
                         }
                     },
@@ -217,6 +634,7 @@ impl Hub {
                         },
                         Ok(frame) => {
                             debug!("Received {frame:?} from {name}");
+                            hub.inner.lock().await.statistics.entry(name.clone()).or_default().received(&frame, &connection);
                             let message = Arc::new(frame);
                             hub.broadcast(name.clone(), message).await;
                         }
@@ -228,13 +646,86 @@ impl Hub {
                 hub.errored(name.to_string(), error.to_string()).await;
             }
 
-            hub.disconnect(name.to_string()).await;
+            hub.disconnect(id, name.to_string()).await;
             result
         })
     }
 }
 
 
+impl Inner {
+    /// Whether the loop guard has seen an identical frame within its window,
+    /// if it hasn't then the frame is remembered.
+    fn seen_recently(&mut self, message: &RawFrame) -> bool {
+        let Some(window) = self.loop_guard else { return false };
+        let now = tokio::time::Instant::now();
+        while self.recent_frames.front().is_some_and(|(_, seen)| now.duration_since(*seen) > window) {
+            self.recent_frames.pop_front();
+        }
+
+        let hash = {
+            let mut hasher = std::hash::DefaultHasher::new();
+            std::hash::Hash::hash(&**message, &mut hasher);
+            std::hash::Hasher::finish(&hasher)
+        };
+        if self.recent_frames.iter().any(|(recent, _)| *recent == hash) {
+            return true;
+        }
+
+        if self.recent_frames.len() >= LOOP_GUARD_CAPACITY {
+            self.recent_frames.pop_front();
+        }
+        self.recent_frames.push_back((hash, now));
+        false
+    }
+
+    /// The connections to send message to (except the one which received it), highest priority first,
+    /// they're owned so the message can be sent after the lock is released.
+    fn forward(&mut self, source: &str, message: &ConnectionMessage) -> Vec<(String, ConnectionTx)> {
+        self.retain(message);
+        self.destinations(source, message).into_iter()
+            .map(|(destination, channel)| (destination.clone(), channel.clone()))
+            .collect()
+    }
+
+    /// Keep hold of a frame if it's transmit or receive data, and replay on connect is enabled.
+    fn retain(&mut self, message: &ConnectionMessage) {
+        if !self.replay_on_connect { return }
+        if let (Some(address), Some(message_type @ ('T' | 'R'))) = (message.address(), message.message_type()) {
+            self.last_data.insert((address, message_type), message.clone());
+        }
+    }
+
+    /// The connections to send a frame from source to, in priority order (highest first).
+    /// The source is only included if it has echo enabled,
+    /// only connections in the same group (or ungrouped) are included,
+    /// only connections whose filter accepts the frame are included.
+    fn destinations(&self, source: &str, message: &ConnectionMessage) -> Vec<(&String, &ConnectionTx)> {
+        let echo = self.echo.contains(source);
+        let group = self.groups.get(source);
+        let mut destinations = self.connections.values()
+            .map(|connection| (&connection.name, &connection.tx))
+            .filter(|(name, _)| echo || name.as_str() != source)
+            .filter(|(name, _)| group.map_or(true, |group| self.groups.get(*name).map_or(true, |other| other == group)))
+            .filter(|(name, _)| self.accepts(name, message))
+            .collect::<Vec<_>>();
+        destinations.sort_by(|(a, _), (b, _)| {
+            let priority = |name| self.priorities.get(name).copied().unwrap_or_default();
+            priority(*b).cmp(&priority(*a)).then_with(|| a.cmp(b))
+        });
+        destinations
+    }
+
+    /// Whether a connection's filter accepts a frame, frames without a valid address are always accepted.
+    fn accepts(&self, name: &str, message: &RawFrame) -> bool {
+        let Some(filter) = self.filters.get(name) else { return true };
+        message.address()
+            .and_then(|address| cmri::Address::try_from_node_address(address).ok())
+            .map_or(true, |address| filter.contains(address))
+    }
+}
+
+
 #[allow(clippy::missing_panics_doc, reason="tests")]
 #[cfg(test)]
 mod tests {
@@ -269,6 +760,568 @@ mod tests {
         assert!(connections[0].read(&mut buffer).await.is_ok_and(|u| { let len = frames[1].len(); u == len && &buffer[..len] == frames[1].as_slice() }));
     }
 
+    mod reconnecting {
+        use super::*;
+
+        #[test]
+        fn backoff_policy() {
+            let policy = BackoffPolicy { base: Duration::from_secs(1), max: Duration::from_secs(5) };
+            assert_eq!(policy.delay(0), Duration::from_secs(1));
+            assert_eq!(policy.delay(1), Duration::from_secs(2));
+            assert_eq!(policy.delay(2), Duration::from_secs(4));
+            assert_eq!(policy.delay(3), Duration::from_secs(5));
+            assert_eq!(policy.delay(u32::MAX), Duration::from_secs(5));
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn refused_retries_at_increasing_intervals() {
+            let hub = Hub::new();
+            let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let policy = BackoffPolicy { base: Duration::from_secs(1), max: Duration::from_secs(5) };
+            let start = tokio::time::Instant::now();
+            let task = {
+                let attempts = attempts.clone();
+                hub.supervise(String::from("connection"), policy, move || {
+                    attempts.lock().unwrap().push(start.elapsed());
+                    async { Err(std::io::ErrorKind::ConnectionRefused.into()) }
+                })
+            };
+
+            tokio::time::sleep(Duration::from_secs(20)).await;
+            task.abort();
+            assert_eq!(
+                attempts.lock().unwrap().as_slice(),
+                [0, 1, 3, 7, 12, 17].map(Duration::from_secs)
+            );
+        }
+
+        #[tokio::test]
+        async fn reconnects_after_disconnect() {
+            let listener = tokio::net::TcpListener::bind("localhost:0").await.unwrap();
+            let peer = listener.local_addr().unwrap();
+            let address = peer.to_string();
+            let hub = Hub::new();
+            let mut subscription = hub.subscribe(String::from("test")).await;
+            let policy = BackoffPolicy { base: Duration::from_millis(10), max: Duration::from_millis(10) };
+            let task = hub.add_network_reconnecting(&address, policy);
+
+            let (connection, _) = listener.accept().await.unwrap();
+            assert_eq!(subscription.recv().await, Some(SubscriberMessage::Connected(address.clone(), ConnectionKind::Tcp { peer })));
+            drop(connection);
+            assert_eq!(subscription.recv().await, Some(SubscriberMessage::Disconnected(address.clone())));
+
+            let (_connection, _) = listener.accept().await.unwrap();
+            assert_eq!(subscription.recv().await, Some(SubscriberMessage::Connected(address.clone(), ConnectionKind::Tcp { peer })));
+            task.abort();
+        }
+
+        #[tokio::test]
+        async fn stops_after_disconnect_connection() {
+            let listener = tokio::net::TcpListener::bind("localhost:0").await.unwrap();
+            let peer = listener.local_addr().unwrap();
+            let address = peer.to_string();
+            let hub = Hub::new();
+            let mut subscription = hub.subscribe(String::from("test")).await;
+            let policy = BackoffPolicy { base: Duration::from_millis(10), max: Duration::from_millis(10) };
+            let task = hub.add_network_reconnecting(&address, policy);
+
+            let (_connection, _) = listener.accept().await.unwrap();
+            assert_eq!(subscription.recv().await, Some(SubscriberMessage::Connected(address.clone(), ConnectionKind::Tcp { peer })));
+            assert!(hub.disconnect_connection(&address).await);
+            assert_eq!(subscription.recv().await, Some(SubscriberMessage::Disconnected(address.clone())));
+
+            tokio::time::timeout(Duration::from_secs(1), task).await.unwrap().unwrap();
+            assert!(tokio::time::timeout(Duration::from_millis(50), listener.accept()).await.is_err());
+            assert!(hub.inner.lock().await.supervisors.is_empty());
+        }
+
+        #[tokio::test]
+        async fn refused() {
+            // Find a port with nothing listening on it
+            let address = std::net::TcpListener::bind("localhost:0").unwrap().local_addr().unwrap().to_string();
+            let hub = Hub::new();
+            let task = hub.add_network_reconnecting(&address, BackoffPolicy::default());
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert!(!task.is_finished());
+            assert!(hub.inner.lock().await.connections.is_empty());
+            task.abort();
+        }
+    }
+
+    mod replay {
+        use super::*;
+
+        #[tokio::test(start_paused = true)]
+        async fn frames_then_disconnects() {
+            let mut path = std::env::temp_dir();
+            path.push(format!("cmri-hub-{}-replay.rec", std::process::id()));
+            let frame = Packet::new_poll_request(Address::try_from_node_address(5).unwrap()).encode_frame();
+            let mut recording = Vec::from(cmri_tools::recording::MAGIC);
+            cmri_tools::recording::write_record(&mut recording, Duration::from_millis(10), &frame).unwrap();
+            std::fs::write(&path, recording).unwrap();
+
+            let hub = Hub::new();
+            let mut subscriber = hub.subscribe(String::from("subscriber")).await;
+            hub.add_replay(&path, 1.0).unwrap();
+            let name = path.display().to_string();
+            assert_eq!(subscriber.recv().await, Some(SubscriberMessage::Connected(name.clone(), ConnectionKind::Replay)));
+            assert_eq!(subscriber.recv().await, Some(SubscriberMessage::Frame(name.clone(), Arc::new(frame))));
+            assert_eq!(subscriber.recv().await, Some(SubscriberMessage::Disconnected(name)));
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        #[tokio::test]
+        async fn missing_file() {
+            let hub = Hub::new();
+            assert_eq!(
+                hub.add_replay(std::path::Path::new("/does/not/exist.rec"), 1.0).unwrap_err().kind(),
+                std::io::ErrorKind::NotFound
+            );
+        }
+    }
+
+    mod priority {
+        use super::*;
+
+        #[tokio::test]
+        async fn destinations_in_priority_order() {
+            let hub = Hub::new();
+            let _rxs = [
+                hub.connect(String::from("logging"), ConnectionKind::Channel).await.0,
+                hub.connect(String::from("panel"), ConnectionKind::Channel).await.0,
+                hub.connect(String::from("other"), ConnectionKind::Channel).await.0,
+                hub.connect(String::from("source"), ConnectionKind::Channel).await.0
+            ];
+            hub.set_priority("panel", 10).await;
+            hub.set_priority("other", 5).await;
+
+            let destinations = hub.inner.lock().await
+                .destinations("source", &Arc::new(RawFrame::new()))
+                .into_iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(destinations, vec!["panel", "other", "logging"]);
+        }
+
+        #[tokio::test]
+        async fn higher_priority_sent_first_when_congested() {
+            let hub = Hub::new();
+            let mut logging = hub.connect(String::from("logging"), ConnectionKind::Channel).await.0;
+            let mut panel = hub.connect(String::from("panel"), ConnectionKind::Channel).await.0;
+            hub.set_priority("panel", 10).await;
+
+            // Fill the logging connection's channel
+            let frame = Arc::new(Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).encode_frame());
+            for _ in 0..CHANNEL_BUFFER {
+                hub.broadcast(String::from("panel"), frame.clone()).await;
+            }
+
+            // Broadcasting now blocks on the logging connection, but the panel already has the frame
+            let broadcast = {
+                let hub = hub.clone();
+                let frame = frame.clone();
+                tokio::spawn(async move { hub.broadcast(String::from("source"), frame).await; })
+            };
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            assert!(!broadcast.is_finished());
+            assert_eq!(panel.try_recv(), Ok(frame.clone()));
+
+            // Draining the logging connection allows the broadcast to complete
+            for _ in 0..=CHANNEL_BUFFER {
+                assert_eq!(logging.recv().await, Some(frame.clone()));
+            }
+            broadcast.await.unwrap();
+        }
+    }
+
+    mod drop_invalid_types {
+        use super::*;
+
+        fn invalid_frame() -> ConnectionMessage {
+            Arc::new(RawFrame::try_from(&[0xFF, 0xFF, 0x02, 65, b'z', 0x03]).unwrap()) // Lowercase is invalid even for experimenters
+        }
+
+        #[tokio::test]
+        async fn forwarded_by_default() {
+            let hub = Hub::new();
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            let frame = invalid_frame();
+            hub.broadcast(String::from("source"), frame.clone()).await;
+            assert_eq!(other.try_recv(), Ok(frame));
+        }
+
+        #[tokio::test]
+        async fn dropped_when_enabled() {
+            let hub = Hub::new();
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            let mut subscriber = hub.subscribe(String::from("subscriber")).await;
+            hub.set_drop_invalid_types(true).await;
+
+            hub.broadcast(String::from("source"), invalid_frame()).await;
+            assert_eq!(other.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+            assert_eq!(subscriber.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+
+            // Valid frames are still sent
+            let frame = Arc::new(Packet::new_poll_request(Address::try_from_node_address(0).unwrap()).encode_frame());
+            hub.broadcast(String::from("source"), frame.clone()).await;
+            assert_eq!(other.try_recv(), Ok(frame));
+        }
+    }
+
+    mod loop_guard {
+        use super::*;
+
+        fn frame(node_address: u8) -> ConnectionMessage {
+            Arc::new(Packet::new_poll_request(Address::try_from_node_address(node_address).unwrap()).encode_frame())
+        }
+
+        #[tokio::test]
+        async fn off_by_default() {
+            let hub = Hub::new();
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            hub.broadcast(String::from("source"), frame(1)).await;
+            hub.broadcast(String::from("source"), frame(1)).await;
+            assert_eq!(other.try_recv(), Ok(frame(1)));
+            assert_eq!(other.try_recv(), Ok(frame(1)));
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn drops_duplicate_within_window() {
+            let hub = Hub::new();
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            let mut subscriber = hub.subscribe(String::from("subscriber")).await;
+            hub.set_loop_guard(Duration::from_millis(5)).await;
+
+            hub.broadcast(String::from("source"), frame(1)).await;
+            assert_eq!(other.try_recv(), Ok(frame(1)));
+            assert!(subscriber.try_recv().is_ok());
+
+            // An identical frame (even from another connection) is dropped
+            tokio::time::advance(Duration::from_millis(5)).await;
+            hub.broadcast(String::from("another"), frame(1)).await;
+            assert_eq!(other.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+            assert_eq!(subscriber.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+
+            // A different frame isn't
+            hub.broadcast(String::from("source"), frame(2)).await;
+            assert_eq!(other.try_recv(), Ok(frame(2)));
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn forwards_duplicate_after_window() {
+            let hub = Hub::new();
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            hub.set_loop_guard(Duration::from_millis(5)).await;
+
+            hub.broadcast(String::from("source"), frame(1)).await;
+            assert_eq!(other.try_recv(), Ok(frame(1)));
+
+            tokio::time::advance(Duration::from_millis(6)).await;
+            hub.broadcast(String::from("source"), frame(1)).await;
+            assert_eq!(other.try_recv(), Ok(frame(1)));
+        }
+
+        #[tokio::test]
+        async fn turned_off() {
+            let hub = Hub::new();
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            hub.set_loop_guard(Duration::from_secs(60)).await;
+            hub.broadcast(String::from("source"), frame(1)).await;
+            hub.set_loop_guard(Duration::ZERO).await;
+            hub.broadcast(String::from("source"), frame(1)).await;
+            assert_eq!(other.try_recv(), Ok(frame(1)));
+            assert_eq!(other.try_recv(), Ok(frame(1)));
+        }
+
+        #[tokio::test]
+        async fn capacity_is_limited() {
+            let hub = Hub::new();
+            hub.set_loop_guard(Duration::from_secs(60)).await;
+            for i in 0..=LOOP_GUARD_CAPACITY {
+                hub.broadcast(String::from("source"), frame(u8::try_from(i).unwrap())).await;
+            }
+            assert_eq!(hub.inner.lock().await.recent_frames.len(), LOOP_GUARD_CAPACITY);
+        }
+    }
+
+    mod echo {
+        use super::*;
+
+        #[tokio::test]
+        async fn off_by_default() {
+            let hub = Hub::new();
+            let mut source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            let frame = Arc::new(Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).encode_frame());
+
+            hub.broadcast(String::from("source"), frame.clone()).await;
+            assert_eq!(other.try_recv(), Ok(frame));
+            assert_eq!(source.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+        }
+
+        #[tokio::test]
+        async fn on() {
+            let hub = Hub::new();
+            let mut source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            hub.set_echo("source", true).await;
+            let frame = Arc::new(Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).encode_frame());
+
+            hub.broadcast(String::from("source"), frame.clone()).await;
+            assert_eq!(other.try_recv(), Ok(frame.clone()));
+            assert_eq!(source.try_recv(), Ok(frame));
+        }
+
+        #[tokio::test]
+        async fn turned_off() {
+            let hub = Hub::new();
+            let mut source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            hub.set_echo("source", true).await;
+            hub.set_echo("source", false).await;
+            let frame = Arc::new(Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).encode_frame());
+
+            hub.broadcast(String::from("source"), frame).await;
+            assert_eq!(source.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+        }
+    }
+
+    mod group {
+        use super::*;
+
+        fn frame() -> ConnectionMessage {
+            Arc::new(Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).encode_frame())
+        }
+
+        #[tokio::test]
+        async fn only_within_group() {
+            let hub = Hub::new();
+            let _source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            let mut same = hub.connect(String::from("same"), ConnectionKind::Channel).await.0;
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            let mut ungrouped = hub.connect(String::from("ungrouped"), ConnectionKind::Channel).await.0;
+            hub.set_group("source", Some(1)).await;
+            hub.set_group("same", Some(1)).await;
+            hub.set_group("other", Some(2)).await;
+
+            hub.broadcast(String::from("source"), frame()).await;
+            assert_eq!(same.try_recv(), Ok(frame()));
+            assert_eq!(ungrouped.try_recv(), Ok(frame()));
+            assert_eq!(other.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+        }
+
+        #[tokio::test]
+        async fn ungrouped_reaches_all() {
+            let hub = Hub::new();
+            let _source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            let mut one = hub.connect(String::from("one"), ConnectionKind::Channel).await.0;
+            let mut two = hub.connect(String::from("two"), ConnectionKind::Channel).await.0;
+            hub.set_group("one", Some(1)).await;
+            hub.set_group("two", Some(2)).await;
+
+            hub.broadcast(String::from("source"), frame()).await;
+            assert_eq!(one.try_recv(), Ok(frame()));
+            assert_eq!(two.try_recv(), Ok(frame()));
+        }
+
+        #[tokio::test]
+        async fn cleared() {
+            let hub = Hub::new();
+            let _source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            let mut other = hub.connect(String::from("other"), ConnectionKind::Channel).await.0;
+            hub.set_group("source", Some(1)).await;
+            hub.set_group("other", Some(2)).await;
+            hub.set_group("other", None).await;
+
+            hub.broadcast(String::from("source"), frame()).await;
+            assert_eq!(other.try_recv(), Ok(frame()));
+        }
+    }
+
+    mod filter {
+        use super::*;
+
+        fn frame(address: u8) -> ConnectionMessage {
+            Arc::new(Packet::new_poll_request(Address::try_from_node_address(address).unwrap()).encode_frame())
+        }
+
+        fn range(addresses: std::ops::RangeInclusive<u8>) -> AddressSet {
+            addresses.map(|address| Address::try_from_node_address(address).unwrap()).collect()
+        }
+
+        #[tokio::test]
+        async fn only_matching_addresses() {
+            let hub = Hub::new();
+            let _source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            let mut a = hub.connect(String::from("a"), ConnectionKind::Channel).await.0;
+            let mut b = hub.connect(String::from("b"), ConnectionKind::Channel).await.0;
+            hub.set_filter("a", Some(range(0..=63))).await;
+            hub.set_filter("b", Some(range(64..=127))).await;
+
+            hub.broadcast(String::from("source"), frame(10)).await;
+            assert_eq!(a.try_recv(), Ok(frame(10)));
+            assert_eq!(b.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+
+            hub.broadcast(String::from("source"), frame(100)).await;
+            assert_eq!(a.try_recv(), Err(mpsc::error::TryRecvError::Empty));
+            assert_eq!(b.try_recv(), Ok(frame(100)));
+        }
+
+        #[tokio::test]
+        async fn invalid_address_reaches_all() {
+            let hub = Hub::new();
+            let _source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            let mut a = hub.connect(String::from("a"), ConnectionKind::Channel).await.0;
+            hub.set_filter("a", Some(AddressSet::new())).await;
+
+            let frame = Arc::new(RawFrame::try_from(&[0xFF, 0xFF, 0x02, 0, b'P', 0x03]).unwrap());
+            hub.broadcast(String::from("source"), frame.clone()).await;
+            assert_eq!(a.try_recv(), Ok(frame));
+        }
+
+        #[tokio::test]
+        async fn cleared() {
+            let hub = Hub::new();
+            let _source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+            let mut a = hub.connect(String::from("a"), ConnectionKind::Channel).await.0;
+            hub.set_filter("a", Some(range(64..=127))).await;
+            hub.set_filter("a", None).await;
+
+            hub.broadcast(String::from("source"), frame(10)).await;
+            assert_eq!(a.try_recv(), Ok(frame(10)));
+        }
+    }
+
+    mod replay_on_connect {
+        use super::*;
+
+        #[tokio::test]
+        async fn retains_latest_data_per_address() {
+            let hub = Hub::new();
+            hub.set_replay_on_connect(true).await;
+            let address = Address::try_from_node_address(1).unwrap();
+            let old = Arc::new(Packet::new_transmit_data(address, [1].try_into().unwrap()).encode_frame());
+            let new = Arc::new(Packet::new_transmit_data(address, [2].try_into().unwrap()).encode_frame());
+            let receive = Arc::new(Packet::new_receive_data(address, [3].try_into().unwrap()).encode_frame());
+            let poll = Arc::new(Packet::new_poll_request(address).encode_frame());
+
+            for frame in [&old, &new, &receive, &poll] {
+                hub.broadcast(String::from("source"), frame.clone()).await;
+            }
+
+            let retained = hub.inner.lock().await.last_data.values().cloned().collect::<Vec<_>>();
+            assert_eq!(retained, vec![receive, new]);
+        }
+
+        #[tokio::test]
+        async fn disabled_by_default() {
+            let hub = Hub::new();
+            let frame = Arc::new(Packet::new_transmit_data(Address::try_from_node_address(1).unwrap(), [1].try_into().unwrap()).encode_frame());
+            hub.broadcast(String::from("source"), frame).await;
+            assert!(hub.inner.lock().await.last_data.is_empty());
+        }
+
+        #[tokio::test]
+        async fn joining_connection_receives_cached_frame() {
+            let frame = Packet::new_transmit_data(Address::try_from_node_address(5).unwrap(), [1, 2].try_into().unwrap()).encode_frame();
+            let hub = Hub::new();
+            hub.set_replay_on_connect(true).await;
+            hub.broadcast(String::from("controller"), Arc::new(frame)).await;
+
+            let (connection, mut peer) = tokio::io::duplex(64);
+            hub.run_connection(Connection::new("connection", Box::new(connection)));
+
+            let mut buffer = [0; 64];
+            let len = peer.read(&mut buffer).await.unwrap();
+            assert_eq!(&buffer[..len], frame.as_slice());
+        }
+    }
+
+    mod pause {
+        use super::*;
+
+        fn frame(node_address: u8) -> ConnectionMessage {
+            Arc::new(Packet::new_poll_request(Address::try_from_node_address(node_address).unwrap()).encode_frame())
+        }
+
+        #[tokio::test]
+        async fn reaches_subscribers_but_not_connections() {
+            let hub = Hub::new();
+            let mut subscriber = hub.subscribe(String::from("subscriber")).await;
+            let mut connection = hub.connect(String::from("connection"), ConnectionKind::Channel).await.0;
+            assert_eq!(subscriber.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Channel)));
+
+            hub.pause(PauseMode::Drop).await;
+            assert_eq!(hub.paused().await, Some(PauseMode::Drop));
+            hub.broadcast(String::from("source"), frame(1)).await;
+            assert_eq!(subscriber.try_recv(), Ok(SubscriberMessage::Frame(String::from("source"), frame(1))));
+            assert!(connection.try_recv().is_err());
+
+            // Dropped frames aren't sent on resuming
+            hub.resume().await;
+            assert_eq!(hub.paused().await, None);
+            assert!(connection.try_recv().is_err());
+            hub.broadcast(String::from("source"), frame(2)).await;
+            assert_eq!(connection.try_recv(), Ok(frame(2)));
+        }
+
+        #[tokio::test]
+        async fn buffered_frames_sent_on_resume() {
+            let hub = Hub::new();
+            let mut connection = hub.connect(String::from("connection"), ConnectionKind::Channel).await.0;
+            let mut source = hub.connect(String::from("source"), ConnectionKind::Channel).await.0;
+
+            hub.pause(PauseMode::Buffer).await;
+            hub.broadcast(String::from("source"), frame(1)).await;
+            hub.broadcast(String::from("connection"), frame(2)).await;
+            assert!(connection.try_recv().is_err());
+            assert!(source.try_recv().is_err());
+
+            hub.resume().await;
+            assert_eq!(connection.try_recv(), Ok(frame(1)));
+            assert_eq!(source.try_recv(), Ok(frame(2)));
+        }
+
+        #[tokio::test]
+        async fn resume_more_than_channel_buffer() {
+            let frames = (1..=u8::try_from(CHANNEL_BUFFER * 4).unwrap()).map(frame).collect::<Vec<_>>();
+            let (connection, mut peer) = tokio::io::duplex(64);
+            let hub = Hub::new();
+            let mut rx = hub.subscribe(String::from("subscriber")).await;
+            hub.run_connection(Connection::new("connection", Box::new(connection)));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
+            drop(rx); // So publishing the frames doesn't wait for it
+
+            hub.pause(PauseMode::Buffer).await;
+            for frame in &frames {
+                hub.broadcast(String::from("source"), frame.clone()).await;
+            }
+            let resume = tokio::spawn({
+                let hub = hub.clone();
+                async move { hub.resume().await }
+            });
+
+            // The connection's task needs the lock to record each frame sent, so resume mustn't hold it
+            let mut received = vec![0; frames.iter().map(|frame| frame.len()).sum()];
+            tokio::time::timeout(Duration::from_secs(1), peer.read_exact(&mut received)).await.unwrap().unwrap();
+            assert_eq!(received, frames.iter().flat_map(|frame| frame.to_vec()).collect::<Vec<_>>());
+            tokio::time::timeout(Duration::from_secs(1), resume).await.unwrap().unwrap();
+            assert_eq!(hub.paused().await, None);
+        }
+
+        #[tokio::test]
+        #[allow(clippy::significant_drop_tightening)]
+        async fn buffer_keeps_latest() {
+            let hub = Hub::new();
+            hub.pause(PauseMode::Buffer).await;
+            for i in 0..=PAUSE_BUFFER {
+                hub.broadcast(String::from("source"), frame(u8::try_from(i % 128).unwrap())).await;
+            }
+            let inner = hub.inner.lock().await;
+            assert_eq!(inner.paused_frames.len(), PAUSE_BUFFER);
+            assert_eq!(inner.paused_frames.front(), Some(&(String::from("source"), frame(1))));
+        }
+    }
+
     mod run_connection {
         use super::*;
 
@@ -286,7 +1339,7 @@ mod tests {
             hub.run_connection(Connection::new("connection", Box::new(connection)));
 
             // Publishes connected
-            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"))));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
 
             // Publishes frame
             assert_eq!(rx.recv().await, Some(SubscriberMessage::Frame(String::from("connection"), Arc::new(frame_rx))));
@@ -309,7 +1362,7 @@ mod tests {
             let mut rx = hub.subscribe(String::from("subscriber")).await;
             hub.run_connection(Connection::new("connection", Box::new(connection)));
 
-            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"))));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
             hub.broadcast(String::from("test"), Arc::new(frame)).await;
             assert!(matches!(rx.recv().await, Some(SubscriberMessage::Frame(_, _))));
 
@@ -317,6 +1370,22 @@ mod tests {
             assert_eq!(rx.recv().await, Some(SubscriberMessage::Errored(String::from("connection"), String::from("error"))));
         }
 
+        #[tokio::test(start_paused = true)]
+        async fn write_timeout() {
+            let frame = Packet::new_poll_request(Address::try_from_node_address(70).unwrap()).encode_frame();
+            let (connection, _peer) = tokio::io::duplex(1); // Peer never reads so writing stalls
+            let hub = Hub::new();
+            let mut rx = hub.subscribe(String::from("subscriber")).await;
+            hub.run_connection(Connection::new("connection", Box::new(connection)));
+
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
+            hub.broadcast(String::from("test"), Arc::new(frame)).await;
+            assert!(matches!(rx.recv().await, Some(SubscriberMessage::Frame(_, _))));
+
+            // Treated as a disconnection
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Disconnected(String::from("connection"))));
+        }
+
         #[tokio::test]
         async fn read_error() {
             let connection = tokio_test::io::Builder::new()
@@ -326,12 +1395,96 @@ mod tests {
             let mut rx = hub.subscribe(String::from("subscriber")).await;
             hub.run_connection(Connection::new("connection", Box::new(connection)));
 
-            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"))));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
 
             // Publishes the error
             assert_eq!(rx.recv().await, Some(SubscriberMessage::Errored(String::from("connection"), String::from("error"))));
         }
 
+        #[tokio::test]
+        async fn read_buffer() {
+            let frame = Packet::new_poll_request(Address::try_from_node_address(80).unwrap()).encode_frame();
+            let connection = tokio_test::io::Builder::new()
+                .read(frame.as_slice())
+                .build();
+            let hub = Hub::new();
+            hub.set_read_buffer(4).await;
+            let mut rx = hub.subscribe(String::from("subscriber")).await;
+            hub.run_connection(Connection::new("connection", Box::new(connection)));
+
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Frame(String::from("connection"), Arc::new(frame))));
+
+            // The 6 byte frame needed two reads of the 4 byte buffer
+            let statistics = hub.statistics().await;
+            assert_eq!(statistics.get("connection").map(|statistics| statistics.reads.reads()), Some(2));
+            assert_eq!(statistics.get("connection").map(|statistics| statistics.reads.bytes()), Some(6));
+        }
+
+        #[tokio::test]
+        async fn statistics() {
+            let frame_rx = Packet::new_poll_request(Address::try_from_node_address(50).unwrap()).encode_frame();
+            let frame_tx = Packet::new_poll_request(Address::try_from_node_address(60).unwrap()).encode_frame();
+            let connection = tokio_test::io::Builder::new()
+                .read(frame_rx.as_slice())
+                .read(frame_rx.as_slice())
+                .write(frame_tx.as_slice())
+                .build();
+            let hub = Hub::new();
+            let mut rx = hub.subscribe(String::from("subscriber")).await;
+            hub.run_connection(Connection::new("connection", Box::new(connection)));
+
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
+            assert!(matches!(rx.recv().await, Some(SubscriberMessage::Frame(_, _))));
+            assert!(matches!(rx.recv().await, Some(SubscriberMessage::Frame(_, _))));
+            hub.broadcast(String::from("test"), Arc::new(frame_tx)).await;
+            assert!(matches!(rx.recv().await, Some(SubscriberMessage::Frame(_, _))));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Disconnected(String::from("connection"))));
+
+            let statistics = hub.statistics().await.get("connection").cloned().unwrap();
+            assert_eq!(statistics.frames_rx, 2);
+            assert_eq!(statistics.bytes_rx, 12);
+            assert_eq!(statistics.frames_tx, 1);
+            assert_eq!(statistics.bytes_tx, 6);
+            assert!(statistics.last_seen.is_some());
+        }
+
+        #[tokio::test]
+        async fn burst_to_slow_connection() {
+            let frame = Packet::new_poll_request(Address::try_from_node_address(50).unwrap()).encode_frame();
+            let hub = Hub::new();
+            let (source, mut source_peer) = tokio::io::duplex(1024);
+            let (destination, mut destination_peer) = tokio::io::duplex(6);
+            hub.run_connection(Connection::new("destination", Box::new(destination)));
+            while !hub.inner.lock().await.connections.values().any(|connection| connection.name == "destination") {
+                tokio::task::yield_now().await;
+            }
+            hub.run_connection(Connection::new("source", Box::new(source)));
+
+            // More frames than the channel can hold, faster than the destination can write them
+            for _ in 0..20 {
+                source_peer.write_all(frame.as_slice()).await.unwrap();
+            }
+
+            let mut received = vec![0; frame.len() * 20];
+            tokio::time::timeout(Duration::from_secs(2), destination_peer.read_exact(&mut received)).await.unwrap().unwrap();
+            assert_eq!(received, frame.as_slice().repeat(20));
+        }
+
+        #[tokio::test]
+        async fn statistics_reset_on_reconnect() {
+            let frame = Packet::new_poll_request(Address::try_from_node_address(50).unwrap()).encode_frame();
+            let hub = Hub::new();
+
+            let connection = tokio_test::io::Builder::new().read(frame.as_slice()).build();
+            hub.run_connection(Connection::new("connection", Box::new(connection))).await.unwrap().unwrap();
+            assert_eq!(hub.statistics().await.get("connection").map(|statistics| statistics.frames_rx), Some(1));
+
+            let connection = tokio_test::io::Builder::new().build();
+            hub.run_connection(Connection::new("connection", Box::new(connection))).await.unwrap().unwrap();
+            assert_eq!(hub.statistics().await.get("connection").map(|statistics| statistics.frames_rx), Some(0));
+        }
+
         #[tokio::test]
         async fn bad_frame() {
             let frame = Packet::new_poll_request(Address::try_from_node_address(80).unwrap()).encode_frame();
@@ -343,10 +1496,89 @@ mod tests {
             let mut rx = hub.subscribe(String::from("subscriber")).await;
             hub.run_connection(Connection::new("connection", Box::new(connection)));
 
-            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"))));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
 
             // Bad frame is silently ignored, so the following good one is seen next
             assert_eq!(rx.recv().await, Some(SubscriberMessage::Frame(String::from("connection"), Arc::new(frame))));
         }
     }
+
+    mod disconnect_connection {
+        use super::*;
+
+        /// Receive from the subscription until the connection is disconnected.
+        async fn disconnected(rx: &mut SubscriberRx) {
+            loop {
+                match rx.recv().await {
+                    Some(SubscriberMessage::Frame(_, _)) => (),
+                    message => {
+                        assert_eq!(message, Some(SubscriberMessage::Disconnected(String::from("connection"))));
+                        return;
+                    }
+                }
+            }
+        }
+
+        #[tokio::test]
+        async fn closes_connection() {
+            let (connection, mut peer) = tokio::io::duplex(64);
+            let hub = Hub::new();
+            let mut rx = hub.subscribe(String::from("subscriber")).await;
+            let handle = hub.run_connection(Connection::new("connection", Box::new(connection)));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
+
+            assert!(hub.disconnect_connection("connection").await);
+            assert!(handle.await.unwrap().is_ok());
+            disconnected(&mut rx).await;
+            assert!(hub.inner.lock().await.connections.is_empty());
+
+            // Connection was shutdown
+            assert_eq!(peer.read(&mut [0; 64]).await.unwrap(), 0);
+        }
+
+        #[tokio::test]
+        async fn drains_queued_frames() {
+            let frames = (1..=3).map(|node| Arc::new(Packet::new_poll_request(Address::try_from_node_address(node).unwrap()).encode_frame())).collect::<Vec<_>>();
+            let (connection, mut peer) = tokio::io::duplex(64);
+            let hub = Hub::new();
+            let mut rx = hub.subscribe(String::from("subscriber")).await;
+            let handle = hub.run_connection(Connection::new("connection", Box::new(connection)));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
+
+            for frame in &frames {
+                hub.broadcast(String::from("source"), frame.clone()).await;
+            }
+            assert!(hub.disconnect_connection("connection").await);
+
+            let mut received = Vec::new();
+            peer.read_to_end(&mut received).await.unwrap();
+            assert_eq!(received, frames.iter().flat_map(|frame| frame.to_vec()).collect::<Vec<_>>());
+            assert!(handle.await.unwrap().is_ok());
+            disconnected(&mut rx).await;
+            assert_eq!(hub.statistics().await.get("connection").map(|statistics| statistics.frames_tx), Some(3));
+        }
+
+        #[tokio::test]
+        async fn same_name() {
+            let (first, _first_peer) = tokio::io::duplex(64);
+            let (second, _second_peer) = tokio::io::duplex(64);
+            let hub = Hub::new();
+            let mut rx = hub.subscribe(String::from("subscriber")).await;
+            let first = hub.run_connection(Connection::new("connection", Box::new(first)));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
+            let second = hub.run_connection(Connection::new("connection", Box::new(second)));
+            assert_eq!(rx.recv().await, Some(SubscriberMessage::Connected(String::from("connection"), ConnectionKind::Stream)));
+
+            assert!(hub.disconnect_connection("connection").await);
+            assert!(first.await.unwrap().is_ok());
+            assert!(second.await.unwrap().is_ok());
+            assert!(hub.inner.lock().await.connections.is_empty());
+        }
+
+        #[tokio::test]
+        async fn unknown_connection() {
+            let hub = Hub::new();
+            assert!(!hub.disconnect_connection("connection").await);
+        }
+    }
 }
@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use cmri_tools::readings::Readings;
-use super::{Hub, SubscriberMessage};
+use cmri_tools::{connection::ConnectionKind, readings::Readings};
+use super::{ConnectionStatistics, Hub, SubscriberMessage};
 
 const READINGS_SIZE: usize = 300; // 5 minutes worth
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 #[expect(clippy::module_name_repetitions)]
 pub enum ConnectionState {
     Connected,
@@ -14,11 +15,51 @@ pub enum ConnectionState {
     Errored(String)
 }
 
+/// A point in time view of the `Hub`'s statistics, suitable for serializing (E.G. to JSON).
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct HubSnapshot {
+    /// The total number of frames handled.
+    pub frames_total: u64,
+    /// The number of frames handled in the last whole second.
+    pub frames_per_sec: u16,
+    /// The total number of bytes handled.
+    pub bytes_total: u64,
+    /// The number of bytes handled in the last whole second.
+    pub bytes_per_sec: u32,
+    /// The connections (ordered by name).
+    pub connections: Vec<ConnectionSnapshot>
+}
+
+/// A point in time view of a connection to the `Hub`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ConnectionSnapshot {
+    /// The connection's name.
+    pub name: String,
+    /// The connection's state.
+    pub state: ConnectionState,
+    /// What the connection is connected to (see `ConnectionKind`).
+    pub kind: Option<String>,
+    /// The average number of bytes returned by each read of the connection.
+    pub bytes_per_read: Option<f64>,
+    /// The number of frames received from the connection.
+    pub frames_rx: u64,
+    /// The number of bytes received from the connection.
+    pub bytes_rx: u64,
+    /// The number of frames sent to the connection.
+    pub frames_tx: u64,
+    /// The number of bytes sent to the connection.
+    pub bytes_tx: u64,
+    /// How many seconds ago a frame was last received from the connection.
+    pub last_seen_secs: Option<f64>
+}
+
 #[derive(Debug)]
 pub struct State {
     frames: (u16, u64, Readings<u16, READINGS_SIZE>),  // (current second, total, previous READINGS_SIZE)
     bytes: (u32, u64, Readings<u32, READINGS_SIZE>),   // (current second, total, previous READINGS_SIZE)
     connections: HashMap<String, ConnectionState>,
+    kinds: HashMap<String, ConnectionKind>,
+    statistics: HashMap<String, ConnectionStatistics>,
     server: Option<String>
 }
 
@@ -29,6 +70,8 @@ impl State {
                 frames: (0, 0, Readings::new()),
                 bytes: (0, 0, Readings::new()),
                 connections: HashMap::new(),
+                kinds: HashMap::new(),
+                statistics: HashMap::new(),
                 server: None
             }
         ));
@@ -38,7 +81,7 @@ impl State {
             hub.subscribe(String::from("State monitor")).await
         );
 
-        Self::run_ticker(state.clone());
+        Self::run_ticker(state.clone(), hub.clone());
         state
     }
 
@@ -47,6 +90,16 @@ impl State {
         self.connections.iter()
     }
 
+    /// What a connection is connected to, if it's been connected.
+    pub fn kind(&self, name: &str) -> Option<&ConnectionKind> {
+        self.kinds.get(name)
+    }
+
+    /// The statistics of each connection, updated every second.
+    pub const fn statistics(&self) -> &HashMap<String, ConnectionStatistics> {
+        &self.statistics
+    }
+
     /// The address of the server, if it's running.
     pub const fn server(&self) -> Option<&String> {
         self.server.as_ref()
@@ -64,14 +117,45 @@ impl State {
         &self.bytes
     }
 
+    /// Take a snapshot of the current statistics.
+    pub fn snapshot(&self) -> HubSnapshot {
+        let mut connections = self.connections.iter()
+            .map(|(name, state)| {
+                let statistics = self.statistics.get(name).cloned().unwrap_or_default();
+                ConnectionSnapshot {
+                    name: name.clone(),
+                    state: state.clone(),
+                    kind: self.kinds.get(name).map(ToString::to_string),
+                    bytes_per_read: statistics.reads.bytes_per_read(),
+                    frames_rx: statistics.frames_rx,
+                    bytes_rx: statistics.bytes_rx,
+                    frames_tx: statistics.frames_tx,
+                    bytes_tx: statistics.bytes_tx,
+                    last_seen_secs: statistics.last_seen.map(|instant| instant.elapsed().as_secs_f64())
+                }
+            })
+            .collect::<Vec<_>>();
+        connections.sort_by(|a, b| a.name.cmp(&b.name));
+
+        HubSnapshot {
+            frames_total: self.frames.1,
+            frames_per_sec: self.frames.2.last().copied().unwrap_or_default(),
+            bytes_total: self.bytes.1,
+            bytes_per_sec: self.bytes.2.last().copied().unwrap_or_default(),
+            connections
+        }
+    }
+
     /// Run the receiver to update this `State` from event omitted by the `Hub`.
     fn run_receiver(state: Arc<Mutex<Self>>, mut receiver: super::SubscriberRx) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
             loop {
                 match receiver.recv().await {
                     None => break,
-                    Some(SubscriberMessage::Connected(address)) => {
-                        state.lock().await.connections.insert(address, ConnectionState::Connected);
+                    Some(SubscriberMessage::Connected(address, kind)) => {
+                        let mut state = state.lock().await;
+                        state.kinds.insert(address.clone(), kind);
+                        state.connections.insert(address, ConnectionState::Connected);
                     },
                     Some(SubscriberMessage::Disconnected(address)) => {
                         if state.lock().await.connections.get(&address).is_some_and(|a| !matches!(a, ConnectionState::Errored(_))) {
@@ -97,13 +181,15 @@ impl State {
         })
     }
 
-    fn run_ticker(state: Arc<Mutex<Self>>) -> tokio::task::JoinHandle<anyhow::Result<()>> {
+    fn run_ticker(state: Arc<Mutex<Self>>, hub: Hub) -> tokio::task::JoinHandle<anyhow::Result<()>> {
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
             interval.tick().await; // Disregard the first tick as it's immediate
             loop {
                 interval.tick().await;
+                let statistics = hub.statistics().await;
                 let mut state = state.lock().await;
+                state.statistics = statistics;
                 let frames = std::mem::take(&mut state.frames.0);
                 state.frames.2.push(frames);
                 let bytes = std::mem::take(&mut state.bytes.0);
@@ -126,6 +212,8 @@ mod tests {
             frames: (0, 0, Readings::new()),
             bytes: (0, 0, Readings::new()),
             connections: HashMap::new(),
+            kinds: HashMap::new(),
+            statistics: HashMap::new(),
             server: None
         };
 
@@ -148,6 +236,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn snapshot() {
+        let mut state = State {
+            frames: (1, 5, Readings::new()),
+            bytes: (6, 40, Readings::new()),
+            connections: HashMap::new(),
+            kinds: HashMap::new(),
+            statistics: HashMap::new(),
+            server: None
+        };
+        state.frames.2.push(4);
+        state.bytes.2.push(34);
+        state.connections.insert(String::from("B"), ConnectionState::Errored(String::from("Error")));
+        state.connections.insert(String::from("A"), ConnectionState::Connected);
+        state.kinds.insert(String::from("A"), ConnectionKind::Tcp { peer: "192.168.1.2:5000".parse().unwrap() });
+        state.statistics.insert(String::from("A"), ConnectionStatistics { frames_rx: 2, bytes_rx: 12, frames_tx: 1, bytes_tx: 6, ..ConnectionStatistics::default() });
+
+        let snapshot = state.snapshot();
+        assert_eq!(
+            snapshot,
+            HubSnapshot {
+                frames_total: 5,
+                frames_per_sec: 4,
+                bytes_total: 40,
+                bytes_per_sec: 34,
+                connections: vec![
+                    ConnectionSnapshot { name: String::from("A"), state: ConnectionState::Connected, kind: Some(String::from("TCP 192.168.1.2:5000")), bytes_per_read: None, frames_rx: 2, bytes_rx: 12, frames_tx: 1, bytes_tx: 6, last_seen_secs: None },
+                    ConnectionSnapshot { name: String::from("B"), state: ConnectionState::Errored(String::from("Error")), kind: None, bytes_per_read: None, frames_rx: 0, bytes_rx: 0, frames_tx: 0, bytes_tx: 0, last_seen_secs: None }
+                ]
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&snapshot).unwrap(),
+            r#"{"frames_total":5,"frames_per_sec":4,"bytes_total":40,"bytes_per_sec":34,"connections":[{"name":"A","state":"connected","kind":"TCP 192.168.1.2:5000","bytes_per_read":null,"frames_rx":2,"bytes_rx":12,"frames_tx":1,"bytes_tx":6,"last_seen_secs":null},{"name":"B","state":{"errored":"Error"},"kind":null,"bytes_per_read":null,"frames_rx":0,"bytes_rx":0,"frames_tx":0,"bytes_tx":0,"last_seen_secs":null}]}"#
+        );
+    }
+
     mod updates_connections {
         use super::*;
         use crate::hub::SubscriberMessage;
@@ -165,9 +290,10 @@ mod tests {
         #[tokio::test]
         async fn connected() {
             let (hub, state) = create().await;
-            hub.publish(SubscriberMessage::Connected(String::from("B"))).await;
+            hub.publish(SubscriberMessage::Connected(String::from("B"), ConnectionKind::Channel)).await;
             tokio::time::sleep(std::time::Duration::from_millis(1)).await;
             assert_eq!(state.lock().await.connections.get("B"), Some(&ConnectionState::Connected));
+            assert_eq!(state.lock().await.kind("B"), Some(&ConnectionKind::Channel));
         }
 
         #[tokio::test]
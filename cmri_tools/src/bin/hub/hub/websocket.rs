@@ -0,0 +1,163 @@
+//! Bridge WebSocket clients (E.G. a browser) into the `Hub`.
+
+use anyhow::Context;
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{WebSocketStream, tungstenite::Message};
+use tracing::{debug, info, warn, error};
+use cmri::frame::Raw as RawFrame;
+use cmri_tools::connection::{Connection, ConnectionKind, channel_pair};
+use super::{Hub, SubscriberMessage};
+
+impl Hub {
+    /// Start a WebSocket server, and add incoming connections to the `Hub`.
+    ///
+    /// Each binary message carries one frame (in each direction), other messages are ignored.
+    /// Each client is bridged to the `Hub` as a `Connection` named `ws://ADDRESS`.
+    ///
+    /// # Errors
+    ///
+    /// If the server can't bind or be configured, see:
+    ///   * `std::net::TcpListener::bind`
+    ///   * `std::net::TcpListener::set_nonblocking`
+    #[expect(clippy::missing_panics_doc)]
+    pub async fn start_websocket_server(&self, address: &str) -> anyhow::Result<std::net::SocketAddr> {
+        debug!("Starting WebSocket server on {address}");
+        let listener = std::net::TcpListener::bind(address).context(format!("Starting WebSocket server on {address}"))?;
+        listener.set_nonblocking(true).context(format!("Starting WebSocket server on {address}"))?;
+        let address = listener.local_addr().context(format!("Starting WebSocket server on {address}"))?;
+        info!("Started WebSocket server on {address}");
+        self.publish(SubscriberMessage::ServerStarted(format!("ws://{address}"))).await;
+
+        let hub = self.clone();
+        tokio::spawn(async move {
+            let listener = TcpListener::from_std(listener).expect("A std listener to convert to a tokio listener");
+            loop {
+                match listener.accept().await {
+                    Err(error) => error!("Couldn't get WebSocket client: {error}"),
+                    Ok((stream, addr)) => {
+                        info!("WebSocket connection from {addr}");
+                        let hub = hub.clone();
+                        tokio::spawn(async move {
+                            match tokio_tungstenite::accept_async(stream).await {
+                                Err(error) => error!("Couldn't run WebSocket connection for {addr}: {error}"),
+                                Ok(websocket) => {
+                                    let (connection, bridged) = channel_pair();
+                                    hub.run_connection(connection.with_name(format!("ws://{addr}")).with_kind(ConnectionKind::WebSocket { peer: addr }));
+                                    bridge(websocket, bridged).await;
+                                }
+                            }
+                        });
+                    }
+                }
+            }
+        });
+        Ok(address)
+    }
+}
+
+/// Move frames between a WebSocket and a connection, until either is closed.
+async fn bridge(mut websocket: WebSocketStream<TcpStream>, mut connection: Connection) {
+    let name = websocket.get_ref().peer_addr().map_or_else(|_| String::from("WebSocket"), |addr| format!("ws://{addr}"));
+    loop {
+        tokio::select! {
+            message = websocket.next() => match message {
+                None | Some(Ok(Message::Close(_))) => break,
+                Some(Err(error)) => {
+                    error!("Read error on {name}: {error}");
+                    break;
+                },
+                Some(Ok(Message::Binary(bytes))) => match RawFrame::try_from(bytes.as_slice()) {
+                    Err(error) => warn!("Received bad frame from {name}: {error}"),
+                    Ok(frame) => if connection.send(&frame).await.is_err() { break }
+                },
+                Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_))) => (), // Pings are answered by tungstenite
+                Some(Ok(message)) => warn!("Ignoring non-binary message from {name}: {message:?}")
+            },
+            frame = connection.receive() => match frame {
+                Err(_) => break, // The hub has closed the connection
+                Ok(frame) => if let Err(error) = websocket.send(Message::Binary(frame.to_vec())).await {
+                    error!("Write error on {name}: {error}");
+                    break;
+                }
+            }
+        }
+    }
+    let _ = connection.shutdown().await;
+    let _ = websocket.close(None).await;
+}
+
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+mod tests {
+    use cmri::{Address, packet::Packet};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use super::*;
+
+    async fn connect(address: std::net::SocketAddr) -> WebSocketStream<tokio_tungstenite::MaybeTlsStream<TcpStream>> {
+        tokio_tungstenite::connect_async(format!("ws://{address}")).await.unwrap().0
+    }
+
+    fn frame(node_address: u8) -> RawFrame {
+        Packet::new_poll_request(Address::try_from_node_address(node_address).unwrap()).encode_frame()
+    }
+
+    /// A hub with a WebSocket server and a TCP client, both connected.
+    async fn connected() -> (Hub, WebSocketStream<tokio_tungstenite::MaybeTlsStream<TcpStream>>, TcpStream) {
+        let hub = Hub::new();
+        let mut subscription = hub.subscribe(String::from("test")).await;
+        let websocket = connect(hub.start_websocket_server("localhost:0").await.unwrap()).await;
+        let tcp = TcpStream::connect(hub.start_server("localhost:0").await.unwrap()).await.unwrap();
+        let mut connected = 0;
+        while connected < 2 {
+            if let Some(SubscriberMessage::Connected(..)) = subscription.recv().await {
+                connected += 1;
+            }
+        }
+        (hub, websocket, tcp)
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        let (_hub, mut websocket, mut tcp) = connected().await;
+        let mut buffer = [0; 8];
+
+        websocket.send(Message::Binary(frame(10).to_vec())).await.unwrap();
+        let len = tcp.read(&mut buffer).await.unwrap();
+        assert_eq!(buffer[..len], *frame(10));
+
+        tcp.write_all(&frame(20)).await.unwrap();
+        assert_eq!(websocket.next().await.unwrap().unwrap(), Message::Binary(frame(20).to_vec()));
+    }
+
+    #[tokio::test]
+    async fn named_by_peer() {
+        let hub = Hub::new();
+        let mut subscription = hub.subscribe(String::from("test")).await;
+        let address = hub.start_websocket_server("localhost:0").await.unwrap();
+        assert_eq!(subscription.recv().await, Some(SubscriberMessage::ServerStarted(format!("ws://{address}"))));
+
+        let websocket = connect(address).await;
+        let local = match websocket.get_ref() {
+            tokio_tungstenite::MaybeTlsStream::Plain(stream) => stream.local_addr().unwrap(),
+            _ => unreachable!()
+        };
+        assert_eq!(subscription.recv().await, Some(SubscriberMessage::Connected(format!("ws://{local}"), ConnectionKind::WebSocket { peer: local })));
+
+        drop(websocket);
+        assert_eq!(subscription.recv().await, Some(SubscriberMessage::Disconnected(format!("ws://{local}"))));
+    }
+
+    #[tokio::test]
+    async fn skips_other_messages() {
+        let (_hub, mut websocket, mut tcp) = connected().await;
+        let mut buffer = [0; 8];
+
+        websocket.send(Message::Text(String::from("Hello"))).await.unwrap();
+        websocket.send(Message::Binary(vec![0xFF, 0xFF])).await.unwrap(); // Too short to be a frame
+        websocket.send(Message::Binary(frame(10).to_vec())).await.unwrap();
+        let len = tcp.read(&mut buffer).await.unwrap();
+        assert_eq!(buffer[..len], *frame(10));
+    }
+}
@@ -6,11 +6,13 @@ use anyhow::Context;
 use tracing::info;
 use tokio::sync::Mutex;
 use std::sync::Arc;
+use std::collections::HashMap;
+use cmri::{Address, AddressSet};
 
 mod cli;
 mod gui;
 mod hub;
-use hub::{Hub, state::State};
+use hub::{Hub, BackoffPolicy, state::State};
 
 #[allow(clippy::missing_panics_doc, clippy::missing_errors_doc)]
 fn main() -> anyhow::Result<()> {
@@ -25,25 +27,8 @@ fn main() -> anyhow::Result<()> {
     let hub_state: anyhow::Result<(Hub, Arc<Mutex<State>>)> = runtime.block_on(async {
         let (hub, state) = hub::new().await;
 
-        // Setup a TCP server
-        if let Some(address) = cli.get_one::<String>("server") {
-            hub.start_server(address).await.context(format!("Starting TCP server {address:?}."))?;
-        }
-
-        // Setup TCP clients
-        if let Some(addresses) = cli.get_many::<String>("network") {
-            for address in addresses {
-                hub.add_network(address).context(format!("Connecting to TCP server {address:?}."))?;
-            }
-        }
-
-        // Setup Serial ports
-        if let Some(addresses) = cli.get_many::<String>("serial") {
-            for address in addresses {
-                let (port, baud) = cmri_tools::connection::port_baud_from_str(address).context(format!("Parsing serial port {address:?}."))?;
-                hub.add_serial_port(port, baud).context(format!("Opening serial port {port:?}."))?;
-            }
-        }
+        configure(&hub, &cli).await;
+        connect(&hub, &cli).await?;
 
         Ok((hub, state))
     });
@@ -52,20 +37,131 @@ fn main() -> anyhow::Result<()> {
     // Run the GUI, or print statistics every second.
     if cli.get_flag("gui") {
         gui::run(hub, state, runtime.handle().clone());
+    } else if cli.get_flag("json") {
+        loop {
+            let snapshot = state.blocking_lock().snapshot();
+            println!("{}", serde_json::to_string(&snapshot)?);
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
     } else {
         loop {
-            {
-                let state = state.blocking_lock();
-                info!(
-                    "Frames: {} ({}/s)\nBytes: {} ({}/s)\nConnections:\n",
-                    readable::num::Unsigned::from(state.frames().1),
-                    readable::num::Unsigned::from(state.frames().2.last().copied().unwrap_or_default()),
-                    readable_byte::readable_byte::b(state.bytes().1).to_string_as(true),
-                    readable_byte::readable_byte::b(state.bytes().2.last().copied().unwrap_or_default().into()).to_string_as(true)
-                );
-            }
+            log_statistics(&state.blocking_lock());
             std::thread::sleep(std::time::Duration::from_secs(1));
         }
     }
     Ok(())
 }
+
+/// Apply the settings given on the command line to the hub.
+async fn configure(hub: &Hub, cli: &clap::ArgMatches) {
+    if let Some(len) = cli.get_one::<u16>("read-buffer") {
+        hub.set_read_buffer((*len).into()).await;
+    }
+
+    if cli.get_flag("replay-on-connect") {
+        hub.set_replay_on_connect(true).await;
+    }
+
+    if cli.get_flag("drop-invalid-types") {
+        hub.set_drop_invalid_types(true).await;
+    }
+
+    if let Some(milliseconds) = cli.get_one::<u64>("loop-guard") {
+        hub.set_loop_guard(std::time::Duration::from_millis(*milliseconds)).await;
+    }
+
+    if let Some(priorities) = cli.get_many::<(String, u8)>("priority") {
+        for (name, priority) in priorities {
+            hub.set_priority(name.clone(), *priority).await;
+        }
+    }
+
+    if let Some(groups) = cli.get_many::<(String, u8)>("group") {
+        for (name, group) in groups {
+            hub.set_group(name.clone(), Some(*group)).await;
+        }
+    }
+
+    if let Some(filters) = cli.get_many::<(String, (Address, Address))>("filter") {
+        let mut sets = HashMap::<&String, AddressSet>::new();
+        for (name, (first, last)) in filters {
+            sets.entry(name).or_default().extend(Address::iter().filter(|address| (*first..=*last).contains(address)));
+        }
+        for (name, set) in sets {
+            hub.set_filter(name.clone(), Some(set)).await;
+        }
+    }
+
+    if let Some(names) = cli.get_many::<String>("echo") {
+        for name in names {
+            hub.set_echo(name.clone(), true).await;
+        }
+    }
+}
+
+/// Start the servers, and make the connections, given on the command line.
+///
+/// # Errors
+///
+/// If a server can't be started, or a connection can't be made.
+async fn connect(hub: &Hub, cli: &clap::ArgMatches) -> anyhow::Result<()> {
+    // Setup a TCP server
+    if let Some(address) = cli.get_one::<String>("server") {
+        hub.start_server(address).await.context(format!("Starting TCP server {address:?}."))?;
+    }
+
+    // Setup a WebSocket server
+    if let Some(address) = cli.get_one::<String>("websocket") {
+        hub.start_websocket_server(address).await.context(format!("Starting WebSocket server {address:?}."))?;
+    }
+
+    // Setup TCP clients
+    if let Some(addresses) = cli.get_many::<String>("network") {
+        for address in addresses {
+            if cli.get_flag("reconnect") {
+                hub.add_network_reconnecting(address, BackoffPolicy::default());
+            } else {
+                hub.add_network(address).context(format!("Connecting to TCP server {address:?}."))?;
+            }
+        }
+    }
+
+    // Setup Serial ports
+    if let Some(addresses) = cli.get_many::<String>("serial") {
+        for address in addresses {
+            let (port, baud) = cmri_tools::connection::port_baud_from_str(address).context(format!("Parsing serial port {address:?}."))?;
+            hub.add_serial_port(port, baud).context(format!("Opening serial port {port:?}."))?;
+        }
+    }
+
+    // Replay recordings
+    if let Some(paths) = cli.get_many::<std::path::PathBuf>("replay") {
+        let speed = cli.get_one::<f64>("speed").copied().unwrap_or(1.0);
+        for path in paths {
+            hub.add_replay(path, speed).context(format!("Replaying {}.", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Log the hub's statistics.
+fn log_statistics(state: &State) {
+    info!(
+        "Frames: {} ({}/s)\nBytes: {} ({}/s)\nConnections:\n",
+        readable::num::Unsigned::from(state.frames().1),
+        readable::num::Unsigned::from(state.frames().2.last().copied().unwrap_or_default()),
+        readable_byte::readable_byte::b(state.bytes().1).to_string_as(true),
+        readable_byte::readable_byte::b(state.bytes().2.last().copied().unwrap_or_default().into()).to_string_as(true)
+    );
+    for (name, statistics) in state.statistics() {
+        info!(
+            "{name}: {:.1} bytes/read, {:.1} frames/s, received {} frames ({}), sent {} frames ({})",
+            statistics.reads.bytes_per_read().unwrap_or_default(),
+            statistics.receive_rates.frames_per_sec(),
+            readable::num::Unsigned::from(statistics.frames_rx),
+            readable_byte::readable_byte::b(statistics.bytes_rx).to_string_as(true),
+            readable::num::Unsigned::from(statistics.frames_tx),
+            readable_byte::readable_byte::b(statistics.bytes_tx).to_string_as(true)
+        );
+    }
+}
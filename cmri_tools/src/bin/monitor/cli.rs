@@ -6,7 +6,7 @@ pub fn command() -> clap::Command {
         .next_line_help(true)
         .group(
             clap::ArgGroup::new("connection")
-                .args(["serial", "network"])
+                .args(["serial", "network", "replay"])
         )
         .arg(common::serial())
         .arg(common::network())
@@ -21,17 +21,120 @@ pub fn command() -> clap::Command {
                 .value_parser(clap::value_parser!(u8).range(..=127))
         )
 
+        .arg(
+            clap::Arg::new("only-node")
+                .long("only-node")
+                .value_name("ADDRESS")
+                .action(clap::ArgAction::Append)
+                .required(false)
+                .help("Only show the given node, may be repeated (everything is still counted)")
+                .value_parser(clap::value_parser!(cmri::Address))
+        )
+        .arg(
+            clap::Arg::new("only-type")
+                .long("only-type")
+                .value_name("TYPE")
+                .action(clap::ArgAction::Append)
+                .required(false)
+                .help("Only show nodes which have sent or been sent this message type, may be repeated (everything is still counted)")
+                .value_parser(["I", "P", "R", "T", "S"])
+        )
+
+        .arg(
+            clap::Arg::new("stale-after")
+                .long("stale-after")
+                .value_name("SECONDS")
+                .default_value("10")
+                .help("How long a node can go without sending receive data before it's flagged as stale")
+                .value_parser(clap::value_parser!(u64).range(1..))
+        )
+
         .arg(common::load_nodes())
+        .arg(
+            clap::Arg::new("load-session")
+                .long("load-session")
+                .value_name("FILE")
+                .required(false)
+                .conflicts_with("load-nodes")
+                .value_hint(clap::ValueHint::FilePath)
+                .help("Load the nodes from a saved session, replaying its recording if not connecting to anything else")
+                .value_parser(clap::value_parser!(std::path::PathBuf))
+        )
+
+        .arg(
+            clap::Arg::new("record")
+                .long("record")
+                .value_name("FILE")
+                .required(false)
+                .value_hint(clap::ValueHint::FilePath)
+                .help("Record every frame received (including bad ones) to FILE, along with when it was received")
+                .value_parser(clap::value_parser!(std::path::PathBuf))
+        )
+        .arg(
+            clap::Arg::new("replay")
+                .long("replay")
+                .value_name("FILE")
+                .required(false)
+                .value_hint(clap::ValueHint::FilePath)
+                .help("Replay a recording (see --record) instead of connecting to a network")
+                .value_parser(clap::value_parser!(std::path::PathBuf))
+        )
+        .arg(common::speed())
 }
 
 mod common {
     include!("../../cli/args.rs");
 }
 
+#[allow(clippy::missing_panics_doc, reason="tests")]
 #[cfg(test)]
 mod tests {
     #[test]
     fn verify_command() {
         super::command().debug_assert();
     }
+
+    #[test]
+    fn parse_speed() {
+        assert_eq!(super::common::parse_speed("2.5"), Ok(2.5));
+        assert_eq!(super::common::parse_speed("0"), Ok(0.0));
+        assert!(super::common::parse_speed("-1").is_err());
+        assert!(super::common::parse_speed("inf").is_err());
+        assert!(super::common::parse_speed("fast").is_err());
+    }
+
+    #[test]
+    fn only_node() {
+        let matches = super::command().try_get_matches_from(["monitor", "--only-node", "1", "--only-node", "127"]).unwrap();
+        assert_eq!(matches.get_many::<cmri::Address>("only-node").unwrap().map(cmri::Address::as_node_address).collect::<Vec<_>>(), vec![1, 127]);
+        assert!(super::command().try_get_matches_from(["monitor", "--only-node", "128"]).is_err());
+    }
+
+    #[test]
+    fn only_type() {
+        let matches = super::command().try_get_matches_from(["monitor", "--only-type", "P", "--only-type", "R"]).unwrap();
+        assert_eq!(matches.get_many::<String>("only-type").unwrap().map(String::as_str).collect::<Vec<_>>(), vec!["P", "R"]);
+        assert!(super::command().try_get_matches_from(["monitor", "--only-type", "S"]).is_ok());
+        assert!(super::command().try_get_matches_from(["monitor", "--only-type", "X"]).is_err());
+    }
+
+    #[test]
+    fn stale_after() {
+        assert_eq!(super::command().try_get_matches_from(["monitor"]).unwrap().get_one::<u64>("stale-after"), Some(&10));
+        assert_eq!(super::command().try_get_matches_from(["monitor", "--stale-after", "30"]).unwrap().get_one::<u64>("stale-after"), Some(&30));
+        assert!(super::command().try_get_matches_from(["monitor", "--stale-after", "0"]).is_err());
+    }
+
+    #[test]
+    fn load_session() {
+        let matches = super::command().try_get_matches_from(["monitor", "--load-session", "session.json"]).unwrap();
+        assert_eq!(matches.get_one::<std::path::PathBuf>("load-session"), Some(&std::path::PathBuf::from("session.json")));
+        assert!(super::command().try_get_matches_from(["monitor", "--load-session", "session.json", "--load-nodes", "nodes.json"]).is_err());
+    }
+
+    #[test]
+    fn speed_requires_replay() {
+        assert!(super::command().try_get_matches_from(["monitor", "--speed", "2"]).is_err());
+        assert!(super::command().try_get_matches_from(["monitor", "--replay", "file.rec", "--speed", "2"]).is_ok_and(|matches| matches.get_one::<f64>("speed") == Some(&2.0)));
+    }
 }
@@ -1,14 +1,17 @@
+use std::io::Write;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use anyhow::Context;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
 use tracing::{warn, trace};
 use cmri::{NodeSort, node_configuration::node_cards::NodeCard};
-use cmri_tools::{file, gui};
-use crate::monitor::{State, Statistics, Node, run_connection, run_ticker};
+use cmri_tools::{connection::Connection, file, gui};
+use crate::monitor::{Filter, State, Statistics, Node, NodeEvent, NodeEventKind, Recorder, run_connection, run_ticker};
 
 pub const APP_TITLE: &str = "CMRInet Monitor";
 
 #[expect(clippy::unwrap_used, clippy::missing_panics_doc)]
-pub fn run(cli_args: &clap::ArgMatches, tokio_handle: tokio::runtime::Handle) {
+pub fn run(cli_args: &clap::ArgMatches, replay: Option<Connection>, recorder: Option<Recorder>, recording_path: Option<std::path::PathBuf>, session: Option<(std::path::PathBuf, file::Session)>, tokio_handle: tokio::runtime::Handle) {
     let mut show_nodes = [false; 128];
     if let Some(addresses) = cli_args.get_many::<u8>("open-node") {
         for address in addresses {
@@ -21,7 +24,24 @@ pub fn run(cli_args: &clap::ArgMatches, tokio_handle: tokio::runtime::Handle) {
             }
         }
     }
-    let connection_state = tokio_handle.block_on(async { gui::connection::State::new(cli_args) });
+    let filter = Filter::new(
+        cli_args.get_many::<cmri::Address>("only-node").into_iter().flatten().copied(),
+        cli_args.get_many::<String>("only-type").into_iter().flatten().filter_map(|message_type| message_type.bytes().next())
+    );
+    let filter_nodes = filter.nodes().iter().map(|address| address.as_node_address().to_string()).collect::<Vec<_>>().join(" ");
+    let mut state = State::default();
+    state.set_filter(filter);
+    if let Some(seconds) = cli_args.get_one::<u64>("stale-after") {
+        state.set_stale_after(Duration::from_secs(*seconds));
+    }
+    let session_path = session.map(|(path, session)| {
+        state.load_nodes(session.nodes.into_iter().map(Some).collect());
+        path
+    });
+    let connection_state = replay.map_or_else(
+        || tokio_handle.block_on(async { gui::connection::State::new(cli_args) }),
+        gui::connection::State::Connected
+    );
     let file_path = cli_args.get_one::<std::path::PathBuf>("load-nodes").cloned();
 
     let options = eframe::NativeOptions {
@@ -39,10 +59,22 @@ pub fn run(cli_args: &clap::ArgMatches, tokio_handle: tokio::runtime::Handle) {
                 egui_modal::Modal::new(&cc.egui_ctx, "Modal").with_style(&style)
             };
             Ok(Box::new(App {
-                state: Arc::new(Mutex::new(State::default())),
+                state: Arc::new(Mutex::new(state)),
                 show_nodes,
+                show_events: false,
+                show_packets: false,
+                show_bad_packets: false,
+                show_bus_usage: false,
+                show_filter: false,
+                filter_nodes,
                 connection_state,
                 file_path,
+                session_path,
+                statistics_path: None,
+                recorder,
+                recording_path,
+                disconnect: None,
+                connection_task: None,
                 modal,
                 tokio_handle
             }))
@@ -50,19 +82,36 @@ pub fn run(cli_args: &clap::ArgMatches, tokio_handle: tokio::runtime::Handle) {
     ).unwrap();
 }
 
+#[expect(clippy::struct_excessive_bools, reason = "Whether each window is shown")]
 struct App {
     state: Arc<Mutex<State>>,
     show_nodes: [bool; 128],
+    show_events: bool,
+    show_packets: bool,
+    show_bad_packets: bool,
+    show_bus_usage: bool,
+    show_filter: bool,
+    filter_nodes: String,
     connection_state: gui::connection::State,
     file_path: Option<std::path::PathBuf>,
+    session_path: Option<std::path::PathBuf>,
+    statistics_path: Option<std::path::PathBuf>,
+    /// Handed to the connection when it's made, as there's no reconnecting after Disconnect
+    /// (the monitor only ever makes one connection) the recording ends with the connection.
+    recorder: Option<Recorder>,
+    /// Where the traffic is being recorded to or replayed from, saved with the session.
+    recording_path: Option<std::path::PathBuf>,
+    /// Notified to close the connection, once it's running.
+    disconnect: Option<Arc<Notify>>,
+    /// The task running the connection, awaited on exit so the recording is flushed.
+    connection_task: Option<tokio::task::JoinHandle<std::io::Result<()>>>,
     modal: egui_modal::Modal,
     tokio_handle: tokio::runtime::Handle
 }
 
-impl eframe::App for App {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.modal.show_dialog();
-
+impl App {
+    /// Show the menu bar.
+    fn show_menu(&mut self, ctx: &egui::Context) {
         egui::TopBottomPanel::top("MenuPanel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
@@ -76,10 +125,159 @@ impl eframe::App for App {
                             .filter_map(|n| file::Node::try_from(n).ok() )
                             .collect()
                     );
+                    gui::menu::file::save_session(ui, &self.modal, &mut self.session_path, || {
+                        let state = self.state.blocking_lock();
+                        file::Session {
+                            nodes: state.nodes().iter().filter_map(|n| file::Node::try_from(n).ok()).collect(),
+                            statistics: state.statistics().into(),
+                            recording: self.recording_path.clone()
+                        }
+                    });
+                    if ui.button("Save Statistics").clicked() {
+                        self.save_statistics();
+                    }
+                    if ui.button("Merge Recording").clicked() {
+                        self.merge_recording();
+                    }
+                    if ui.add_enabled(self.disconnect.is_some(), egui::Button::new("Disconnect")).clicked() {
+                        if let Some(disconnect) = self.disconnect.take() {
+                            disconnect.notify_one();
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Title(format!("{APP_TITLE}: Disconnected")));
+                        }
+                        ui.close_menu();
+                    }
                     gui::menu::file::exit(ui);
                 });
+                ui.menu_button("View", |ui| {
+                    ui.checkbox(&mut self.show_events, "Event Log");
+                    ui.checkbox(&mut self.show_packets, "Packet Log");
+                    ui.checkbox(&mut self.show_bad_packets, "Bad Packets");
+                    ui.checkbox(&mut self.show_bus_usage, "Bus Usage");
+                    ui.checkbox(&mut self.show_filter, "Filter");
+                });
             });
         });
+    }
+
+    /// Show the statistics, plot & list of nodes, and any windows which are open.
+    fn show_running(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let mut state = self.state.blocking_lock();
+        egui_extras::StripBuilder::new(ui)
+            .size(egui_extras::Size::exact(150.0))
+            .size(egui_extras::Size::remainder())
+            .vertical(|mut strip| {
+                strip.strip(|builder| {
+                    builder
+                        .size(egui_extras::Size::exact(300.0))
+                        .size(egui_extras::Size::remainder())
+                        .horizontal(|mut strip| {
+                            strip.cell(|ui| {
+                                Self::render_statistics(ui, state.statistics());
+                            });
+                            strip.cell(|ui| {
+                                let data = [
+                                    ("Initialization", state.statistics().initialization_packets().history().iter().copied().collect()),
+                                    ("Poll Request", state.statistics().poll_packets().history().iter().copied().collect()),
+                                    ("Receive Data", state.statistics().receive_data_packets().history().iter().copied().collect()),
+                                    ("Transmit Data", state.statistics().transmit_data_packets().history().iter().copied().collect()),
+                                    ("Set", state.statistics().set_packets().history().iter().copied().collect()),
+                                    #[cfg(feature = "experimenter")]
+                                    ("Unknown", state.statistics().unknown_packets().history().iter().copied().collect())
+                                ];
+                                Self::render_plot(ui, data.as_slice());
+                            });
+                        });
+                });
+
+                strip.cell(|ui| {
+                    Self::render_list(ui, &state, &mut self.show_nodes);
+                });
+            });
+
+        for (address, show) in self.show_nodes.iter_mut().enumerate() {
+            if *show {
+                Self::show_node(ctx, address, &state.nodes()[address], show);
+            }
+        }
+
+        if self.show_events {
+            Self::show_events(ctx, state.events(), state.filter(), &mut self.show_events, &mut self.show_nodes);
+        }
+
+        if self.show_packets {
+            Self::show_packets(ctx, &state, &mut self.show_packets, &mut self.show_nodes);
+        }
+
+        if self.show_bad_packets {
+            Self::show_bad_packets(ctx, &state, &mut self.show_bad_packets);
+        }
+
+        if self.show_bus_usage {
+            Self::show_bus_usage(ctx, &state.address_rate_histogram(), &mut self.show_bus_usage);
+        }
+
+        if self.show_filter {
+            if let Some(filter) = Self::show_filter(ctx, state.filter(), &mut self.filter_nodes, &mut self.show_filter) {
+                state.set_filter(filter);
+            }
+        }
+    }
+
+    /// Start running the connection (recording it if there's a recorder), and load the nodes file if one was chosen.
+    fn connect(&mut self, ctx: &egui::Context, connection: Connection) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Title(format!("{}: {} ({})", APP_TITLE, connection.name(), connection.kind())));
+        let disconnect = Arc::new(Notify::new());
+        self.connection_task = Some(run_connection(connection, self.state.clone(), self.recorder.take(), disconnect.clone(), &self.tokio_handle));
+        self.disconnect = Some(disconnect);
+        run_ticker(self.state.clone(), &self.tokio_handle);
+        if let Some(file_path) = self.file_path.as_ref() {
+            match file::load_nodes(file_path) {
+                Err(error) => gui::modal_error(&self.modal, &error),
+                Ok(nodes) => self.state.blocking_lock().load_nodes(nodes)
+            }
+        }
+    }
+
+    /// Prompt for a recording and merge what's in it into the state (see `State::merge`).
+    fn merge_recording(&self) {
+        if let Some(file) = gui::file_prompt("Merge Recording", None).pick_file() {
+            let other = std::fs::File::open(&file)
+                .and_then(|file_handle| State::read_recording(std::io::BufReader::new(file_handle)))
+                .with_context(|| format!("Failed to read recording {}.", file.display()));
+            match other {
+                Err(error) => gui::modal_error(&self.modal, &error),
+                Ok(other) => self.state.blocking_lock().merge(&other)
+            }
+        }
+    }
+
+    /// Prompt for a file and save the statistics to it, as CSV if it has a ".csv" extension otherwise as JSON.
+    fn save_statistics(&mut self) {
+        let prompt = gui::file_prompt("Save Statistics", self.statistics_path.as_ref()).add_filter("CSV", &["csv"]);
+        if let Some(file) = prompt.save_file() {
+            let state = self.state.blocking_lock();
+            let saved = std::fs::File::create(&file).and_then(|file_handle| {
+                let mut writer = std::io::BufWriter::new(file_handle);
+                if file.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv")) {
+                    state.export_csv(&mut writer)?;
+                } else {
+                    serde_json::to_writer_pretty(&mut writer, &state.export_json())?;
+                }
+                writer.flush()
+            });
+            drop(state);
+            match saved.context("Failed to save statistics.") {
+                Err(error) => gui::modal_error(&self.modal, &error),
+                Ok(()) => { self.statistics_path.replace(file); }
+            }
+        }
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.modal.show_dialog();
+        self.show_menu(ctx);
 
         egui::CentralPanel::default().show(ctx, |ui| {
             let running = self.connection_state.modal(&self.modal, &self.tokio_handle, None, |ui| {
@@ -94,58 +292,26 @@ impl eframe::App for App {
             });
 
             if running {
-                let state = self.state.blocking_lock();
-                egui_extras::StripBuilder::new(ui)
-                    .size(egui_extras::Size::exact(150.0))
-                    .size(egui_extras::Size::remainder())
-                    .vertical(|mut strip| {
-                        strip.strip(|builder| {
-                            builder
-                                .size(egui_extras::Size::exact(300.0))
-                                .size(egui_extras::Size::remainder())
-                                .horizontal(|mut strip| {
-                                    strip.cell(|ui| {
-                                        Self::render_statistics(ui, state.statistics());
-                                    });
-                                    strip.cell(|ui| {
-                                        let data = [
-                                            ("Initialization", state.statistics().initialization_packets().2.as_vec()),
-                                            ("Poll Request", state.statistics().poll_packets().2.as_vec()),
-                                            ("Receive Data", state.statistics().receive_data_packets().2.as_vec()),
-                                            ("Transmit Data", state.statistics().transmit_data_packets().2.as_vec()),
-                                            #[cfg(feature = "experimenter")]
-                                            ("Unknown", state.statistics().unknown_packets().2.as_vec())
-                                        ];
-                                        Self::render_plot(ui, data.as_slice());
-                                    });
-                                });
-                        });
-
-                        strip.cell(|ui| {
-                            Self::render_list(ui, &state, &mut self.show_nodes);
-                        });
-                    });
-
-                for (address, show) in self.show_nodes.iter_mut().enumerate() {
-                    if *show {
-                        Self::show_node(ctx, address, &state.nodes()[address], show);
-                    }
-                }
+                self.show_running(ctx, ui);
             } else if let Some(connection) = self.connection_state.try_get_connection() {
-                ctx.send_viewport_cmd(egui::ViewportCommand::Title(format!("{}: {}", APP_TITLE, connection.name())));
-                run_connection(connection, self.state.clone(), &self.tokio_handle);
-                run_ticker(self.state.clone(), &self.tokio_handle);
-                if let Some(file_path) = self.file_path.as_ref() {
-                    match file::load_nodes(file_path) {
-                        Err(error) => gui::modal_error(&self.modal, &error),
-                        Ok(nodes) => self.state.blocking_lock().load_nodes(nodes)
-                    }
-                }
+                self.connect(ctx, connection);
             }
         });
 
         ctx.request_repaint_after(std::time::Duration::from_millis(100));
     }
+
+    /// Close the connection and wait for it to finish, so the end of the recording is written.
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Some(disconnect) = self.disconnect.take() {
+            disconnect.notify_one();
+        }
+        if let Some(task) = self.connection_task.take() {
+            if let Err(error) = self.tokio_handle.block_on(task) {
+                warn!("Connection didn't finish cleanly: {error}");
+            }
+        }
+    }
 }
 
 impl App {
@@ -179,12 +345,13 @@ impl App {
                                     });
                                     strip.cell(|ui| {
                                         let data = [
-                                            ("Initialization", node.statistics().initialization_packets().2.as_vec()),
-                                            ("Poll Request", node.statistics().poll_packets().2.as_vec()),
-                                            ("Receive Data", node.statistics().receive_data_packets().2.as_vec()),
-                                            ("Transmit Data", node.statistics().transmit_data_packets().2.as_vec()),
+                                            ("Initialization", node.statistics().initialization_packets().history().iter().copied().collect()),
+                                            ("Poll Request", node.statistics().poll_packets().history().iter().copied().collect()),
+                                            ("Receive Data", node.statistics().receive_data_packets().history().iter().copied().collect()),
+                                            ("Transmit Data", node.statistics().transmit_data_packets().history().iter().copied().collect()),
+                                            ("Set", node.statistics().set_packets().history().iter().copied().collect()),
                                             #[cfg(feature = "experimenter")]
-                                            ("Unknown", node.statistics().unknown_packets().2.as_vec())
+                                            ("Unknown", node.statistics().unknown_packets().history().iter().copied().collect())
                                         ];
                                         Self::render_plot(ui, data.as_slice());
                                     });
@@ -217,9 +384,109 @@ impl App {
         });
     }
 
+    /// Show the window for editing the filter, returning the new filter if it was changed.
+    fn show_filter(ctx: &egui::Context, filter: &Filter, nodes: &mut String, show: &mut bool) -> Option<Filter> {
+        let mut message_types = filter.message_types().clone();
+        let mut changed = false;
+        egui::Window::new("Filter")
+            .open(show)
+            .default_size([300.0, 150.0])
+            .show(ctx, |ui| {
+                ui.label("Only show nodes (addresses separated by spaces, leave blank for all)");
+                changed |= ui.text_edit_singleline(nodes).changed();
+                ui.label("Only show nodes which have sent or been sent (none selected for all)");
+                ui.horizontal(|ui| {
+                    for (message_type, name) in [(b'I', "Initialization"), (b'P', "Poll Request"), (b'R', "Receive Data"), (b'T', "Transmit Data"), (b'S', "Set")] {
+                        let mut checked = message_types.contains(&message_type);
+                        if ui.checkbox(&mut checked, name).changed() {
+                            changed = true;
+                            if checked { message_types.insert(message_type); } else { message_types.remove(&message_type); }
+                        }
+                    }
+                });
+            });
+        changed.then(|| Filter::new(
+            nodes.split([' ', ',']).filter_map(|address| address.parse::<cmri::Address>().ok()),
+            message_types
+        ))
+    }
+
+    fn show_events(ctx: &egui::Context, events: &[NodeEvent], filter: &Filter, show: &mut bool, show_nodes: &mut [bool; 128]) {
+        egui::Window::new("Event Log")
+            .open(show)
+            .default_size([300.0, 400.0])
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                    for event in events.iter().rev().filter(|event| filter.allows_node(event.address())) {
+                        let ago = event.at().elapsed().unwrap_or_default();
+                        let mut text = egui::RichText::new(format!("{:?} ago: {event}", Duration::from_secs(ago.as_secs())));
+                        if event.kind() == NodeEventKind::Stale {
+                            text = text.color(ui.visuals().warn_fg_color);
+                        }
+                        if ui.link(text).on_hover_text_at_pointer("Open node").clicked() {
+                            show_nodes[usize::from(event.address().as_node_address())] = true;
+                        }
+                    }
+                });
+            });
+    }
+
+    fn show_packets(ctx: &egui::Context, state: &State, show: &mut bool, show_nodes: &mut [bool; 128]) {
+        egui::Window::new("Packet Log")
+            .open(show)
+            .default_size([300.0, 400.0])
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                    for packet in state.packets().iter().rev().filter(|packet| state.matches_filter(packet)) {
+                        let text = format!("{}: {:?}", packet.address().as_node_address(), packet.payload());
+                        if ui.link(text).on_hover_text_at_pointer("Open node").clicked() {
+                            show_nodes[usize::from(packet.address().as_node_address())] = true;
+                        }
+                    }
+                });
+            });
+    }
+
+    fn show_bad_packets(ctx: &egui::Context, state: &State, show: &mut bool) {
+        egui::Window::new("Bad Packets")
+            .open(show)
+            .default_size([400.0, 400.0])
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+                    let reports = state.bad_packets().iter().rev()
+                        .filter(|report| report.bytes().address().and_then(|address| cmri::Address::try_from_node_address(address).ok()).map_or(true, |address| state.filter().allows_node(address)));
+                    for report in reports {
+                        ui.label(report.to_string());
+                    }
+                });
+            });
+    }
+
+    fn show_bus_usage(ctx: &egui::Context, histogram: &[u32; 128], show: &mut bool) {
+        let bars = (0_u8..).zip(histogram)
+            .filter(|(_, count)| **count > 0)
+            .map(|(address, count)| egui_plot::Bar::new(f64::from(address), f64::from(*count)).name(format!("Node {address}")))
+            .collect();
+        egui::Window::new("Bus Usage")
+            .open(show)
+            .default_size([400.0, 200.0])
+            .show(ctx, |ui| {
+                egui_plot::Plot::new("Bus Usage")
+                    .allow_zoom(false)
+                    .allow_drag(false)
+                    .allow_scroll(false)
+                    .include_x(0.0)
+                    .include_x(127.0)
+                    .include_y(0.0)
+                    .x_axis_label("Node address")
+                    .y_axis_label("Packets in the last second")
+                    .show(ui, |plot_ui| plot_ui.bar_chart(egui_plot::BarChart::new(bars)));
+            });
+    }
+
     #[inline]
     fn render_statistics(ui: &mut egui::Ui, statistics: &Statistics) {
-        let total_packets = statistics.packets().1;
+        let total_packets = statistics.packets().total();
         let do_percent = total_packets > 0;
 
         let layout = egui::Layout { main_align: egui::Align::Center, cross_align: egui::Align::Center, main_justify: true, cross_justify: true, ..*ui.layout() };
@@ -239,45 +506,52 @@ impl App {
                         });
                         body.row(15.00, |mut row| {
                             row.col(|ui| { ui.label("Bad Packets"); });
-                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.bad_packets().1).as_str()); });
+                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.bad_packets().total()).as_str()); });
                             if do_percent {
-                                row.col(|ui| { ui.label(format!("{}%", (statistics.bad_packets().1  * 100) / total_packets)); });
+                                row.col(|ui| { ui.label(format!("{}%", (statistics.bad_packets().total()  * 100) / total_packets)); });
                             }
                         });
                         body.row(15.00, |mut row| {
                             row.col(|ui| { ui.label("Initialization Packets"); });
-                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.initialization_packets().1).as_str()); });
+                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.initialization_packets().total()).as_str()); });
                             if do_percent {
-                                row.col(|ui| { ui.label(format!("{}%", (statistics.initialization_packets().1  * 100) / total_packets)); });
+                                row.col(|ui| { ui.label(format!("{}%", (statistics.initialization_packets().total()  * 100) / total_packets)); });
                             }
                         });
                         body.row(15.00, |mut row| {
                             row.col(|ui| { ui.label("Poll Packets"); });
-                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.poll_packets().1).as_str()); });
+                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.poll_packets().total()).as_str()); });
                             if do_percent {
-                                row.col(|ui| { ui.label(format!("{}%", (statistics.poll_packets().1 * 100) / total_packets)); });
+                                row.col(|ui| { ui.label(format!("{}%", (statistics.poll_packets().total() * 100) / total_packets)); });
                             }
                         });
                         body.row(15.00, |mut row| {
                             row.col(|ui| { ui.label("Receive Data Packets"); });
-                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.receive_data_packets().1).as_str()); });
+                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.receive_data_packets().total()).as_str()); });
                             if do_percent {
-                                row.col(|ui| { ui.label(format!("{}%", (statistics.receive_data_packets().1  * 100) / total_packets)); });
+                                row.col(|ui| { ui.label(format!("{}%", (statistics.receive_data_packets().total()  * 100) / total_packets)); });
                             }
                         });
                         body.row(15.00, |mut row| {
                             row.col(|ui| { ui.label("Transmit Data Packets"); });
-                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.transmit_data_packets().1).as_str()); });
+                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.transmit_data_packets().total()).as_str()); });
                             if do_percent {
-                                row.col(|ui| { ui.label(format!("{}%", (statistics.transmit_data_packets().1  * 100) / total_packets)); });
+                                row.col(|ui| { ui.label(format!("{}%", (statistics.transmit_data_packets().total()  * 100) / total_packets)); });
+                            }
+                        });
+                        body.row(15.00, |mut row| {
+                            row.col(|ui| { ui.label("Set Packets"); });
+                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.set_packets().total()).as_str()); });
+                            if do_percent {
+                                row.col(|ui| { ui.label(format!("{}%", (statistics.set_packets().total()  * 100) / total_packets)); });
                             }
                         });
                         #[cfg(feature = "experimenter")]
                         body.row(15.00, |mut row| {
                             row.col(|ui| { ui.label("Unknown Packets"); });
-                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.unknown_packets().1).as_str()); });
+                            row.col(|ui| { ui.label(readable::num::Unsigned::from(statistics.unknown_packets().total()).as_str()); });
                             if do_percent {
-                                row.col(|ui| { ui.label(format!("{}%", (statistics.unknown_packets().1  * 100) / total_packets)); });
+                                row.col(|ui| { ui.label(format!("{}%", (statistics.unknown_packets().total()  * 100) / total_packets)); });
                             }
                         });
                     });
@@ -286,7 +560,7 @@ impl App {
     }
 
     #[expect(clippy::missing_panics_doc, clippy::cast_possible_truncation, clippy::cast_lossless, clippy::cast_sign_loss)]
-    fn render_plot(ui: &mut egui::Ui, data: &[(&str, Vec<u16>)]) {
+    fn render_plot(ui: &mut egui::Ui, data: &[(&str, Vec<u32>)]) {
         let data = data.iter().map(|(title, values)| {
             let count = values.len() as u16;
             let values = values.iter()
@@ -333,7 +607,7 @@ impl App {
 
     fn render_list(ui: &mut egui::Ui, state: &State, show_nodes: &mut [bool; 128]) {
         ui.spacing_mut().scroll.floating = false;
-        let total_packets = state.statistics().packets().1;
+        let total_packets = state.statistics().packets().total();
         egui_extras::TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
@@ -354,46 +628,60 @@ impl App {
                 header.col(|ui| { ui.label("Initialization Count"); });
             })
             .body(|mut body| {
-                for (address, node) in state.nodes().iter().enumerate() {
-                    if node.has_been_seen() || node.sort().is_some() {
-                        let sort = node.sort().map_or(String::new(), |i| format!("{i}"));
-                        body.row(20.0, |mut row| {
-                            row.col(|ui| { ui.label(format!("{address:3}")); });
-                            row.col(|ui| {
-                                if let Some(name) = node.name() {
-                                    ui.label(name);
-                                }
-                            });
-                            row.col(|ui| {
-                                if let Some(sort) = node.sort() {
-                                    let inputs = sort.configuration().input_bits();
-                                    let outputs = sort.configuration().output_bits();
-                                    ui.label(format!("{sort} with {inputs} inputs and {outputs} outputs"));
-                                }
-                            });
-                            row.col(|ui| {
-                                let value = node.sort()
-                                    .map(|i| readable::num::Unsigned::from(u32::from(i.configuration().transmit_delay()) * 10).to_string())
-                                    .unwrap_or_default();
-                                ui.label(value);
-                            });
-                            row.col(|ui| {
-                                if total_packets > 0 {
-                                    ui.label(format!("{} ({}%)", readable::num::Unsigned::from(node.statistics().packets().1).as_str(), (node.statistics().packets().1 * 100) / total_packets));
+                for node in state.visible_nodes() {
+                    let address = usize::from(node.address().as_node_address());
+                    let sort = node.sort().map_or(String::new(), |i| format!("{i}"));
+                    body.row(20.0, |mut row| {
+                        row.col(|ui| {
+                            if node.is_stale() {
+                                ui.label(egui::RichText::new(format!("{address:3}")).color(ui.visuals().weak_text_color()))
+                                    .on_hover_text(format!("Stale, no receive data for at least {:?}", state.stale_after()));
+                            } else {
+                                ui.label(format!("{address:3}"));
+                            }
+                        });
+                        row.col(|ui| {
+                            if let Some(name) = node.name() {
+                                if node.is_stale() {
+                                    ui.label(egui::RichText::new(name).color(ui.visuals().weak_text_color()));
                                 } else {
-                                    ui.label("0");
+                                    ui.label(name);
                                 }
-                            });
-                            row.col(|ui| {
-                                ui.label(node.initialization_count().to_string());
-                            });
-                            row.col(|ui| {
-                                if ui.button("Open").on_hover_text_at_pointer(format!("Open node {address} ({})", &sort)).clicked() {
-                                    show_nodes[address] = true;
+                            }
+                        });
+                        row.col(|ui| {
+                            if let Some(sort) = node.sort() {
+                                let inputs = sort.configuration().input_bits();
+                                let outputs = sort.configuration().output_bits();
+                                ui.label(format!("{sort} with {inputs} inputs and {outputs} outputs"));
+                                if let Some(len) = node.wrong_size_inputs() {
+                                    ui.colored_label(egui::Color32::RED, "⚠")
+                                        .on_hover_text(format!("Responded with {len} bytes of input data, check the node's configuration"));
                                 }
-                            });
+                            }
                         });
-                    }
+                        row.col(|ui| {
+                            let value = node.sort()
+                                .map(|i| readable::num::Unsigned::from(u32::from(i.configuration().transmit_delay()) * 10).to_string())
+                                .unwrap_or_default();
+                            ui.label(value);
+                        });
+                        row.col(|ui| {
+                            if total_packets > 0 {
+                                ui.label(format!("{} ({}%)", readable::num::Unsigned::from(node.statistics().packets().total()).as_str(), (node.statistics().packets().total() * 100) / total_packets));
+                            } else {
+                                ui.label("0");
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(node.initialization_count().to_string());
+                        });
+                        row.col(|ui| {
+                            if ui.button("Open").on_hover_text_at_pointer(format!("Open node {address} ({})", &sort)).clicked() {
+                                show_nodes[address] = true;
+                            }
+                        });
+                    });
                 }
             });
     }
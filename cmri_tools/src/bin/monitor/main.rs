@@ -1,5 +1,7 @@
 //! A simple GUI app for monitoring a CMRInet bus.
 
+use anyhow::Context;
+
 mod cli;
 mod gui;
 mod monitor;
@@ -13,7 +15,26 @@ fn main() -> anyhow::Result<()> {
 
     let runtime = cmri_tools::tokio_runtime(2)?;
     let cli_args = cli::command().get_matches();
+    let recorder = cli_args.get_one::<std::path::PathBuf>("record")
+        .map(|path| runtime.block_on(monitor::Recorder::create(path)).context(format!("Creating recording {}.", path.display())))
+        .transpose()?;
+    let session = cli_args.get_one::<std::path::PathBuf>("load-session")
+        .map(|path| cmri_tools::file::Session::load(path).map(|session| (path.clone(), session)))
+        .transpose()?;
+    let replay_path = cli_args.get_one::<std::path::PathBuf>("replay").or_else(||
+        // Replay the session's recording if not told to connect to anything else.
+        session.as_ref()
+            .filter(|_| !cli_args.contains_id("serial") && !cli_args.contains_id("network"))
+            .and_then(|(_, session)| session.recording.as_ref())
+    );
+    let replay = replay_path
+        .map(|path| {
+            let speed = cli_args.get_one::<f64>("speed").copied().unwrap_or(1.0);
+            cmri_tools::connection::Connection::new_replay(path, speed).context(format!("Replaying {}.", path.display()))
+        })
+        .transpose()?;
+    let recording_path = cli_args.get_one::<std::path::PathBuf>("record").or(replay_path).cloned();
 
-    gui::run(&cli_args, runtime.handle().clone());
+    gui::run(&cli_args, replay, recorder, recording_path, session, runtime.handle().clone());
     Ok(())
 }
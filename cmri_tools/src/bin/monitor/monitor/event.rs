@@ -0,0 +1,80 @@
+use std::time::SystemTime;
+use cmri::Address;
+
+/// Something which happened to a node on the CMRInet network.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct NodeEvent {
+    pub(super) at: SystemTime,
+    pub(super) address: Address,
+    pub(super) kind: NodeEventKind
+}
+
+/// The sorts of `NodeEvent` which can happen.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum NodeEventKind {
+    /// The node started responding to polls.
+    Appeared,
+    /// The node stopped responding to polls.
+    Stale,
+    /// The node was sent an initialization packet.
+    Reconfigured
+}
+
+impl NodeEvent {
+    #[must_use]
+    pub(super) fn new(address: Address, kind: NodeEventKind) -> Self {
+        Self { at: SystemTime::now(), address, kind }
+    }
+
+    /// When the event happened.
+    #[must_use]
+    pub const fn at(&self) -> SystemTime {
+        self.at
+    }
+
+    /// The address of the node the event happened to.
+    #[must_use]
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// What happened.
+    #[must_use]
+    pub const fn kind(&self) -> NodeEventKind {
+        self.kind
+    }
+}
+
+impl std::fmt::Display for NodeEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            NodeEventKind::Appeared => write!(f, "Node {} appeared", self.address),
+            NodeEventKind::Stale => write!(f, "Node {} went stale", self.address),
+            NodeEventKind::Reconfigured => write!(f, "Node {} reconfigured", self.address)
+        }
+    }
+}
+
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn getters() {
+        let address = Address::try_from_node_address(12).unwrap();
+        let event = NodeEvent::new(address, NodeEventKind::Stale);
+        assert!(event.at() <= SystemTime::now());
+        assert_eq!(event.address(), address);
+        assert_eq!(event.kind(), NodeEventKind::Stale);
+    }
+
+    #[test]
+    fn display() {
+        let address = Address::try_from_node_address(12).unwrap();
+        assert_eq!(NodeEvent::new(address, NodeEventKind::Appeared).to_string(), "Node 12 appeared");
+        assert_eq!(NodeEvent::new(address, NodeEventKind::Stale).to_string(), "Node 12 went stale");
+        assert_eq!(NodeEvent::new(address, NodeEventKind::Reconfigured).to_string(), "Node 12 reconfigured");
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeSet;
+use cmri::{Address, AddressSet, packet::{Packet, Payload}};
+
+/// Which node addresses and message types the GUI should show.
+///
+/// An empty set allows everything, so the default filter shows it all.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Filter {
+    nodes: AddressSet,
+    message_types: BTreeSet<u8>
+}
+
+impl Filter {
+    /// Create a filter allowing only the given node addresses and message types (E.G. `b'P'`).
+    #[must_use]
+    pub fn new(nodes: impl IntoIterator<Item = Address>, message_types: impl IntoIterator<Item = u8>) -> Self {
+        Self {
+            nodes: nodes.into_iter().collect(),
+            message_types: message_types.into_iter().collect()
+        }
+    }
+
+    /// The allowed node addresses (all are allowed if empty).
+    #[must_use]
+    pub const fn nodes(&self) -> &AddressSet {
+        &self.nodes
+    }
+
+    /// The allowed message types (all are allowed if empty).
+    #[must_use]
+    pub const fn message_types(&self) -> &BTreeSet<u8> {
+        &self.message_types
+    }
+
+    /// Whether a node address is allowed.
+    #[must_use]
+    pub const fn allows_node(&self, address: Address) -> bool {
+        self.nodes.is_empty() || self.nodes.contains(address)
+    }
+
+    /// Whether a message type is allowed.
+    #[must_use]
+    pub fn allows_message_type(&self, message_type: u8) -> bool {
+        self.message_types.is_empty() || self.message_types.contains(&message_type)
+    }
+
+    /// Whether both the packet's address and message type are allowed.
+    #[must_use]
+    pub fn matches(&self, packet: &Packet) -> bool {
+        self.allows_node(packet.address()) && self.allows_message_type(message_type(packet.payload()))
+    }
+}
+
+/// The message type byte used when encoding a payload.
+const fn message_type(payload: &Payload) -> u8 {
+    match payload {
+        Payload::Initialization { .. } => b'I',
+        Payload::PollRequest => b'P',
+        Payload::ReceiveData { .. } => b'R',
+        Payload::TransmitData { .. } => b'T',
+        Payload::Set { .. } => b'S',
+        #[cfg(feature = "experimenter")]
+        Payload::Unknown { message_type, .. } => *message_type
+    }
+}
+
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses<const N: usize>(node_addresses: [u8; N]) -> [Address; N] {
+        node_addresses.map(|node_address| Address::try_from_node_address(node_address).unwrap())
+    }
+
+    fn packets(node_address: u8) -> [Packet; 4] {
+        let address = Address::try_from_node_address(node_address).unwrap();
+        [
+            Packet::new_initialization(address, cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap()),
+            Packet::new_poll_request(address),
+            Packet::new_receive_data(address, [0].try_into().unwrap()),
+            Packet::new_transmit_data(address, [0].try_into().unwrap())
+        ]
+    }
+
+    #[test]
+    fn default_matches_everything() {
+        let filter = Filter::default();
+        for node_address in [0, 1, 127] {
+            for packet in packets(node_address) {
+                assert!(filter.matches(&packet), "{packet:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn nodes() {
+        let filter = Filter::new(addresses([1, 127]), []);
+        for packet in packets(0) {
+            assert!(!filter.matches(&packet), "{packet:?}");
+        }
+        for node_address in [1, 127] {
+            for packet in packets(node_address) {
+                assert!(filter.matches(&packet), "{packet:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn message_types() {
+        let filter = Filter::new([], *b"PR");
+        for node_address in [0, 127] {
+            let [init, poll, receive, transmit] = packets(node_address);
+            assert!(!filter.matches(&init));
+            assert!(filter.matches(&poll));
+            assert!(filter.matches(&receive));
+            assert!(!filter.matches(&transmit));
+        }
+        let set = Packet::new_set(Address::try_from_node_address(0).unwrap(), [0].try_into().unwrap());
+        assert!(!filter.matches(&set));
+        assert!(Filter::new([], *b"S").matches(&set));
+    }
+
+    #[test]
+    fn nodes_and_message_types() {
+        let filter = Filter::new(addresses([5]), *b"T");
+        let [init, poll, receive, transmit] = packets(5);
+        assert!(!filter.matches(&init));
+        assert!(!filter.matches(&poll));
+        assert!(!filter.matches(&receive));
+        assert!(filter.matches(&transmit));
+        for packet in packets(6) {
+            assert!(!filter.matches(&packet), "{packet:?}");
+        }
+    }
+
+    #[cfg(feature = "experimenter")]
+    #[test]
+    fn unknown() {
+        let packet = Packet::try_new_unknown(Address::try_from_node_address(0).unwrap(), b'Z', [0].try_into().unwrap()).unwrap();
+        assert!(Filter::new([], *b"Z").matches(&packet));
+        assert!(!Filter::new([], *b"P").matches(&packet));
+    }
+}
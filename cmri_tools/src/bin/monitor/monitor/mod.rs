@@ -1,39 +1,81 @@
 //! Monitor a CMRInet network; gathering statistics, present nodes, and node states.
 
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::error;
+use tokio::sync::{Mutex, Notify};
+use tracing::{error, info, warn};
 use cmri_tools::connection::Connection;
 
+mod event;
+mod filter;
 mod node;
+mod recorder;
 mod state;
 mod statistics;
 
+pub use event::{NodeEvent, NodeEventKind};
+pub use filter::Filter;
 pub use node::Node;
+pub use recorder::Recorder;
 pub use state::State;
 pub use statistics::Statistics;
 
-pub fn run_connection(mut connection: Connection, state: Arc<Mutex<State>>, tokio_handle: &tokio::runtime::Handle) -> tokio::task::JoinHandle<std::io::Result<()>> {
+/// Receive frames from the connection into state, recording them first (even bad ones) if there's a recorder.
+///
+/// Runs until the connection fails or cancel is notified (E.G. by the GUI), when the connection
+/// is shutdown and the task finishes with `Ok`. The recording is flushed every `recorder::FLUSH_INTERVAL`
+/// and when the task finishes.
+pub fn run_connection(mut connection: Connection, state: Arc<Mutex<State>>, mut recorder: Option<Recorder>, cancel: Arc<Notify>, tokio_handle: &tokio::runtime::Handle) -> tokio::task::JoinHandle<std::io::Result<()>> {
     tokio_handle.spawn(async move {
-        loop {
-            match connection.receive().await {
-                Err(error) => {
-                    error!("Read error: {error}");
-                    break Err(error);
+        let mut flush_interval = tokio::time::interval_at(tokio::time::Instant::now() + recorder::FLUSH_INTERVAL, recorder::FLUSH_INTERVAL);
+        flush_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let result = loop {
+            tokio::select! {
+                () = cancel.notified() => {
+                    info!("Disconnecting from {}", connection.name());
+                    if let Err(error) = connection.shutdown().await {
+                        warn!("Couldn't shutdown {}: {error}", connection.name());
+                    }
+                    break Ok(())
+                },
+                _ = flush_interval.tick(), if recorder.is_some() => {
+                    if let Some(active) = recorder.as_mut() {
+                        if let Err(error) = active.flush().await {
+                            error!("Couldn't flush recording, recording stopped: {error}");
+                            recorder = None;
+                        }
+                    }
                 },
-                Ok(frame) => {
-                    match frame.try_as_packet() {
-                        Err(error) => {
-                            error!("Bad packet: {error:?}");
-                            state.lock().await.got_bad_packet(frame.address());
-                        },
-                        Ok(packet) => {
-                            state.lock().await.got_packet(&packet);
+                frame = connection.receive() => match frame {
+                    Err(error) => {
+                        error!("Read error: {error}");
+                        break Err(error);
+                    },
+                    Ok(frame) => {
+                        if let Some(active) = recorder.as_mut() {
+                            if let Err(error) = active.record(&frame).await {
+                                error!("Couldn't record frame, recording stopped: {error}");
+                                recorder = None;
+                            }
+                        }
+                        match frame.try_as_packet_partial() {
+                            (address, message_type, Err(error)) => {
+                                error!("Bad packet (address {address:?}, type {message_type:?}): {error:?}");
+                                state.lock().await.got_bad_packet(error.with_context(&frame));
+                            },
+                            (_, _, Ok(packet)) => {
+                                state.lock().await.got_packet(&packet);
+                            }
                         }
                     }
                 }
             }
+        };
+        if let Some(mut recorder) = recorder {
+            if let Err(error) = recorder.flush().await {
+                error!("Couldn't write the end of the recording: {error}");
+            }
         }
+        result
     })
 }
 
@@ -43,7 +85,13 @@ pub fn run_ticker(state: Arc<Mutex<State>>, tokio_handle: &tokio::runtime::Handl
         interval.tick().await; // Disregard the first tick as it's immediate
         loop {
             interval.tick().await;
-            state.lock().await.tick();
+            let (gone_stale, stale_after) = {
+                let mut state = state.lock().await;
+                (state.tick(), state.stale_after())
+            };
+            for address in gone_stale {
+                warn!("Node {address} has stopped responding, no receive data for {stale_after:?}");
+            }
         }
     })
 }
@@ -56,6 +104,7 @@ mod tests {
 
     mod run_connection {
         use super::*;
+        use super::super::recorder::tests::TempFile;
 
         #[tokio::test]
         #[allow(clippy::significant_drop_tightening)]
@@ -66,13 +115,13 @@ mod tests {
                 .build();
             let connection = Connection::new("test connection", Box::new(connection));
             let state = Arc::new(Mutex::new(State::default()));
-            run_connection(connection, state.clone(), &tokio::runtime::Handle::current());
+            run_connection(connection, state.clone(), None, Arc::new(Notify::new()), &tokio::runtime::Handle::current());
 
             tokio::time::sleep(std::time::Duration::from_millis(10)).await;
             let state = state.lock().await;
-            assert_eq!(state.statistics.poll_packets.1, 2);
-            assert_eq!(state.nodes[0].statistics.poll_packets.1, 1);
-            assert_eq!(state.nodes[1].statistics.poll_packets.1, 1);
+            assert_eq!(state.statistics.poll_packets.total(), 2);
+            assert_eq!(state.nodes[0].statistics.poll_packets.total(), 1);
+            assert_eq!(state.nodes[1].statistics.poll_packets.total(), 1);
         }
 
         #[tokio::test]
@@ -85,10 +134,74 @@ mod tests {
                 .build();
             let connection = Connection::new("test connection", Box::new(connection));
             let state = Arc::new(Mutex::new(State::default()));
-            run_connection(connection, state.clone(), &tokio::runtime::Handle::current());
+            run_connection(connection, state.clone(), None, Arc::new(Notify::new()), &tokio::runtime::Handle::current());
+
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            assert_eq!(state.lock().await.statistics.bad_packets.total(), 2);
+            assert_eq!(state.lock().await.bad_packets().len(), 2);
+        }
+
+        #[tokio::test]
+        async fn records_frames() {
+            let file = TempFile::new("run_connection-records_frames");
+            let connection = tokio_test::io::Builder::new()
+                .read(&[0xFF, 0xFF, 0x02, 65, b'P', 0x03])   // A poll request for node 0
+                .read(&[0xFF, 0xFF, 0x02, b'P', 0x00, 0x03]) // A bad frame
+                .build();
+            let connection = Connection::new("test connection", Box::new(connection));
+            let state = Arc::new(Mutex::new(State::default()));
+            let recorder = Recorder::create(file.path()).await.unwrap();
+            // The recording is flushed when the connection ends
+            run_connection(connection, state.clone(), Some(recorder), Arc::new(Notify::new()), &tokio::runtime::Handle::current()).await.unwrap().unwrap_err();
+
+            let frames = cmri_tools::recording::read(std::fs::File::open(file.path()).unwrap()).unwrap().into_iter().map(|(_, frame)| frame.to_vec()).collect::<Vec<_>>();
+            assert_eq!(frames, vec![vec![0xFF, 0xFF, 0x02, 65, b'P', 0x03], vec![0xFF, 0xFF, 0x02, b'P', 0x00, 0x03]]);
+            assert_eq!(state.lock().await.statistics.bad_packets.total(), 1);
+        }
+
+        #[tokio::test]
+        async fn flushes_recording_periodically() {
+            let file = TempFile::new("run_connection-flushes_recording_periodically");
+            let (connection, mut peer) = tokio::io::duplex(64);
+            let connection = Connection::new("test connection", Box::new(connection));
+            let recorder = Recorder::create(file.path()).await.unwrap();
+            let cancel = Arc::new(Notify::new());
+            let handle = run_connection(connection, Arc::new(Mutex::new(State::default())), Some(recorder), cancel.clone(), &tokio::runtime::Handle::current());
+            let frames_in_file = || cmri_tools::recording::read(std::fs::File::open(file.path()).unwrap()).unwrap().len();
+
+            tokio::io::AsyncWriteExt::write_all(&mut peer, &[0xFF, 0xFF, 0x02, 65, b'P', 0x03]).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            assert_eq!(frames_in_file(), 0, "Shouldn't be flushed yet");
+
+            // Flushed without any more frames arriving
+            tokio::time::sleep(recorder::FLUSH_INTERVAL).await;
+            assert_eq!(frames_in_file(), 1);
+
+            cancel.notify_one();
+            assert!(handle.await.unwrap().is_ok());
+        }
+
+        #[tokio::test]
+        async fn cancelled() {
+            use tokio::io::AsyncReadExt;
+            let file = TempFile::new("run_connection-cancelled");
+            let (connection, mut peer) = tokio::io::duplex(64);
+            let connection = Connection::new("test connection", Box::new(connection));
+            let state = Arc::new(Mutex::new(State::default()));
+            let recorder = Recorder::create(file.path()).await.unwrap();
+            let cancel = Arc::new(Notify::new());
+            let handle = run_connection(connection, state.clone(), Some(recorder), cancel.clone(), &tokio::runtime::Handle::current());
 
+            tokio::io::AsyncWriteExt::write_all(&mut peer, &[0xFF, 0xFF, 0x02, 65, b'P', 0x03]).await.unwrap();
             tokio::time::sleep(std::time::Duration::from_millis(10)).await;
-            assert_eq!(state.lock().await.statistics.bad_packets.1, 2);
+            cancel.notify_one();
+            assert!(handle.await.unwrap().is_ok());
+
+            // Connection was shutdown and recording flushed
+            assert_eq!(peer.read(&mut [0; 8]).await.unwrap(), 0);
+            let frames = cmri_tools::recording::read(std::fs::File::open(file.path()).unwrap()).unwrap();
+            assert_eq!(frames.len(), 1);
+            assert_eq!(state.lock().await.statistics.poll_packets.total(), 1);
         }
     }
 
@@ -102,7 +215,7 @@ mod tests {
         {
             let mut state = state.lock().await;
             assert_eq!(*state, State::default());
-            state.got_bad_packet(None);
+            state.got_bad_packet(cmri::frame::DecodeError::MissingEnd.with_context(&cmri::frame::Raw::try_from(&[0xFF, 0xFF, 0x02, 0x00, b'P']).unwrap()));
         }
 
         // Wait for the ticker to have ticked.
@@ -113,9 +226,9 @@ mod tests {
 
         {
             let state = state.lock().await;
-            assert_eq!(state.statistics.bad_packets.0, 0);
-            assert_eq!(state.statistics.bad_packets.1, 1);
-            assert_eq!(state.statistics.bad_packets.2.len(), 1);
+            assert_eq!(state.statistics.bad_packets.current(), 0);
+            assert_eq!(state.statistics.bad_packets.total(), 1);
+            assert_eq!(state.statistics.bad_packets.history().len(), 1);
         }
     }
 }
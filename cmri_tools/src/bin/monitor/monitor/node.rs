@@ -1,3 +1,4 @@
+use tokio::time::Instant;
 use tracing::{warn, debug};
 use cmri::{Address, packet::{Packet, Payload, Data}, NodeSort};
 use cmri_tools::file;
@@ -13,7 +14,10 @@ pub struct Node {
     pub(super) inputs: Option<Data>,
     pub(super) outputs: Option<Data>,
     pub(super) initialization_count: u16,
-    pub(super) statistics: Statistics
+    pub(super) statistics: Statistics,
+    pub(super) responding: bool,
+    pub(super) last_seen: Option<Instant>,
+    pub(super) wrong_size_inputs: Option<usize>
 }
 
 impl Node {
@@ -31,14 +35,17 @@ impl Node {
             inputs: None,
             outputs: None,
             initialization_count: 0,
-            statistics: Statistics::new()
+            statistics: Statistics::new(),
+            responding: false,
+            last_seen: None,
+            wrong_size_inputs: None
         }
     }
 
     /// Whether the Node has received any packets.
     #[must_use]
     pub const fn has_been_seen(&self) -> bool {
-        self.statistics.packets().0 > 0
+        self.statistics.packets().current() > 0
     }
 
     /// The friendly name of the node.
@@ -90,6 +97,31 @@ impl Node {
         &self.statistics
     }
 
+    /// Whether the node is sending receive data packets (it's not gone stale).
+    #[must_use]
+    pub const fn is_responding(&self) -> bool {
+        self.responding
+    }
+
+    /// When the node last sent receive data (None if it never has).
+    #[must_use]
+    pub const fn last_seen(&self) -> Option<Instant> {
+        self.last_seen
+    }
+
+    /// Whether the node was responding but has gone stale (see `State::stale_nodes`).
+    #[must_use]
+    pub const fn is_stale(&self) -> bool {
+        !self.responding && self.last_seen.is_some()
+    }
+
+    /// The length of receive data which disagreed with the node's sort (since the last Initialization packet seen),
+    /// this suggests the node and controller are configured differently.
+    #[must_use]
+    pub const fn wrong_size_inputs(&self) -> Option<usize> {
+        self.wrong_size_inputs
+    }
+
     pub(super) fn got_packet(&mut self, packet: &Packet) {
         if packet.address() != self.address {
             warn!("I'm node {} but was given a packet for {}!", self.address, packet.address());
@@ -103,24 +135,46 @@ impl Node {
                 debug!("Initialize {} {:?}", self.address, node_sort);
                 self.initialization_count += 1;
                 self.sort = Some(*node_sort);
+                self.wrong_size_inputs = None;
             },
             Payload::PollRequest => {
                 debug!("Poll Request {}", self.address);
             },
             Payload::ReceiveData { data } => {
                 debug!("Receive data {} {:?}", self.address, data.as_slice());
+                if let Some(sort) = self.sort.filter(|sort| !sort.accepts_receive_data(data)) {
+                    warn!("Node {} sent {} bytes of data, but is configured as {sort} with {} input bytes.", self.address, data.len(), sort.configuration().input_bytes());
+                    self.wrong_size_inputs = Some(data.len());
+                }
                 if !data.is_empty() { // Empty indicates no change
                     self.inputs = Some(*data);
                 }
+                self.responding = true;
+                self.last_seen = Some(Instant::now());
             },
             Payload::TransmitData { data } => {
                 debug!("Transmit data {} {:?}", self.address, data.as_slice());
                 self.outputs = Some(*data);
             },
+            Payload::Set { data } => {
+                debug!("Set {} {:?}", self.address, data.as_slice());
+            },
             #[cfg(feature = "experimenter")]
             Payload::Unknown { .. } => ()
         }
     }
+
+    pub(super) fn tick(&mut self) {
+        self.statistics.tick();
+    }
+
+    /// Mark the node as no longer responding, returns whether it has just gone stale.
+    pub(super) fn mark_stale(&mut self) -> bool {
+        if !self.responding { return false }
+        debug!("Stale {}", self.address);
+        self.responding = false;
+        true
+    }
 }
 
 impl std::fmt::Debug for Node {
@@ -134,6 +188,9 @@ impl std::fmt::Debug for Node {
          .field("outputs", &self.outputs.as_slice())
          .field("initialization_count", &self.initialization_count)
          .field("statistics", &self.statistics)
+         .field("responding", &self.responding)
+         .field("last_seen", &self.last_seen)
+         .field("wrong_size_inputs", &self.wrong_size_inputs)
          .finish()
     }
 }
@@ -159,22 +216,22 @@ mod tests {
 
     #[derive(Default, Debug, Eq, PartialEq)]
     struct ReducedStatistics {
-        packets: u16,
-        bad_packets: u16,
-        initialization_packets: u16,
-        poll_packets: u16,
-        receive_data_packets: u16,
-        transmit_data_packets: u16
+        packets: u32,
+        bad_packets: u32,
+        initialization_packets: u32,
+        poll_packets: u32,
+        receive_data_packets: u32,
+        transmit_data_packets: u32
     }
     impl ReducedStatistics {
         const fn new(statistics: &Statistics) -> Self {
             Self {
-                packets: statistics.packets.0,
-                bad_packets: statistics.bad_packets.0,
-                initialization_packets: statistics.initialization_packets.0,
-                poll_packets: statistics.poll_packets.0,
-                receive_data_packets: statistics.receive_data_packets.0,
-                transmit_data_packets: statistics.transmit_data_packets.0
+                packets: statistics.packets.current(),
+                bad_packets: statistics.bad_packets.current(),
+                initialization_packets: statistics.initialization_packets.current(),
+                poll_packets: statistics.poll_packets.current(),
+                receive_data_packets: statistics.receive_data_packets.current(),
+                transmit_data_packets: statistics.transmit_data_packets.current()
             }
         }
     }
@@ -237,6 +294,48 @@ mod tests {
             }
         }
 
+        mod wrong_size_inputs {
+            use super::*;
+
+            fn initialize(node: &mut Node) {
+                let sort = cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap();
+                node.got_packet(&Packet::new_initialization(Address::try_from_node_address(25).unwrap(), sort));
+            }
+
+            fn receive(node: &mut Node, len: usize) {
+                let data = cmri::packet::Data::new(len);
+                node.got_packet(&Packet::new_receive_data(Address::try_from_node_address(25).unwrap(), data));
+            }
+
+            #[test]
+            fn flagged() {
+                let mut node = Node::new(25);
+                initialize(&mut node);
+                receive(&mut node, 3);
+                assert_eq!(node.wrong_size_inputs(), None);
+
+                receive(&mut node, 9); // CPNODE sized
+                assert_eq!(node.wrong_size_inputs(), Some(9));
+                assert!(node.is_responding());
+            }
+
+            #[test]
+            fn cleared_by_initialization() {
+                let mut node = Node::new(25);
+                initialize(&mut node);
+                receive(&mut node, 9);
+                initialize(&mut node);
+                assert_eq!(node.wrong_size_inputs(), None);
+            }
+
+            #[test]
+            fn unknown_sort() {
+                let mut node = Node::new(25);
+                receive(&mut node, 9);
+                assert_eq!(node.wrong_size_inputs(), None);
+            }
+        }
+
         #[test]
         fn transmit_data() {
             let mut node = Node::new(25);
@@ -260,4 +359,37 @@ mod tests {
             assert_eq!(ReducedStatistics::new(node.statistics()), ReducedStatistics::default());
         }
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn last_seen() {
+        let address = Address::try_from_node_address(25).unwrap();
+        let mut node = Node::new(25);
+        assert_eq!(node.last_seen(), None);
+
+        node.got_packet(&Packet::new_poll_request(address));
+        assert_eq!(node.last_seen(), None, "Being polled doesn't mean the node is there");
+
+        node.got_packet(&Packet::new_receive_data(address, [0].try_into().unwrap()));
+        assert_eq!(node.last_seen(), Some(Instant::now()));
+
+        tokio::time::advance(std::time::Duration::from_secs(5)).await;
+        node.got_packet(&Packet::new_receive_data(address, [0].try_into().unwrap()));
+        assert_eq!(node.last_seen(), Some(Instant::now()));
+    }
+
+    #[test]
+    fn mark_stale() {
+        let mut node = Node::new(25);
+        assert!(!node.mark_stale(), "Never responding node shouldn't go stale");
+        assert!(!node.is_stale());
+
+        node.got_packet(&Packet::new_receive_data(Address::try_from_node_address(25).unwrap(), [0].try_into().unwrap()));
+        assert!(node.is_responding());
+        assert!(!node.is_stale());
+
+        assert!(node.mark_stale());
+        assert!(!node.is_responding());
+        assert!(node.is_stale());
+        assert!(!node.mark_stale(), "Should only go stale once");
+    }
 }
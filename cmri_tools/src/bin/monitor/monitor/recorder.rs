@@ -0,0 +1,120 @@
+use std::time::Duration;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::time::Instant;
+use cmri::frame::Raw as RawFrame;
+use cmri_tools::recording;
+
+/// How often recorded frames should be flushed to the file (see `Recorder::flush`).
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Records frames to a file (see `cmri_tools::recording` for the format),
+/// along with when they were received, for later analysis/replay.
+///
+/// Frames are buffered until `flush` is called, which should be done every `FLUSH_INTERVAL`
+/// and before the `Recorder` is dropped (`run_connection` does both, and the GUI waits for
+/// it to finish on exit). The file is written using `tokio::fs` so a
+/// slow disk doesn't block the runtime's worker threads.
+///
+/// The timing is against the `tokio::time` clock so it can be paused in tests.
+#[derive(Debug)]
+pub struct Recorder {
+    writer: BufWriter<tokio::fs::File>,
+    started: Instant
+}
+
+impl Recorder {
+    /// Create a new recording, replacing the file if it already exists.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be created or written to.
+    pub async fn create(path: &std::path::Path) -> std::io::Result<Self> {
+        let mut writer = BufWriter::new(tokio::fs::File::create(path).await?);
+        writer.write_all(&recording::MAGIC).await?;
+        writer.flush().await?;
+        Ok(Self { writer, started: Instant::now() })
+    }
+
+    /// Record a frame (which needn't be valid), it's written to the file on the next `flush`.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be written to.
+    pub async fn record(&mut self, frame: &RawFrame) -> std::io::Result<()> {
+        let mut record = Vec::with_capacity(10 + frame.len());
+        recording::write_record(&mut record, self.started.elapsed(), frame)?;
+        self.writer.write_all(&record).await
+    }
+
+    /// Write any buffered frames to the file.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be written to.
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush().await
+    }
+}
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+pub(super) mod tests {
+    use cmri::{Address, packet::Packet};
+    use super::*;
+
+    fn read(path: &std::path::Path) -> Vec<(Duration, RawFrame)> {
+        recording::read(std::fs::File::open(path).unwrap()).unwrap()
+    }
+
+    /// A path in the temporary directory, which is deleted when dropped.
+    pub(in super::super) struct TempFile(std::path::PathBuf);
+    impl TempFile {
+        pub(in super::super) fn new(name: &str) -> Self {
+            let mut path = std::env::temp_dir();
+            path.push(format!("cmri-monitor-{}-{name}.rec", std::process::id()));
+            Self(path)
+        }
+
+        pub(in super::super) fn path(&self) -> &std::path::Path {
+            &self.0
+        }
+    }
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn records_two_frames() {
+        let file = TempFile::new("records_two_frames");
+        let good = Packet::new_poll_request(Address::try_from_node_address(5).unwrap()).encode_frame();
+        let bad = RawFrame::try_from(&[0xFF, 0xFF, 0x02, 0x03]).unwrap();
+
+        let mut recorder = Recorder::create(file.path()).await.unwrap();
+        tokio::time::advance(Duration::from_millis(10)).await;
+        recorder.record(&good).await.unwrap();
+        tokio::time::advance(Duration::from_millis(25)).await;
+        recorder.record(&bad).await.unwrap();
+        recorder.flush().await.unwrap();
+
+        assert_eq!(
+            read(file.path()),
+            vec![(Duration::from_millis(10), good), (Duration::from_millis(35), bad)]
+        );
+    }
+
+    #[tokio::test]
+    async fn buffered_until_flushed() {
+        let file = TempFile::new("buffered_until_flushed");
+        let frame = Packet::new_poll_request(Address::try_from_node_address(5).unwrap()).encode_frame();
+        let mut recorder = Recorder::create(file.path()).await.unwrap();
+
+        recorder.record(&frame).await.unwrap();
+        recorder.record(&frame).await.unwrap();
+        assert_eq!(read(file.path()).len(), 0);
+
+        recorder.flush().await.unwrap();
+        assert_eq!(read(file.path()).len(), 2);
+    }
+}
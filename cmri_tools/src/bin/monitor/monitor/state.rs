@@ -1,13 +1,33 @@
-use cmri::packet::Packet;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::time::Duration;
+use cmri::{Address, frame::DecodeErrorReport, packet::{Packet, Payload, Data}};
 use cmri_tools::file;
-use super::Node;
+use super::{Filter, Node, NodeEvent, NodeEventKind};
 use super::Statistics;
 
+/// The maximum number of events kept, the oldest are dropped first.
+pub const MAX_EVENTS: usize = 1_000;
+
+/// The maximum number of bad packets kept, the oldest are dropped first.
+pub const MAX_BAD_PACKETS: usize = 100;
+
+/// The maximum number of recent packets kept, the oldest are dropped first.
+pub const MAX_PACKETS: usize = 1_000;
+
+/// How long a responding node can go without sending receive data before it's considered stale, by default.
+pub const STALE_AFTER: Duration = Duration::from_secs(10);
+
 /// Details about the CMRInet network's state.
 #[derive(Eq, PartialEq)]
 pub struct State {
     pub(super) statistics: Statistics,
-    pub(super) nodes: Box<[Node; 128]>
+    pub(super) nodes: Box<[Node; 128]>,
+    pub(super) events: Vec<NodeEvent>,
+    pub(super) bad_packets: VecDeque<DecodeErrorReport>,
+    pub(super) packets: VecDeque<Packet>,
+    pub(super) filter: Filter,
+    pub(super) stale_after: Duration
 }
 
 impl State {
@@ -23,10 +43,155 @@ impl State {
         &self.nodes
     }
 
+    /// Get the filter limiting which nodes are shown, it doesn't affect what's counted.
+    #[must_use]
+    pub const fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    /// Set the filter limiting which nodes are shown.
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+    }
+
+    /// Whether a packet is allowed by the filter (see `Filter::matches`).
+    #[must_use]
+    pub fn matches_filter(&self, packet: &Packet) -> bool {
+        self.filter.matches(packet)
+    }
+
+    /// Get the nodes which have been seen or configured and are allowed by the filter, lowest address first.
+    ///
+    /// If the filter has message types, only nodes which have sent (or been sent) at least one
+    /// packet of those types are included.
+    pub fn visible_nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter()
+            .filter(|node| node.has_been_seen() || node.sort.is_some())
+            .filter(|node| self.filter.allows_node(node.address))
+            .filter(|node| self.filter.message_types().is_empty() || self.filter.message_types().iter().any(|message_type| node.statistics.has_seen_message_type(*message_type)))
+    }
+
+    /// Get how long a responding node can go without sending receive data before it's considered stale.
+    #[must_use]
+    pub const fn stale_after(&self) -> Duration {
+        self.stale_after
+    }
+
+    /// Set how long a responding node can go without sending receive data before it's considered stale.
+    pub fn set_stale_after(&mut self, stale_after: Duration) {
+        self.stale_after = stale_after;
+    }
+
+    /// Get the address of each node which has sent receive data, but not within threshold.
+    #[must_use]
+    pub fn stale_nodes(&self, threshold: Duration) -> Vec<Address> {
+        self.nodes.iter()
+            .filter(|node| node.last_seen.is_some_and(|last_seen| last_seen.elapsed() >= threshold))
+            .map(|node| node.address)
+            .collect()
+    }
+
+    /// Get the timeline of node lifecycle events (oldest first), upto `MAX_EVENTS` are kept.
+    #[must_use]
+    pub fn events(&self) -> &[NodeEvent] {
+        &self.events
+    }
+
+    /// Get the most recent packets (oldest first), upto `MAX_PACKETS` are kept.
+    #[must_use]
+    pub const fn packets(&self) -> &VecDeque<Packet> {
+        &self.packets
+    }
+
+    /// Get the bad packets (oldest first) along with why they couldn't be decoded,
+    /// upto `MAX_BAD_PACKETS` are kept.
+    #[must_use]
+    pub const fn bad_packets(&self) -> &VecDeque<DecodeErrorReport> {
+        &self.bad_packets
+    }
+
+    /// Get the number of packets seen for each node address in the last whole second,
+    /// indexed by node address (E.G. for rendering as a bar chart of bus usage).
+    #[must_use]
+    pub fn address_rate_histogram(&self) -> [u32; 128] {
+        let mut histogram = [0; 128];
+        for (count, node) in histogram.iter_mut().zip(self.nodes.iter()) {
+            *count = node.statistics().packets().rate_last_second();
+        }
+        histogram
+    }
+
+    /// Get the nodes which have been seen or configured, lowest address first.
+    fn present_nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter().filter(|node| node.statistics.packets().total() > 0 || node.sort.is_some())
+    }
+
+    /// Export the statistics for the network and each present node (one which has been seen or configured),
+    /// along with the node's details and last known inputs/outputs.
+    ///
+    /// For each statistic the total and the count in the last whole second are exported
+    /// (see `Statistics::export_json`), the rest of the history isn't.
+    #[must_use]
+    pub fn export_json(&self) -> serde_json::Value {
+        let nodes = self.present_nodes().map(|node| serde_json::json!({
+            "address": node.address.as_node_address(),
+            "name": node.name,
+            "sort": node.sort.map(|sort| sort.to_string()),
+            "responding": node.responding,
+            "initialization_count": node.initialization_count,
+            "inputs": node.inputs.as_ref().map(Data::as_slice),
+            "outputs": node.outputs.as_ref().map(Data::as_slice),
+            "statistics": node.statistics.export_json()
+        })).collect::<Vec<_>>();
+        serde_json::json!({
+            "statistics": self.statistics.export_json(),
+            "nodes": nodes
+        })
+    }
+
+    /// Export the same as `export_json` for each present node as CSV, with a header row then one row per node.
+    /// Each statistic has a column for the total, and one suffixed "`_last_second`",
+    /// inputs and outputs are hex bytes separated by spaces.
+    ///
+    /// # Errors
+    ///
+    /// If writer can't be written to.
+    pub fn export_csv(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let names = self.statistics.counters().into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+        write!(writer, "address,name,sort,responding,initialization_count,inputs,outputs")?;
+        for name in &names {
+            write!(writer, ",{name},{name}_last_second")?;
+        }
+        writeln!(writer)?;
+
+        let hex = |data: Option<&Data>| data.map(|data| data.as_slice().iter().map(|byte| format!("{byte:02X}")).collect::<Vec<_>>().join(" ")).unwrap_or_default();
+        for node in self.present_nodes() {
+            write!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                node.address.as_node_address(),
+                csv_escape(node.name.as_deref().unwrap_or_default()),
+                node.sort.map(|sort| csv_escape(&sort.to_string())).unwrap_or_default(),
+                node.responding,
+                node.initialization_count,
+                hex(node.inputs.as_ref()),
+                hex(node.outputs.as_ref())
+            )?;
+            for (_, counter) in node.statistics.counters() {
+                write!(writer, ",{},{}", counter.total(), counter.rate_last_second())?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
     /// Reset the state back to default.
     #[expect(clippy::unwrap_used, clippy::missing_panics_doc, reason="i will never be invalid due to size of the nodes array")]
     pub fn reset(&mut self) {
         self.statistics = Statistics::default();
+        self.events.clear();
+        self.bad_packets.clear();
+        self.packets.clear();
         for (i, node) in self.nodes.iter_mut().enumerate() {
             *node = Node::new(i.try_into().unwrap());
         }
@@ -45,23 +210,127 @@ impl State {
         }
     }
 
-    pub(super) fn got_bad_packet(&mut self, node_address: Option<u8>) {
+    /// Read a recording (see `cmri_tools::recording`) into a new state, E.G. to merge into this one.
+    ///
+    /// # Errors
+    ///
+    /// If the recording can't be read (see `cmri_tools::recording::read`).
+    pub fn read_recording(reader: impl std::io::Read) -> std::io::Result<Self> {
+        let mut state = Self::default();
+        for (_, frame) in cmri_tools::recording::read(reader)? {
+            match frame.try_as_packet() {
+                Ok(packet) => state.got_packet(&packet),
+                Err(error) => state.got_bad_packet(error.with_context(&frame))
+            }
+        }
+        Ok(state)
+    }
+
+    /// Merge another state into this one (e.g. when replaying several recordings).
+    ///
+    /// Statistics are merged (see `Statistics::merge`), node details are only taken from other
+    /// where they're not already known here, and events are combined in time order.
+    pub fn merge(&mut self, other: &Self) {
+        self.statistics.merge(&other.statistics);
+        for (node, other) in self.nodes.iter_mut().zip(other.nodes.iter()) {
+            node.statistics.merge(&other.statistics);
+            node.initialization_count = node.initialization_count.saturating_add(other.initialization_count);
+            node.responding |= other.responding;
+            node.last_seen = node.last_seen.max(other.last_seen);
+            if node.name.is_none() {
+                node.name.clone_from(&other.name);
+            }
+            if node.sort.is_none() {
+                node.sort = other.sort;
+            }
+            if node.labels == file::Labels::default() {
+                node.labels.clone_from(&other.labels);
+            }
+            if node.inputs.is_none() {
+                node.inputs = other.inputs;
+            }
+            if node.outputs.is_none() {
+                node.outputs = other.outputs;
+            }
+            if node.wrong_size_inputs.is_none() {
+                node.wrong_size_inputs = other.wrong_size_inputs;
+            }
+        }
+
+        self.events.extend_from_slice(&other.events);
+        self.events.sort_by_key(NodeEvent::at);
+        if self.events.len() > MAX_EVENTS {
+            self.events.drain(..(self.events.len() - MAX_EVENTS));
+        }
+
+        self.packets.extend(other.packets.iter().copied());
+        if self.packets.len() > MAX_PACKETS {
+            self.packets.drain(..(self.packets.len() - MAX_PACKETS));
+        }
+
+        self.bad_packets.extend(other.bad_packets.iter().cloned());
+        if self.bad_packets.len() > MAX_BAD_PACKETS {
+            self.bad_packets.drain(..(self.bad_packets.len() - MAX_BAD_PACKETS));
+        }
+    }
+
+    pub(super) fn got_bad_packet(&mut self, report: DecodeErrorReport) {
         self.statistics.got_bad_packet();
-        if let Some(index) = node_address.map(usize::from) {
+        if let Some(index) = report.bytes().address().map(usize::from) {
             self.nodes[index].statistics.got_bad_packet();
         }
+        if self.bad_packets.len() >= MAX_BAD_PACKETS {
+            self.bad_packets.pop_front();
+        }
+        self.bad_packets.push_back(report);
     }
 
     pub(super) fn got_packet(&mut self, packet: &Packet) {
         self.statistics.got_packet(packet);
-        self.nodes[usize::from(packet.address().as_node_address())].got_packet(packet);
+        let node = &mut self.nodes[usize::from(packet.address().as_node_address())];
+        let was_responding = node.is_responding();
+        node.got_packet(packet);
+        match packet.payload() {
+            Payload::Initialization { .. } => self.log_event(packet.address(), NodeEventKind::Reconfigured),
+            Payload::ReceiveData { .. } if !was_responding => self.log_event(packet.address(), NodeEventKind::Appeared),
+            _ => ()
+        }
+        if self.packets.len() >= MAX_PACKETS {
+            self.packets.pop_front();
+        }
+        self.packets.push_back(*packet);
     }
 
-    pub(super) fn tick(&mut self) {
+    /// Move on a second, returning the address of each node which has just gone stale (see `stale_nodes`).
+    pub(super) fn tick(&mut self) -> Vec<Address> {
         self.statistics.tick();
         for node in self.nodes.as_mut() {
-            node.statistics.tick();
+            node.tick();
+        }
+        let mut gone_stale = Vec::new();
+        for address in self.stale_nodes(self.stale_after) {
+            if self.nodes[usize::from(address.as_node_address())].mark_stale() {
+                self.log_event(address, NodeEventKind::Stale);
+                gone_stale.push(address);
+            }
+        }
+        gone_stale
+    }
+
+    fn log_event(&mut self, address: cmri::Address, kind: NodeEventKind) {
+        if self.events.len() >= MAX_EVENTS {
+            self.events.drain(..=(self.events.len() - MAX_EVENTS));
         }
+        self.events.push(NodeEvent::new(address, kind));
+    }
+}
+
+/// Quote a CSV field if it contains a comma, quote or newline.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
     }
 }
 
@@ -70,6 +339,11 @@ impl std::fmt::Debug for State {
         f.debug_struct("State")
          .field("statistics", &self.statistics)
          .field("nodes", &self.nodes)
+         .field("events", &self.events)
+         .field("bad_packets", &self.bad_packets)
+         .field("packets", &self.packets)
+         .field("filter", &self.filter)
+         .field("stale_after", &self.stale_after)
          .finish()
     }
 }
@@ -82,7 +356,7 @@ impl std::default::Default for State {
         }
         let nodes: Box<[Node; 128]> = nodes.try_into().expect("A Vec<Node> of length 128 to go into a Box<[Node; 128]>");
 
-        Self { statistics: Statistics::default(), nodes }
+        Self { statistics: Statistics::default(), nodes, events: Vec::new(), bad_packets: VecDeque::new(), packets: VecDeque::new(), filter: Filter::default(), stale_after: STALE_AFTER }
     }
 }
 
@@ -90,10 +364,22 @@ impl std::default::Default for State {
 #[allow(clippy::missing_panics_doc, reason="tests")]
 #[cfg(test)]
 mod tests {
-    use cmri::Address;
-    use cmri_tools::readings::Readings;
+    use cmri::{Address, frame::{DecodeError, Raw as RawFrame}};
+    use cmri_tools::readings::Counter;
     use super::*;
 
+    fn bad_frame(node_address: u8) -> DecodeErrorReport {
+        let frame = RawFrame::try_from(&[0xFF, 0xFF, 0x02, node_address + 65, b'P']).unwrap();
+        DecodeError::MissingEnd.with_context(&frame)
+    }
+
+    /// A new counter which has counted `n` in the current second.
+    fn counted(n: u32) -> Counter {
+        let mut counter = Counter::new(super::super::statistics::READINGS_SIZE);
+        counter.increment(n);
+        counter
+    }
+
     #[test]
     fn default() {
         use super::*;
@@ -101,10 +387,11 @@ mod tests {
         let default = State::default();
 
         let statistics = default.statistics();
-        let check_statistics = |statistics: &(u16, u64, Readings<u16, READINGS_SIZE>)| {
-            assert_eq!(statistics.0, 0, "Current second should default to zero");
-            assert_eq!(statistics.1, 0, "Total should default to zero");
-            assert!(statistics.2.is_empty(), "Readings should default to empty");
+        let check_statistics = |statistics: &Counter| {
+            assert_eq!(statistics.current(), 0, "Current second should default to zero");
+            assert_eq!(statistics.total(), 0, "Total should default to zero");
+            assert!(statistics.history().is_empty(), "Readings should default to empty");
+            assert_eq!(statistics.window(), READINGS_SIZE);
         };
         check_statistics(statistics.packets());
         check_statistics(statistics.bad_packets());
@@ -112,7 +399,10 @@ mod tests {
         check_statistics(statistics.poll_packets());
         check_statistics(statistics.receive_data_packets());
         check_statistics(statistics.transmit_data_packets());
+        check_statistics(statistics.set_packets());
 
+        assert!(default.events().is_empty());
+        assert_eq!(default.filter(), &Filter::default());
         assert_eq!(default.nodes.len(), 128);
         for (index, node) in default.nodes.iter().enumerate() {
             assert_eq!(node.address, Address::try_from_node_address(u8::try_from(index).unwrap()).unwrap());
@@ -128,13 +418,95 @@ mod tests {
     #[test]
     fn reset() {
         let mut state = State::default();
-        state.got_bad_packet(Some(0));
+        state.got_bad_packet(bad_frame(0));
+        state.got_packet(&Packet::new_receive_data(Address::try_from_node_address(5).unwrap(), [0].try_into().unwrap()));
         state.nodes[5].name = Some(String::from("changed"));
 
         state.reset();
         assert_eq!(state, State::default());
     }
 
+    mod export {
+        use super::*;
+
+        fn state() -> State {
+            let mut state = State::default();
+            let address = Address::try_from_node_address(5).unwrap();
+            state.got_packet(&Packet::new_initialization(address, cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap()));
+            state.got_packet(&Packet::new_poll_request(address));
+            state.got_packet(&Packet::new_receive_data(address, [1, 2, 0x0A].try_into().unwrap()));
+            state.tick();
+            state.got_packet(&Packet::new_poll_request(address));
+            state.got_bad_packet(bad_frame(7));
+            state.nodes[5].name = Some(String::from("Yard, west"));
+            state
+        }
+
+        #[test]
+        fn json() {
+            let json = state().export_json();
+            assert_eq!(json["statistics"]["packets"], serde_json::json!({ "total": 5, "last_second": 3 }));
+            assert_eq!(json["statistics"]["bad_packets"], serde_json::json!({ "total": 1, "last_second": 0 }));
+
+            let nodes = json["nodes"].as_array().unwrap();
+            assert_eq!(nodes.iter().map(|node| node["address"].as_u64().unwrap()).collect::<Vec<_>>(), [5, 7]);
+            assert_eq!(nodes[0]["name"], "Yard, west");
+            assert_eq!(nodes[0]["sort"], "SMINI");
+            assert_eq!(nodes[0]["responding"], true);
+            assert_eq!(nodes[0]["initialization_count"], 1);
+            assert_eq!(nodes[0]["inputs"], serde_json::json!([1, 2, 0x0A]));
+            assert_eq!(nodes[0]["outputs"], serde_json::Value::Null);
+            assert_eq!(nodes[0]["statistics"]["poll_packets"], serde_json::json!({ "total": 2, "last_second": 1 }));
+            assert_eq!(nodes[1]["statistics"]["bad_packets"], serde_json::json!({ "total": 1, "last_second": 0 }));
+        }
+
+        #[test]
+        #[cfg(not(feature = "experimenter"))]
+        fn csv() {
+            let mut csv = Vec::new();
+            state().export_csv(&mut csv).unwrap();
+            assert_eq!(
+                String::from_utf8(csv).unwrap(),
+                indoc::indoc!{"
+                    address,name,sort,responding,initialization_count,inputs,outputs,packets,packets_last_second,bad_packets,bad_packets_last_second,initialization_packets,initialization_packets_last_second,poll_packets,poll_packets_last_second,receive_data_packets,receive_data_packets_last_second,transmit_data_packets,transmit_data_packets_last_second,set_packets,set_packets_last_second
+                    5,\"Yard, west\",SMINI,true,1,01 02 0A,,4,3,0,0,1,1,2,1,1,1,0,0,0,0
+                    7,,,false,0,,,1,0,1,0,0,0,0,0,0,0,0,0,0,0
+                "}
+            );
+        }
+
+        #[test]
+        fn csv_escape() {
+            assert_eq!(super::super::csv_escape("plain"), "plain");
+            assert_eq!(super::super::csv_escape("a,b"), "\"a,b\"");
+            assert_eq!(super::super::csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        }
+    }
+
+    #[test]
+    fn address_rate_histogram() {
+        let mut state = State::default();
+        assert_eq!(state.address_rate_histogram(), [0; 128]);
+
+        for _ in 0..3 {
+            state.got_packet(&Packet::new_poll_request(Address::try_from_node_address(5).unwrap()));
+        }
+        state.got_packet(&Packet::new_poll_request(Address::try_from_node_address(127).unwrap()));
+        assert_eq!(state.address_rate_histogram(), [0; 128], "The current second isn't complete");
+
+        state.tick();
+        state.got_packet(&Packet::new_poll_request(Address::try_from_node_address(5).unwrap())); // In the next second
+        let histogram = state.address_rate_histogram();
+        assert_eq!(histogram[5], 3);
+        assert_eq!(histogram[127], 1);
+        assert_eq!(histogram.iter().sum::<u32>(), 4);
+
+        state.tick();
+        let histogram = state.address_rate_histogram();
+        assert_eq!(histogram[5], 1);
+        assert_eq!(histogram[127], 0);
+    }
+
     #[test]
     fn load_nodes() {
         let sort = cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap();
@@ -162,15 +534,71 @@ mod tests {
 
         assert_eq!(state.nodes[10].name, Some(String::from("Test node 1")));
         assert_eq!(state.nodes[10].sort, Some(sort));
-        assert_eq!(state.nodes[10].inputs.unwrap().as_slice(), [1].as_slice());  // Should be untouched
-        assert_eq!(state.nodes[10].outputs.unwrap().as_slice(), [2].as_slice()); // Should be untouched
+        assert_eq!(state.nodes[10].inputs.unwrap().as_slice(), vec![1]);  // Should be untouched
+        assert_eq!(state.nodes[10].outputs.unwrap().as_slice(), vec![2]); // Should be untouched
         assert_eq!(state.nodes[10].initialization_count, 3);                     // Should be untouched
-        assert_eq!(state.nodes[10].statistics.bad_packets().1, 1);               // Should be untouched
+        assert_eq!(state.nodes[10].statistics.bad_packets().total(), 1);               // Should be untouched
 
         assert_eq!(state.nodes[20].name, Some(String::from("Test node 2")));
         assert_eq!(state.nodes[20].sort, Some(sort)); // Should not be replaced as it was present
     }
 
+    #[test]
+    fn merge() {
+        let sort = cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap();
+        let mut state = State::default();
+        state.got_packet(&Packet::new_poll_request(Address::try_from_node_address(1).unwrap()));
+        state.got_bad_packet(bad_frame(1));
+        state.nodes[1].name = Some(String::from("Mine"));
+        let mut other = State::default();
+        other.got_packet(&Packet::new_poll_request(Address::try_from_node_address(1).unwrap()));
+        other.got_packet(&Packet::new_initialization(Address::try_from_node_address(2).unwrap(), sort));
+        other.nodes[1].name = Some(String::from("Theirs"));
+        other.got_bad_packet(bad_frame(2));
+
+        state.merge(&other);
+
+        assert_eq!(state.statistics().packets().total(), 5);
+        assert_eq!(state.statistics().poll_packets().total(), 2);
+        assert_eq!(state.statistics().bad_packets().total(), 2);
+        assert_eq!(state.nodes[1].statistics().packets().total(), 3);
+        assert_eq!(state.nodes[1].statistics().poll_packets().total(), 2);
+        assert_eq!(state.nodes[1].name, Some(String::from("Mine"))); // Should not be replaced as it was present
+        assert_eq!(state.nodes[2].statistics().initialization_packets().total(), 1);
+        assert_eq!(state.nodes[2].initialization_count, 1);
+        assert_eq!(state.nodes[2].sort, Some(sort));
+        assert_eq!(state.events().len(), 1);
+        assert_eq!(state.bad_packets(), &[bad_frame(1), bad_frame(2)]);
+        assert_eq!(state.packets().len(), 3);
+    }
+
+    #[test]
+    fn read_recording() {
+        let mut recording = cmri_tools::recording::MAGIC.to_vec();
+        let poll = Packet::new_poll_request(Address::try_from_node_address(3).unwrap());
+        cmri_tools::recording::write_record(&mut recording, Duration::from_millis(5), &poll.encode_frame()).unwrap();
+        cmri_tools::recording::write_record(&mut recording, Duration::from_millis(9), bad_frame(4).bytes()).unwrap();
+
+        let state = State::read_recording(recording.as_slice()).unwrap();
+        assert_eq!(state.statistics().packets().total(), 2);
+        assert_eq!(state.nodes[3].statistics().poll_packets().total(), 1);
+        assert_eq!(state.bad_packets(), &[bad_frame(4)]);
+        assert_eq!(State::read_recording(b"not a recording".as_slice()).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn packets_limited() {
+        let mut state = State::default();
+        for node_address in 0..=127 {
+            for _ in 0..10 {
+                state.got_packet(&Packet::new_poll_request(Address::try_from_node_address(node_address).unwrap()));
+            }
+        }
+        assert_eq!(state.packets().len(), MAX_PACKETS);
+        assert_eq!(state.packets().front(), Some(&Packet::new_poll_request(Address::try_from_node_address(28).unwrap())));
+        assert_eq!(state.packets().back(), Some(&Packet::new_poll_request(Address::try_from_node_address(127).unwrap())));
+    }
+
     mod got_packet {
         use super::*;
 
@@ -185,8 +613,8 @@ mod tests {
             assert_eq!(
                 state.statistics,
                 Statistics {
-                    packets: (1, 1, default.packets.2),
-                    initialization_packets: (1, 1, default.initialization_packets.2),
+                    packets: counted(1),
+                    initialization_packets: counted(1),
                     ..default
                 }
             );
@@ -194,8 +622,8 @@ mod tests {
             assert_eq!(
                 state.nodes[25].statistics,
                 Statistics {
-                    packets: (1, 1, default.packets.2),
-                    initialization_packets: (1, 1, default.initialization_packets.2),
+                    packets: counted(1),
+                    initialization_packets: counted(1),
                     ..default
                 }
             );
@@ -212,8 +640,8 @@ mod tests {
             assert_eq!(
                 state.statistics,
                 Statistics {
-                    packets: (1, 1, default.packets.2),
-                    poll_packets: (1, 1, default.poll_packets.2),
+                    packets: counted(1),
+                    poll_packets: counted(1),
                     ..default
                 }
             );
@@ -221,8 +649,8 @@ mod tests {
             assert_eq!(
                 state.nodes[25].statistics,
                 Statistics {
-                    packets: (1, 1, default.packets.2),
-                    poll_packets: (1, 1, default.poll_packets.2),
+                    packets: counted(1),
+                    poll_packets: counted(1),
                     ..default
                 }
             );
@@ -239,8 +667,8 @@ mod tests {
             assert_eq!(
                 state.statistics,
                 Statistics {
-                    packets: (1, 1, default.packets.2),
-                    receive_data_packets: (1, 1, default.receive_data_packets.2),
+                    packets: counted(1),
+                    receive_data_packets: counted(1),
                     ..default
                 }
             );
@@ -248,8 +676,8 @@ mod tests {
             assert_eq!(
                 state.nodes[25].statistics,
                 Statistics {
-                    packets: (1, 1, default.packets.2),
-                    receive_data_packets: (1, 1, default.receive_data_packets.2),
+                    packets: counted(1),
+                    receive_data_packets: counted(1),
                     ..default
                 }
             );
@@ -266,8 +694,8 @@ mod tests {
             assert_eq!(
                 state.statistics,
                 Statistics {
-                    packets: (1, 1, default.packets.2),
-                    transmit_data_packets: (1, 1, default.transmit_data_packets.2),
+                    packets: counted(1),
+                    transmit_data_packets: counted(1),
                     ..default
                 }
             );
@@ -275,8 +703,8 @@ mod tests {
             assert_eq!(
                 state.nodes[25].statistics,
                 Statistics {
-                    packets: (1, 1, default.packets.2),
-                    transmit_data_packets: (1, 1, default.transmit_data_packets.2),
+                    packets: counted(1),
+                    transmit_data_packets: counted(1),
                     ..default
                 }
             );
@@ -284,6 +712,52 @@ mod tests {
         }
     }
 
+    mod events {
+        use super::*;
+
+        fn kinds(state: &State) -> Vec<(u8, NodeEventKind)> {
+            state.events().iter().map(|e| (e.address().as_node_address(), e.kind())).collect()
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn appeared_then_stale() {
+            let address = Address::try_from_node_address(12).unwrap();
+            let mut state = State::default();
+            state.got_packet(&Packet::new_poll_request(address));
+            assert_eq!(kinds(&state), [], "A poll alone doesn't mean the node is present");
+
+            state.got_packet(&Packet::new_receive_data(address, [0].try_into().unwrap()));
+            state.got_packet(&Packet::new_receive_data(address, [0].try_into().unwrap()));
+            assert_eq!(kinds(&state), [(12, NodeEventKind::Appeared)]);
+
+            tokio::time::advance(STALE_AFTER).await;
+            assert_eq!(state.tick(), [address]);
+            assert_eq!(kinds(&state), [(12, NodeEventKind::Appeared), (12, NodeEventKind::Stale)]);
+            assert!(state.events()[0].at() <= state.events()[1].at());
+        }
+
+        #[test]
+        fn reconfigured() {
+            let address = Address::try_from_node_address(12).unwrap();
+            let sort = cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap();
+            let mut state = State::default();
+            state.got_packet(&Packet::new_initialization(address, sort));
+            assert_eq!(kinds(&state), [(12, NodeEventKind::Reconfigured)]);
+        }
+
+        #[test]
+        fn bounded() {
+            let sort = cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap();
+            let mut state = State::default();
+            for i in 0..=MAX_EVENTS {
+                let address = Address::try_from_node_address(u8::try_from(i % 128).unwrap()).unwrap();
+                state.got_packet(&Packet::new_initialization(address, sort));
+            }
+            assert_eq!(state.events().len(), MAX_EVENTS);
+            assert_eq!(state.events()[0].address().as_node_address(), 1, "Oldest event should have been dropped");
+        }
+    }
+
     mod got_bad_packet {
         use super::*;
 
@@ -291,14 +765,15 @@ mod tests {
         fn with_valid_address() {
             let mut state = State::default();
 
-            state.got_bad_packet(Some(0));
+            state.got_bad_packet(bad_frame(0));
+            assert_eq!(state.bad_packets(), &[bad_frame(0)]);
 
             let default = Statistics::default();
             assert_eq!(
                 state.statistics,
                 Statistics {
-                    bad_packets: (1, 1, default.bad_packets.2),
-                    packets: (1, 1, default.packets.2),
+                    bad_packets: counted(1),
+                    packets: counted(1),
                     ..default
                 }
             );
@@ -306,8 +781,8 @@ mod tests {
             assert_eq!(
                 state.nodes[0].statistics,
                 Statistics {
-                    bad_packets: (1, 1, default.bad_packets.2),
-                    packets: (1, 1, default.packets.2),
+                    bad_packets: counted(1),
+                    packets: counted(1),
                     ..default
                 }
             );
@@ -320,23 +795,33 @@ mod tests {
             let default = Statistics::default();
             let mut state = State::default();
 
-            state.got_bad_packet(None);
+            state.got_bad_packet(DecodeError::MissingEnd.with_context(&RawFrame::try_from(&[0xFF, 0xFF, 0x02, 0x00, b'P']).unwrap()));
 
             assert_eq!(
                 state.statistics,
                 Statistics {
-                    bad_packets: (1, 1, default.bad_packets.2),
-                    packets: (1, 1, default.packets.2),
+                    bad_packets: counted(1),
+                    packets: counted(1),
                     ..default
                 }
             );
         }
+
+        #[test]
+        fn keeps_latest() {
+            let mut state = State::default();
+            for i in 0..=MAX_BAD_PACKETS {
+                state.got_bad_packet(bad_frame(u8::try_from(i % 128).unwrap()));
+            }
+            assert_eq!(state.bad_packets().len(), MAX_BAD_PACKETS);
+            assert_eq!(state.bad_packets()[0], bad_frame(1));
+        }
     }
 
     #[test]
     fn tick() {
-        fn transform(tuple: &(u16, u64, Readings<u16, { super::super::statistics::READINGS_SIZE }>)) -> (u16, u64, &[u16]) {
-            (tuple.0, tuple.1, tuple.2.as_slice())
+        fn transform(counter: &Counter) -> (u32, u64, Vec<u32>) {
+            (counter.current(), counter.total(), counter.history().iter().copied().collect())
         }
 
         let packet = Packet::new_poll_request(Address::try_from_node_address(0).unwrap());
@@ -344,26 +829,158 @@ mod tests {
         state.got_packet(&packet);
 
         // No ticks so everything should be in current and total only
-        assert_eq!(transform(state.statistics.packets()), (1, 1, [].as_slice()));
-        assert_eq!(transform(state.statistics.bad_packets()), (0, 0, [].as_slice()));
-        assert_eq!(transform(state.statistics.poll_packets()), (1, 1, [].as_slice()));
-        assert_eq!(transform(state.nodes[0].statistics.packets()), (1, 1, [].as_slice()));
-        assert_eq!(transform(state.nodes[0].statistics.bad_packets()), (0, 0, [].as_slice()));
-        assert_eq!(transform(state.nodes[0].statistics.poll_packets()), (1, 1, [].as_slice()));
-        assert_eq!(transform(state.nodes[1].statistics.packets()), (0, 0, [].as_slice()));
-        assert_eq!(transform(state.nodes[1].statistics.bad_packets()), (0, 0, [].as_slice()));
-        assert_eq!(transform(state.nodes[1].statistics.poll_packets()), (0, 0, [].as_slice()));
+        assert_eq!(transform(state.statistics.packets()), (1, 1, vec![]));
+        assert_eq!(transform(state.statistics.bad_packets()), (0, 0, vec![]));
+        assert_eq!(transform(state.statistics.poll_packets()), (1, 1, vec![]));
+        assert_eq!(transform(state.nodes[0].statistics.packets()), (1, 1, vec![]));
+        assert_eq!(transform(state.nodes[0].statistics.bad_packets()), (0, 0, vec![]));
+        assert_eq!(transform(state.nodes[0].statistics.poll_packets()), (1, 1, vec![]));
+        assert_eq!(transform(state.nodes[1].statistics.packets()), (0, 0, vec![]));
+        assert_eq!(transform(state.nodes[1].statistics.bad_packets()), (0, 0, vec![]));
+        assert_eq!(transform(state.nodes[1].statistics.poll_packets()), (0, 0, vec![]));
 
         // Now ticked over so current should be reset and readings should grow
         state.tick();
-        assert_eq!(transform(state.statistics.packets()), (0, 1, [1].as_slice()));
-        assert_eq!(transform(state.statistics.bad_packets()), (0, 0, [0].as_slice()));
-        assert_eq!(transform(state.statistics.poll_packets()), (0, 1, [1].as_slice()));
-        assert_eq!(transform(state.nodes[0].statistics.packets()), (0, 1, [1].as_slice()));
-        assert_eq!(transform(state.nodes[0].statistics.bad_packets()), (0, 0, [0].as_slice()));
-        assert_eq!(transform(state.nodes[0].statistics.poll_packets()), (0, 1, [1].as_slice()));
-        assert_eq!(transform(state.nodes[1].statistics.packets()), (0, 0, [0].as_slice()));
-        assert_eq!(transform(state.nodes[1].statistics.bad_packets()), (0, 0, [0].as_slice()));
-        assert_eq!(transform(state.nodes[1].statistics.poll_packets()), (0, 0, [0].as_slice()));
+        assert_eq!(transform(state.statistics.packets()), (0, 1, vec![1]));
+        assert_eq!(transform(state.statistics.bad_packets()), (0, 0, vec![0]));
+        assert_eq!(transform(state.statistics.poll_packets()), (0, 1, vec![1]));
+        assert_eq!(transform(state.nodes[0].statistics.packets()), (0, 1, vec![1]));
+        assert_eq!(transform(state.nodes[0].statistics.bad_packets()), (0, 0, vec![0]));
+        assert_eq!(transform(state.nodes[0].statistics.poll_packets()), (0, 1, vec![1]));
+        assert_eq!(transform(state.nodes[1].statistics.packets()), (0, 0, vec![0]));
+        assert_eq!(transform(state.nodes[1].statistics.bad_packets()), (0, 0, vec![0]));
+        assert_eq!(transform(state.nodes[1].statistics.poll_packets()), (0, 0, vec![0]));
+    }
+
+    mod filter {
+        use super::*;
+
+        fn address(node_address: u8) -> Address {
+            Address::try_from_node_address(node_address).unwrap()
+        }
+
+        #[test]
+        fn matches_filter() {
+            let mut state = State::default();
+            let poll = |node_address| Packet::new_poll_request(address(node_address));
+            let transmit = |node_address| Packet::new_transmit_data(address(node_address), [0].try_into().unwrap());
+            assert!(state.matches_filter(&poll(0)));
+            assert!(state.matches_filter(&transmit(1)));
+
+            state.set_filter(Filter::new([1].map(address), []));
+            assert!(!state.matches_filter(&poll(0)));
+            assert!(state.matches_filter(&poll(1)));
+            assert!(state.matches_filter(&transmit(1)));
+
+            state.set_filter(Filter::new([], *b"T"));
+            assert!(!state.matches_filter(&poll(0)));
+            assert!(!state.matches_filter(&poll(1)));
+            assert!(state.matches_filter(&transmit(0)));
+            assert!(state.matches_filter(&transmit(1)));
+
+            state.set_filter(Filter::new([1].map(address), *b"PT"));
+            assert!(!state.matches_filter(&poll(0)));
+            assert!(!state.matches_filter(&transmit(0)));
+            assert!(state.matches_filter(&poll(1)));
+            assert!(state.matches_filter(&transmit(1)));
+        }
+
+        #[test]
+        fn visible_nodes() {
+            let mut state = State::default();
+            state.got_packet(&Packet::new_poll_request(address(1)));
+            state.got_packet(&Packet::new_transmit_data(address(2), [0].try_into().unwrap()));
+            state.got_packet(&Packet::new_initialization(address(3), cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap()));
+            let visible = |state: &State| state.visible_nodes().map(|node| node.address().as_node_address()).collect::<Vec<_>>();
+            assert_eq!(visible(&state), vec![1, 2, 3]);
+
+            state.set_filter(Filter::new([2, 3, 4].map(address), []));
+            assert_eq!(visible(&state), vec![2, 3]);
+
+            state.set_filter(Filter::new([], *b"PI"));
+            assert_eq!(visible(&state), vec![1, 3]);
+
+            state.set_filter(Filter::new([2, 3].map(address), *b"PI"));
+            assert_eq!(visible(&state), vec![3]);
+        }
+
+        #[test]
+        fn still_counts_everything() {
+            let mut state = State::default();
+            state.set_filter(Filter::new([1].map(address), *b"P"));
+            state.got_packet(&Packet::new_poll_request(address(0)));
+            state.got_packet(&Packet::new_transmit_data(address(1), [0].try_into().unwrap()));
+            state.got_bad_packet(bad_frame(2));
+
+            assert_eq!(state.statistics().packets().total(), 3);
+            assert_eq!(state.statistics().poll_packets().total(), 1);
+            assert_eq!(state.statistics().transmit_data_packets().total(), 1);
+            assert_eq!(state.statistics().bad_packets().total(), 1);
+            assert_eq!(state.nodes[0].statistics().packets().total(), 1);
+            assert_eq!(state.nodes[1].statistics().packets().total(), 1);
+            assert_eq!(state.nodes[2].statistics().packets().total(), 1);
+        }
+
+        #[test]
+        fn kept_on_reset() {
+            let mut state = State::default();
+            state.set_filter(Filter::new([1].map(address), *b"P"));
+            state.reset();
+            assert_eq!(state.filter(), &Filter::new([1].map(address), *b"P"));
+        }
+    }
+
+    mod stale {
+        use super::*;
+        use tokio::time::advance;
+
+        fn receive_data(state: &mut State, node_address: u8) {
+            state.got_packet(&Packet::new_receive_data(Address::try_from_node_address(node_address).unwrap(), [0].try_into().unwrap()));
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn stale_nodes() {
+            let threshold = Duration::from_secs(5);
+            let mut state = State::default();
+            state.got_packet(&Packet::new_poll_request(Address::try_from_node_address(1).unwrap()));
+            receive_data(&mut state, 2);
+            receive_data(&mut state, 3);
+            assert_eq!(state.stale_nodes(threshold), []);
+
+            advance(Duration::from_secs(3)).await;
+            receive_data(&mut state, 3);
+            assert_eq!(state.stale_nodes(threshold), []);
+
+            advance(Duration::from_secs(2)).await;
+            assert_eq!(state.stale_nodes(threshold), [Address::try_from_node_address(2).unwrap()], "Node 1 was never seen and node 3 was seen recently");
+
+            advance(Duration::from_secs(3)).await;
+            assert_eq!(state.stale_nodes(threshold), [Address::try_from_node_address(2).unwrap(), Address::try_from_node_address(3).unwrap()]);
+
+            receive_data(&mut state, 2);
+            assert_eq!(state.stale_nodes(threshold), [Address::try_from_node_address(3).unwrap()]);
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn tick() {
+            let mut state = State::default();
+            state.set_stale_after(Duration::from_secs(2));
+            assert_eq!(state.stale_after(), Duration::from_secs(2));
+            receive_data(&mut state, 7);
+
+            advance(Duration::from_secs(1)).await;
+            assert_eq!(state.tick(), []);
+            assert!(state.nodes[7].is_responding());
+
+            advance(Duration::from_secs(1)).await;
+            assert_eq!(state.tick(), [Address::try_from_node_address(7).unwrap()]);
+            assert!(state.nodes[7].is_stale());
+
+            advance(Duration::from_secs(1)).await;
+            assert_eq!(state.tick(), [], "Should only go stale once");
+
+            receive_data(&mut state, 7);
+            assert!(!state.nodes[7].is_stale());
+        }
     }
 }
@@ -1,19 +1,36 @@
 use cmri::packet::{Packet, Payload};
-use cmri_tools::readings::Readings;
+use cmri_tools::readings::Counter;
 
 pub const READINGS_SIZE: usize = 300; // 5 minutes worth
 
 /// CMRInet network statistics for a connection/node.
-#[derive(Default, Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct Statistics {
-    pub(super) packets: (u16, u64, Readings<u16, READINGS_SIZE>),                  // (current second, total, previous READINGS_SIZE)
-    pub(super) bad_packets: (u16, u64, Readings<u16, READINGS_SIZE>),              // (current second, total, previous READINGS_SIZE)
-    pub(super) initialization_packets: (u16, u64, Readings<u16, READINGS_SIZE>),   // (current second, total, previous READINGS_SIZE)
-    pub(super) poll_packets: (u16, u64, Readings<u16, READINGS_SIZE>),             // (current second, total, previous READINGS_SIZE)
-    pub(super) receive_data_packets: (u16, u64, Readings<u16, READINGS_SIZE>),     // (current second, total, previous READINGS_SIZE)
-    pub(super) transmit_data_packets: (u16, u64, Readings<u16, READINGS_SIZE>),    // (current second, total, previous READINGS_SIZE)
+    pub(super) packets: Counter,
+    pub(super) bad_packets: Counter,
+    pub(super) initialization_packets: Counter,
+    pub(super) poll_packets: Counter,
+    pub(super) receive_data_packets: Counter,
+    pub(super) transmit_data_packets: Counter,
+    pub(super) set_packets: Counter,
     #[cfg(feature = "experimenter")]
-    pub(super) unknown_packets: (u16, u64, Readings<u16, READINGS_SIZE>)           // (current second, total, previous READINGS_SIZE)
+    pub(super) unknown_packets: Counter
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Self {
+            packets: Counter::new(READINGS_SIZE),
+            bad_packets: Counter::new(READINGS_SIZE),
+            initialization_packets: Counter::new(READINGS_SIZE),
+            poll_packets: Counter::new(READINGS_SIZE),
+            receive_data_packets: Counter::new(READINGS_SIZE),
+            transmit_data_packets: Counter::new(READINGS_SIZE),
+            set_packets: Counter::new(READINGS_SIZE),
+            #[cfg(feature = "experimenter")]
+            unknown_packets: Counter::new(READINGS_SIZE)
+        }
+    }
 }
 
 impl Statistics {
@@ -22,106 +39,165 @@ impl Statistics {
     }
 
     /// How wany total packets have been seen.
-    /// (current second, total, previous `READINGS_SIZE`)
     #[must_use]
-    pub const fn packets(&self) -> &(u16, u64, Readings<u16, READINGS_SIZE>) {
+    pub const fn packets(&self) -> &Counter {
         &self.packets
     }
 
     /// How many bad packets have been seen.
-    /// (current second, total, previous `READINGS_SIZE`)
     #[must_use]
-    pub const fn bad_packets(&self) -> &(u16, u64, Readings<u16, READINGS_SIZE>) {
+    pub const fn bad_packets(&self) -> &Counter {
         &self.bad_packets
     }
 
     /// How many initialization packets have been seen.
-    /// (current second, total, previous `READINGS_SIZE`)
     #[must_use]
-    pub const fn initialization_packets(&self) -> &(u16, u64, Readings<u16, READINGS_SIZE>) {
+    pub const fn initialization_packets(&self) -> &Counter {
         &self.initialization_packets
     }
 
     /// How many poll request packets have been seen.
-    /// (current second, total, previous `READINGS_SIZE`)
     #[must_use]
-    pub const fn poll_packets(&self) -> &(u16, u64, Readings<u16, READINGS_SIZE>) {
+    pub const fn poll_packets(&self) -> &Counter {
         &self.poll_packets
     }
 
     /// How many receive data packets have been seen.
     ///
     /// These are sent by nodes to the controller to report input states in response to a poll request.
-    /// (current second, total, previous `READINGS_SIZE`)
     #[must_use]
-    pub const fn receive_data_packets(&self) -> &(u16, u64, Readings<u16, READINGS_SIZE>) {
+    pub const fn receive_data_packets(&self) -> &Counter {
         &self.receive_data_packets
     }
 
     /// How many transmit data packets have been seen.
     ///
     /// These are sent by the controller to a node to set output states.
-    /// (current second, total, previous `READINGS_SIZE`)
     #[must_use]
-    pub const fn transmit_data_packets(&self) -> &(u16, u64, Readings<u16, READINGS_SIZE>) {
+    pub const fn transmit_data_packets(&self) -> &Counter {
         &self.transmit_data_packets
     }
 
-    /// How many unknown packets have been seen.
+    /// How many set packets have been seen.
     ///
-    /// (current second, total, previous `READINGS_SIZE`)
+    /// These are sent by the controller to a node to set node specific parameters.
+    #[must_use]
+    pub const fn set_packets(&self) -> &Counter {
+        &self.set_packets
+    }
+
+    /// How many unknown packets have been seen.
     #[cfg(feature = "experimenter")]
     #[must_use]
-    pub const fn unknown_packets(&self) -> &(u16, u64, Readings<u16, READINGS_SIZE>) {
+    pub const fn unknown_packets(&self) -> &Counter {
         &self.unknown_packets
     }
 
+    /// Whether any packets of a message type (E.G. `b'P'`) have been seen.
+    pub(super) const fn has_seen_message_type(&self, message_type: u8) -> bool {
+        let counter = match message_type {
+            b'I' => &self.initialization_packets,
+            b'P' => &self.poll_packets,
+            b'R' => &self.receive_data_packets,
+            b'T' => &self.transmit_data_packets,
+            b'S' => &self.set_packets,
+            #[cfg(feature = "experimenter")]
+            _ => &self.unknown_packets,
+            #[cfg(not(feature = "experimenter"))]
+            _ => return false
+        };
+        counter.total() > 0
+    }
+
     pub(super) fn got_bad_packet(&mut self) {
-        self.packets.0 += 1;
-        self.packets.1 += 1;
-        self.bad_packets.0 += 1;
-        self.bad_packets.1 += 1;
+        self.packets.increment(1);
+        self.bad_packets.increment(1);
     }
 
     pub(super) fn got_packet(&mut self, packet: &Packet) {
-        self.packets.0 += 1;
-        self.packets.1 += 1;
+        self.packets.increment(1);
         match packet.payload() {
-            Payload::Initialization { .. } => {
-                self.initialization_packets.0 += 1;
-                self.initialization_packets.1 += 1;
-            },
-            Payload::PollRequest => {
-                self.poll_packets.0 += 1;
-                self.poll_packets.1 += 1;
-            },
-            Payload::ReceiveData { .. } => {
-                self.receive_data_packets.0 += 1;
-                self.receive_data_packets.1 += 1;
-            },
-            Payload::TransmitData { .. } => {
-                self.transmit_data_packets.0 += 1;
-                self.transmit_data_packets.1 += 1;
-            },
+            Payload::Initialization { .. } => self.initialization_packets.increment(1),
+            Payload::PollRequest => self.poll_packets.increment(1),
+            Payload::ReceiveData { .. } => self.receive_data_packets.increment(1),
+            Payload::TransmitData { .. } => self.transmit_data_packets.increment(1),
+            Payload::Set { .. } => self.set_packets.increment(1),
             #[cfg(feature = "experimenter")]
-            Payload::Unknown { .. } => {
-                self.unknown_packets.0 += 1;
-                self.unknown_packets.1 += 1;
-            }
+            Payload::Unknown { .. } => self.unknown_packets.increment(1)
         }
     }
 
     pub(super) fn tick(&mut self) {
-        self.packets.2.push(std::mem::take(&mut self.packets.0));
-        self.bad_packets.2.push(std::mem::take(&mut self.bad_packets.0));
-        self.initialization_packets.2.push(std::mem::take(&mut self.initialization_packets.0));
-        self.poll_packets.2.push(std::mem::take(&mut self.poll_packets.0));
-        self.receive_data_packets.2.push(std::mem::take(&mut self.receive_data_packets.0));
-        self.transmit_data_packets.2.push(std::mem::take(&mut self.transmit_data_packets.0));
+        self.packets.tick();
+        self.bad_packets.tick();
+        self.initialization_packets.tick();
+        self.poll_packets.tick();
+        self.receive_data_packets.tick();
+        self.transmit_data_packets.tick();
+        self.set_packets.tick();
+        #[cfg(feature = "experimenter")]
+        self.unknown_packets.tick();
+    }
+
+    /// Each counter along with its name (E.G. "`poll_packets`"), for exporting.
+    pub(super) fn counters(&self) -> Vec<(&'static str, &Counter)> {
+        vec![
+            ("packets", &self.packets),
+            ("bad_packets", &self.bad_packets),
+            ("initialization_packets", &self.initialization_packets),
+            ("poll_packets", &self.poll_packets),
+            ("receive_data_packets", &self.receive_data_packets),
+            ("transmit_data_packets", &self.transmit_data_packets),
+            ("set_packets", &self.set_packets),
+            #[cfg(feature = "experimenter")]
+            ("unknown_packets", &self.unknown_packets)
+        ]
+    }
+
+    /// Export as JSON, an object with each counter's total and the count in the last whole second, E.G.
+    /// `{"packets": {"total": 10, "last_second": 2}, ...}`.
+    pub(super) fn export_json(&self) -> serde_json::Value {
+        self.counters().into_iter()
+            .map(|(name, counter)| (
+                name.to_string(),
+                serde_json::json!({ "total": counter.total(), "last_second": counter.rate_last_second() })
+            ))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    /// Merge another set of statistics into this one (e.g. when replaying several recordings).
+    ///
+    /// See `Counter::merge`.
+    pub fn merge(&mut self, other: &Self) {
+        self.packets.merge(&other.packets);
+        self.bad_packets.merge(&other.bad_packets);
+        self.initialization_packets.merge(&other.initialization_packets);
+        self.poll_packets.merge(&other.poll_packets);
+        self.receive_data_packets.merge(&other.receive_data_packets);
+        self.transmit_data_packets.merge(&other.transmit_data_packets);
+        self.set_packets.merge(&other.set_packets);
         #[cfg(feature = "experimenter")]
-        self.unknown_packets.2.push(std::mem::take(&mut self.unknown_packets.0));
+        self.unknown_packets.merge(&other.unknown_packets);
     }
+}
 
+impl From<&Statistics> for cmri_tools::file::Statistics {
+    fn from(statistics: &Statistics) -> Self {
+        Self {
+            packets: statistics.packets.total(),
+            bad_packets: statistics.bad_packets.total(),
+            initialization_packets: statistics.initialization_packets.total(),
+            poll_packets: statistics.poll_packets.total(),
+            receive_data_packets: statistics.receive_data_packets.total(),
+            transmit_data_packets: statistics.transmit_data_packets.total(),
+            set_packets: statistics.set_packets.total(),
+            #[cfg(feature = "experimenter")]
+            unknown_packets: statistics.unknown_packets.total(),
+            #[cfg(not(feature = "experimenter"))]
+            unknown_packets: 0
+        }
+    }
 }
 
 
@@ -131,6 +207,27 @@ mod tests {
     use cmri::Address;
     use super::*;
 
+    /// A new counter which has counted `n` in the current second.
+    fn counted(n: u32) -> Counter {
+        let mut counter = Counter::new(READINGS_SIZE);
+        counter.increment(n);
+        counter
+    }
+
+    #[test]
+    fn into_file_statistics() {
+        let mut statistics = Statistics::new();
+        statistics.got_bad_packet();
+        statistics.got_packet(&Packet::new_poll_request(Address::try_from_node_address(0).unwrap()));
+        statistics.tick();
+        statistics.got_packet(&Packet::new_poll_request(Address::try_from_node_address(1).unwrap()));
+
+        assert_eq!(
+            cmri_tools::file::Statistics::from(&statistics),
+            cmri_tools::file::Statistics { packets: 3, bad_packets: 1, poll_packets: 2, ..Default::default() }
+        );
+    }
+
     #[test]
     fn got_bad_packet() {
         let new = Statistics::new();
@@ -141,8 +238,8 @@ mod tests {
         assert_eq!(
             statistics,
             Statistics {
-                bad_packets: (1, 1, new.bad_packets.2),
-                packets: (1, 1, new.packets.2),
+                bad_packets: counted(1),
+                packets: counted(1),
                 ..new
             }
         );
@@ -162,8 +259,8 @@ mod tests {
             assert_eq!(
                 statistics,
                 Statistics {
-                    initialization_packets: (1, 1, new.initialization_packets.2),
-                    packets: (1, 1, new.packets.2),
+                    initialization_packets: counted(1),
+                    packets: counted(1),
                     ..new
                 }
             );
@@ -180,8 +277,8 @@ mod tests {
             assert_eq!(
                 statistics,
                 Statistics {
-                    poll_packets: (1, 1, new.poll_packets.2),
-                    packets: (1, 1, new.packets.2),
+                    poll_packets: counted(1),
+                    packets: counted(1),
                     ..new
                 }
             );
@@ -198,8 +295,8 @@ mod tests {
             assert_eq!(
                 statistics,
                 Statistics {
-                    receive_data_packets: (1, 1, new.receive_data_packets.2),
-                    packets: (1, 1, new.packets.2),
+                    receive_data_packets: counted(1),
+                    packets: counted(1),
                     ..new
                 }
             );
@@ -216,13 +313,32 @@ mod tests {
             assert_eq!(
                 statistics,
                 Statistics {
-                    transmit_data_packets: (1, 1, new.transmit_data_packets.2),
-                    packets: (1, 1, new.packets.2),
+                    transmit_data_packets: counted(1),
+                    packets: counted(1),
                     ..new
                 }
             );
         }
 
+        #[test]
+        fn set() {
+            let packet = Packet::new_set(Address::try_from_node_address(0).unwrap(), [0].try_into().unwrap());
+            let new = Statistics::new();
+            let mut statistics = Statistics::new();
+
+            statistics.got_packet(&packet);
+
+            assert_eq!(
+                statistics,
+                Statistics {
+                    set_packets: counted(1),
+                    packets: counted(1),
+                    ..new
+                }
+            );
+            assert!(statistics.has_seen_message_type(b'S'));
+        }
+
         #[test]
         #[cfg(feature = "experimenter")]
         fn unknown() {
@@ -235,8 +351,8 @@ mod tests {
             assert_eq!(
                 statistics,
                 Statistics {
-                    unknown_packets: (1, 1, new.unknown_packets.2),
-                    packets: (1, 1, new.packets.2),
+                    unknown_packets: counted(1),
+                    packets: counted(1),
                     ..new
                 }
             );
@@ -245,8 +361,8 @@ mod tests {
 
     #[test]
     fn tick() {
-        fn transform(tuple: &(u16, u64, Readings<u16, READINGS_SIZE>)) -> (u16, u64, &[u16]) {
-            (tuple.0, tuple.1, tuple.2.as_slice())
+        fn transform(counter: &Counter) -> (u32, u64, Vec<u32>) {
+            (counter.current(), counter.total(), counter.history().iter().copied().collect())
         }
 
         let init_packet = Packet::new_initialization(Address::try_from_node_address(0).unwrap(), cmri::NodeSort::try_new_smini(0, [0; 6]).unwrap());
@@ -280,45 +396,107 @@ mod tests {
         send_packets(&mut statistics);
         // No ticks so everything should be in current and total only
         #[cfg(not(feature = "experimenter"))]
-        assert_eq!(transform(statistics.packets()), (15, 15, [].as_slice()));
+        assert_eq!(transform(statistics.packets()), (15, 15, vec![]));
         #[cfg(feature = "experimenter")]
-        assert_eq!(transform(statistics.packets()), (16, 16, [].as_slice()));
-        assert_eq!(transform(statistics.bad_packets()), (1, 1, [].as_slice()));
-        assert_eq!(transform(statistics.initialization_packets()), (2, 2, [].as_slice()));
-        assert_eq!(transform(statistics.poll_packets()), (3, 3, [].as_slice()));
-        assert_eq!(transform(statistics.receive_data_packets()), (4, 4, [].as_slice()));
-        assert_eq!(transform(statistics.transmit_data_packets()), (5, 5, [].as_slice()));
+        assert_eq!(transform(statistics.packets()), (16, 16, vec![]));
+        assert_eq!(transform(statistics.bad_packets()), (1, 1, vec![]));
+        assert_eq!(transform(statistics.initialization_packets()), (2, 2, vec![]));
+        assert_eq!(transform(statistics.poll_packets()), (3, 3, vec![]));
+        assert_eq!(transform(statistics.receive_data_packets()), (4, 4, vec![]));
+        assert_eq!(transform(statistics.transmit_data_packets()), (5, 5, vec![]));
         #[cfg(feature = "experimenter")]
-        assert_eq!(transform(statistics.unknown_packets()), (1, 1, [].as_slice()));
+        assert_eq!(transform(statistics.unknown_packets()), (1, 1, vec![]));
 
         // Now ticked over so current should be reset and readings should grow
         statistics.tick();
         #[cfg(not(feature = "experimenter"))]
-        assert_eq!(transform(statistics.packets()), (0, 15, [15].as_slice()));
+        assert_eq!(transform(statistics.packets()), (0, 15, vec![15]));
         #[cfg(feature = "experimenter")]
-        assert_eq!(transform(statistics.packets()), (0, 16, [16].as_slice()));
-        assert_eq!(transform(statistics.bad_packets()), (0, 1, [1].as_slice()));
-        assert_eq!(transform(statistics.initialization_packets()), (0, 2, [2].as_slice()));
-        assert_eq!(transform(statistics.poll_packets()), (0, 3, [3].as_slice()));
-        assert_eq!(transform(statistics.receive_data_packets()), (0, 4, [4].as_slice()));
-        assert_eq!(transform(statistics.transmit_data_packets()), (0, 5, [5].as_slice()));
+        assert_eq!(transform(statistics.packets()), (0, 16, vec![16]));
+        assert_eq!(transform(statistics.bad_packets()), (0, 1, vec![1]));
+        assert_eq!(transform(statistics.initialization_packets()), (0, 2, vec![2]));
+        assert_eq!(transform(statistics.poll_packets()), (0, 3, vec![3]));
+        assert_eq!(transform(statistics.receive_data_packets()), (0, 4, vec![4]));
+        assert_eq!(transform(statistics.transmit_data_packets()), (0, 5, vec![5]));
         #[cfg(feature = "experimenter")]
-        assert_eq!(transform(statistics.unknown_packets()), (0, 1, [1].as_slice()));
+        assert_eq!(transform(statistics.unknown_packets()), (0, 1, vec![1]));
 
         // Receive twice the previous packets and tick again
         send_packets(&mut statistics);
         send_packets(&mut statistics);
         statistics.tick();
         #[cfg(not(feature = "experimenter"))]
-        assert_eq!(transform(statistics.packets()), (0, 45, [15, 30].as_slice()));
+        assert_eq!(transform(statistics.packets()), (0, 45, vec![15, 30]));
         #[cfg(feature = "experimenter")]
-        assert_eq!(transform(statistics.packets()), (0, 48, [16, 32].as_slice()));
-        assert_eq!(transform(statistics.bad_packets()), (0, 3, [1, 2].as_slice()));
-        assert_eq!(transform(statistics.initialization_packets()), (0, 6, [2, 4].as_slice()));
-        assert_eq!(transform(statistics.poll_packets()), (0, 9, [3, 6].as_slice()));
-        assert_eq!(transform(statistics.receive_data_packets()), (0, 12, [4, 8].as_slice()));
-        assert_eq!(transform(statistics.transmit_data_packets()), (0, 15, [5, 10].as_slice()));
+        assert_eq!(transform(statistics.packets()), (0, 48, vec![16, 32]));
+        assert_eq!(transform(statistics.bad_packets()), (0, 3, vec![1, 2]));
+        assert_eq!(transform(statistics.initialization_packets()), (0, 6, vec![2, 4]));
+        assert_eq!(transform(statistics.poll_packets()), (0, 9, vec![3, 6]));
+        assert_eq!(transform(statistics.receive_data_packets()), (0, 12, vec![4, 8]));
+        assert_eq!(transform(statistics.transmit_data_packets()), (0, 15, vec![5, 10]));
         #[cfg(feature = "experimenter")]
-        assert_eq!(transform(statistics.unknown_packets()), (0, 3, [1, 2].as_slice()));
+        assert_eq!(transform(statistics.unknown_packets()), (0, 3, vec![1, 2]));
+    }
+
+    mod merge {
+        use super::*;
+
+        #[test]
+        fn sums_totals() {
+            let poll_packet = Packet::new_poll_request(Address::try_from_node_address(0).unwrap());
+            let mut statistics = Statistics::new();
+            statistics.got_packet(&poll_packet);
+            statistics.got_bad_packet();
+            let mut other = Statistics::new();
+            other.got_packet(&poll_packet);
+            other.got_packet(&poll_packet);
+
+            statistics.merge(&other);
+
+            assert_eq!(statistics.packets().current(), 4);
+            assert_eq!(statistics.packets().total(), 4);
+            assert_eq!(statistics.poll_packets().current(), 3);
+            assert_eq!(statistics.poll_packets().total(), 3);
+            assert_eq!(statistics.bad_packets().total(), 1);
+            assert_eq!(statistics.receive_data_packets().total(), 0);
+        }
+
+        #[test]
+        fn aligns_histories() {
+            let poll_packet = Packet::new_poll_request(Address::try_from_node_address(0).unwrap());
+            let mut statistics = Statistics::new();
+            for count in [1, 2, 3] {
+                for _ in 0..count {
+                    statistics.got_packet(&poll_packet);
+                }
+                statistics.tick();
+            }
+            let mut other = Statistics::new();
+            for count in [10, 20] {
+                for _ in 0..count {
+                    other.got_packet(&poll_packet);
+                }
+                other.tick();
+            }
+
+            statistics.merge(&other);
+
+            assert_eq!(statistics.poll_packets().total(), 36);
+            assert_eq!(statistics.poll_packets().history(), &[1, 12, 23]);
+            assert_eq!(statistics.bad_packets().history(), &[0, 0, 0]);
+        }
+
+        #[test]
+        fn with_empty() {
+            let poll_packet = Packet::new_poll_request(Address::try_from_node_address(0).unwrap());
+            let mut statistics = Statistics::new();
+            statistics.got_packet(&poll_packet);
+            statistics.tick();
+            let expected = statistics.poll_packets().clone();
+
+            statistics.merge(&Statistics::new());
+
+            assert_eq!(statistics.poll_packets(), &expected);
+        }
     }
 }
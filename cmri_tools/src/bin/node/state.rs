@@ -78,6 +78,7 @@ pub fn run_connection(state: Arc<Mutex<State>>, mut connection: Connection, addr
                                     Payload::ReceiveData { .. } => {
                                         panic!("Another node on the CMRInet has address {address}");
                                     },
+                                    Payload::Set { .. } => (), // Nodes have no parameters to set
                                     #[cfg(feature = "experimenter")]
                                     Payload::Unknown { .. } => ()
                                 }
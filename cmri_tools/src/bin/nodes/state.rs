@@ -135,6 +135,7 @@ pub fn run_connection(mut connection: Connection, state: Arc<Mutex<State>>, toki
                                 Payload::ReceiveData { .. } => {
                                     panic!("Another node on the CMRInet has address {address}");
                                 },
+                                Payload::Set { .. } => (), // Nodes have no parameters to set
                                 #[cfg(feature = "experimenter")]
                                 Payload::Unknown { .. } => ()
                             }
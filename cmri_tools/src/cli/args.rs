@@ -27,3 +27,28 @@ pub fn load_nodes() -> clap::Arg {
         .help("Load initial nodes from a file")
         .value_parser(clap::value_parser!(std::path::PathBuf))
 }
+
+#[allow(dead_code, reason = "Included in several files.")]
+pub fn speed() -> clap::Arg {
+    clap::Arg::new("speed")
+        .long("speed")
+        .value_name("MULTIPLIER")
+        .requires("replay")
+        .default_value("1")
+        .help("How fast to replay the recording, e.g. 2 for twice real-time or 0 for as fast as possible")
+        .value_parser(parse_speed)
+}
+
+/// Parse a replay speed multiplier.
+///
+/// # Errors
+///
+/// If the value isn't a finite number of at least 0.
+#[allow(dead_code, reason = "Included in several files.")]
+pub fn parse_speed(value: &str) -> Result<f64, String> {
+    let speed = value.parse::<f64>().map_err(|error| format!("invalid speed: {error}"))?;
+    if !speed.is_finite() || speed < 0.0 {
+        return Err(format!("speed must be finite and at least 0, got {value}"));
+    }
+    Ok(speed)
+}
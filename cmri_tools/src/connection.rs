@@ -1,33 +1,333 @@
 //! Create and use a connection to a CMRInet network.
 
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufStream, AsyncRead, AsyncWrite};
+use std::time::Duration;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncWriteExt, BufWriter, AsyncRead, AsyncWrite, ReadBuf};
 use tokio::net::TcpStream;
+use tokio::sync::mpsc;
 use tokio_serial::SerialStream;
-use tracing::{trace, debug, info, warn};
+use futures_util::StreamExt;
+use tracing::{debug, info, warn};
 use cmri::frame::{Raw as RawFrame, ReceiveError};
+use crate::readings::Readings;
+use crate::recording::Player;
 
 const BUFFER_LEN: usize = 128;
+const CHANNEL_BUFFER: usize = 16;
+/// The number of whole seconds the receive rates are averaged over.
+const RATE_WINDOW: usize = 5;
 
 /// Trait for anything which can be used as a `Connection` to a CMRInet bus.
 #[expect(clippy::module_name_repetitions)]
 pub trait CanBeConnection: AsyncRead + AsyncWrite + std::fmt::Debug + Send + std::marker::Unpin + 'static {}
 impl<T> CanBeConnection for T where T: AsyncRead + AsyncWrite + std::fmt::Debug + Send + std::marker::Unpin + 'static {}
 
+/// Statistics about the reads made from the stream underlying a `Connection`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ReadStatistics {
+    reads: u64,
+    bytes: u64
+}
+
+impl ReadStatistics {
+    /// The number of reads made.
+    #[must_use]
+    pub const fn reads(&self) -> u64 {
+        self.reads
+    }
+
+    /// The number of bytes read.
+    #[must_use]
+    pub const fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// The average number of bytes got from each read, `None` if nothing has been read.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss)]
+    pub fn bytes_per_read(&self) -> Option<f64> {
+        if self.reads == 0 {
+            None
+        } else {
+            Some(self.bytes as f64 / self.reads as f64)
+        }
+    }
+
+    const fn record(&mut self, bytes: usize) {
+        self.reads += 1;
+        self.bytes += bytes as u64;
+    }
+}
+
+/// The rates frames and bytes are received by a `Connection`,
+/// averaged over the last `RATE_WINDOW` whole seconds.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReceiveRates {
+    /// When the current second started.
+    started: tokio::time::Instant,
+    /// The (frames, bytes) received in the current second.
+    current: (u32, u32),
+    /// The (frames, bytes) received in each of the previous whole seconds.
+    window: Readings<(u32, u32), RATE_WINDOW>
+}
+
+impl ReceiveRates {
+    #[must_use]
+    fn new() -> Self {
+        Self {
+            started: tokio::time::Instant::now(),
+            current: (0, 0),
+            window: Readings::new()
+        }
+    }
+
+    /// The average number of frames received per second.
+    #[must_use]
+    pub fn frames_per_sec(&self) -> f64 {
+        self.average(|(frames, _)| frames)
+    }
+
+    /// The average number of bytes received per second.
+    #[must_use]
+    pub fn bytes_per_sec(&self) -> f64 {
+        self.average(|(_, bytes)| bytes)
+    }
+
+    fn average(&self, value: impl Fn((u32, u32)) -> u32) -> f64 {
+        let mut rates = self.clone();
+        rates.roll(tokio::time::Instant::now());
+        if rates.window.is_empty() { return 0.0 }
+        let total = rates.window.as_slice().iter().map(|reading| f64::from(value(*reading))).sum::<f64>();
+        #[expect(clippy::cast_precision_loss, reason = "There's only RATE_WINDOW readings")]
+        let len = rates.window.len() as f64;
+        total / len
+    }
+
+    fn record(&mut self, bytes: usize) {
+        self.roll(tokio::time::Instant::now());
+        self.current.0 = self.current.0.saturating_add(1);
+        self.current.1 = self.current.1.saturating_add(u32::try_from(bytes).unwrap_or(u32::MAX));
+    }
+
+    /// Move any whole seconds which have elapsed into the window.
+    fn roll(&mut self, now: tokio::time::Instant) {
+        let elapsed = now.duration_since(self.started).as_secs();
+        if elapsed == 0 { return }
+        self.window.push(std::mem::take(&mut self.current));
+        for _ in 0..(elapsed - 1).min(RATE_WINDOW as u64) {
+            self.window.push((0, 0)); // Nothing was received in these seconds
+        }
+        self.started += Duration::from_secs(elapsed);
+    }
+}
+
+impl Default for ReceiveRates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How a `Connection` splits the bytes read from a stream into frames.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Mode {
+    /// Bytes are framed with SYN/STX/ETX (the standard for CMRInet).
+    #[default]
+    Framed,
+
+    /// Bytes are delivered in records of a fixed length, without any parsing of SYN/STX/ETX,
+    /// for bridging nonstandard hardware which streams raw bytes.
+    /// The length must be between 4 and the maximum length of a frame (inclusive).
+    FixedLength(usize),
+
+    /// Each frame is preceded by its length as 2 big-endian bytes, for bridging over links
+    /// (E.G. a lossy TCP tunnel) where a corrupted byte shouldn't swallow the following frame.
+    /// Frames are sent with the same prefix.
+    LengthPrefixed
+}
+
+/// What a `Connection` is connected to, E.G. for labelling it in a GUI.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[expect(clippy::module_name_repetitions)]
+pub enum ConnectionKind {
+    /// A TCP stream to a peer.
+    Tcp {
+        /// The address of the peer.
+        peer: std::net::SocketAddr
+    },
+
+    /// A serial port.
+    Serial {
+        /// The name (or path) of the port.
+        port: String,
+        /// The speed of the port.
+        baud: u32
+    },
+
+    /// A WebSocket client (bridged over an in-process channel).
+    WebSocket {
+        /// The address of the client.
+        peer: std::net::SocketAddr
+    },
+
+    /// An in-process channel (see `channel_pair`).
+    Channel,
+
+    /// A replayed recording (see `Connection::new_replay`).
+    Replay,
+
+    /// Any other stream (see `Connection::new`).
+    Stream
+}
+
+impl std::fmt::Display for ConnectionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp { peer } => write!(f, "TCP {peer}"),
+            Self::Serial { port, baud } => write!(f, "Serial {port} at {baud}bps"),
+            Self::WebSocket { peer } => write!(f, "WebSocket {peer}"),
+            Self::Channel => f.write_str("Channel"),
+            Self::Replay => f.write_str("Replay"),
+            Self::Stream => f.write_str("Stream")
+        }
+    }
+}
+
+/// How a `Connection` moves frames.
+enum Transport {
+    /// Bytes over a stream (E.G. TCP or a serial port).
+    Stream(Box<FrameStream<BufWriter<Box<dyn CanBeConnection>>>>),
+    /// Whole frames over an in-process channel, tx is `None` once shutdown.
+    Channel { tx: Option<mpsc::Sender<RawFrame>>, rx: mpsc::Receiver<RawFrame> },
+    /// Whole frames played back from a recording, anything sent is discarded.
+    Replay(Player)
+}
+
 /// A named connection to a CMRInet.
 pub struct Connection {
     name: String,
-    buffer: BufStream<Box<dyn CanBeConnection>>,
-    frame: RawFrame
+    kind: ConnectionKind,
+    transport: Transport,
+    read_buffer_len: usize,
+    read_statistics: ReadStatistics,
+    receive_rates: ReceiveRates,
+    mode: Mode,
+    write_timeout: Option<Duration>
 }
 
 impl Connection {
-    /// Create a new connection from a boxed stream.
+    /// Create a new connection from a boxed stream,
+    /// its kind is `ConnectionKind::Stream` unless changed with `with_kind`.
     pub fn new(name: impl Into<String>, connection: Box<impl CanBeConnection>) -> Self {
+        Self::with_transport(name, Transport::Stream(Box::new(FrameStream::new(BufWriter::with_capacity(BUFFER_LEN, connection)))))
+    }
+
+    fn with_transport(name: impl Into<String>, transport: Transport) -> Self {
+        let kind = match transport {
+            Transport::Stream(_) => ConnectionKind::Stream,
+            Transport::Channel { .. } => ConnectionKind::Channel,
+            Transport::Replay(_) => ConnectionKind::Replay
+        };
         Self {
             name: name.into(),
-            buffer: BufStream::with_capacity(BUFFER_LEN, BUFFER_LEN, connection),
-            frame: RawFrame::new()
+            kind,
+            transport,
+            read_buffer_len: BUFFER_LEN,
+            read_statistics: ReadStatistics::default(),
+            receive_rates: ReceiveRates::default(),
+            mode: Mode::Framed,
+            write_timeout: None
+        }
+    }
+
+    /// Create a new connection from a boxed stream, where frames are prefixed with their length
+    /// (see `Mode::LengthPrefixed`).
+    pub fn new_length_prefixed(name: impl Into<String>, connection: Box<impl CanBeConnection>) -> Self {
+        let mut connection = Self::new(name, connection);
+        if let Transport::Stream(frames) = &mut connection.transport {
+            frames.set_mode(Mode::LengthPrefixed);
+        }
+        connection.mode = Mode::LengthPrefixed;
+        connection
+    }
+
+    /// Change how bytes read are split into frames (the default is `Mode::Framed`).
+    ///
+    /// Should be used before the connection is used, as any partially received frame is lost.
+    /// Has no effect on the reading of a channel or replay connection (see `channel_pair` and `new_replay`).
+    ///
+    /// # Errors
+    ///
+    /// `std::io::ErrorKind::InvalidInput` if a `Mode::FixedLength` record couldn't fit in a frame.
+    pub fn with_mode(self, mode: Mode) -> std::io::Result<Self> {
+        let transport = match self.transport {
+            Transport::Stream(frames) => Transport::Stream(Box::new(frames.with_mode(mode)?)),
+            transport @ (Transport::Channel { .. } | Transport::Replay(_)) => {
+                check_mode(mode)?;
+                transport
+            }
+        };
+        Ok(Self { transport, mode, ..self })
+    }
+
+    /// Get how bytes read are split into frames.
+    #[must_use]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Change the size of the buffer used when reading (the default is 128 bytes),
+    /// a larger buffer allows a fast connection to be read with fewer reads.
+    ///
+    /// Should be used before the connection is used, as anything already buffered is lost.
+    /// Has no effect on the reading of a channel or replay connection (see `channel_pair` and `new_replay`).
+    ///
+    /// # Errors
+    ///
+    /// `std::io::ErrorKind::InvalidInput` if len is 0.
+    pub fn with_read_buffer(self, len: usize) -> std::io::Result<Self> {
+        let transport = match self.transport {
+            Transport::Stream(frames) => Transport::Stream(Box::new(FrameStream::with_capacity(len, frames.into_inner())?.with_mode(self.mode)?)),
+            transport @ (Transport::Channel { .. } | Transport::Replay(_)) => {
+                check_capacity(len)?;
+                transport
+            }
+        };
+        Ok(Self {
+            transport,
+            read_buffer_len: len,
+            ..self
+        })
+    }
+
+    /// Get the size of the buffer used when reading.
+    #[must_use]
+    pub const fn read_buffer_len(&self) -> usize {
+        self.read_buffer_len
+    }
+
+    /// Get statistics about the reads made from the underlying stream.
+    #[must_use]
+    pub const fn read_statistics(&self) -> ReadStatistics {
+        match &self.transport {
+            Transport::Stream(frames) => frames.read_statistics(),
+            Transport::Channel { .. } | Transport::Replay(_) => self.read_statistics
+        }
+    }
+
+    /// Get the rates frames and bytes are being received.
+    #[must_use]
+    pub fn receive_rates(&self) -> ReceiveRates {
+        self.receive_rates.clone()
+    }
+
+    /// Reset the read statistics and receive rates, E.G. after tuning the connection.
+    pub fn reset_stats(&mut self) {
+        if let Transport::Stream(frames) = &mut self.transport {
+            frames.read_statistics = ReadStatistics::default();
         }
+        self.read_statistics = ReadStatistics::default();
+        self.receive_rates = ReceiveRates::default();
     }
 
     /// Get the connection's name.
@@ -36,6 +336,37 @@ impl Connection {
         &self.name
     }
 
+    /// Change the connection's name, E.G. to give each of several `channel_pair`s a unique name.
+    #[must_use]
+    pub fn with_name(self, name: impl Into<String>) -> Self {
+        Self { name: name.into(), ..self }
+    }
+
+    /// Get what the connection is connected to.
+    #[must_use]
+    pub const fn kind(&self) -> &ConnectionKind {
+        &self.kind
+    }
+
+    /// Change what the connection is recorded as being connected to,
+    /// E.G. when a stream passed to `new` is actually a serial port.
+    #[must_use]
+    pub fn with_kind(self, kind: ConnectionKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    /// Get the maximum time a `send` may take before it fails.
+    #[must_use]
+    pub const fn write_timeout(&self) -> Option<Duration> {
+        self.write_timeout
+    }
+
+    /// Set the maximum time a `send` may take before it fails,
+    /// this stops a stalled peer (E.G. a wedged serial device) blocking forever.
+    pub const fn set_write_timeout(&mut self, timeout: Duration) {
+        self.write_timeout = Some(timeout);
+    }
+
     /// Send a frame to the CMRInet.
     ///
     /// # Errors
@@ -48,10 +379,32 @@ impl Connection {
     /// * `std::io::ErrorKind::NetworkUnreachable`
     /// * `std::io::ErrorKind::NetworkDown`
     /// * `std::io::ErrorKind::BrokenPipe`
+    /// * `std::io::ErrorKind::TimedOut` (if a write timeout is set)
     pub async fn send(&mut self, frame: &RawFrame) -> std::io::Result<()> {
         debug!("Sending to {}: {:?}", self.name, frame);
-        self.buffer.write_all(frame).await?;
-        self.buffer.flush().await
+        let write = async {
+            match &mut self.transport {
+                Transport::Stream(frames) => {
+                    let buffer = frames.get_mut();
+                    if self.mode == Mode::LengthPrefixed {
+                        #[expect(clippy::cast_possible_truncation, reason = "A frame is at most a few hundred bytes")]
+                        buffer.write_all(&(frame.len() as u16).to_be_bytes()).await?;
+                    }
+                    buffer.write_all(frame).await?;
+                    buffer.flush().await
+                },
+                Transport::Channel { tx, .. } => match tx {
+                    None => Err(std::io::ErrorKind::BrokenPipe.into()),
+                    Some(tx) => tx.send(*frame).await.map_err(|_| std::io::ErrorKind::BrokenPipe.into())
+                },
+                Transport::Replay(_) => Ok(())
+            }
+        };
+        match self.write_timeout {
+            None => write.await,
+            Some(timeout) => tokio::time::timeout(timeout, write).await
+                .unwrap_or_else(|_| Err(std::io::Error::new(std::io::ErrorKind::TimedOut, format!("Write not completed within {timeout:?}"))))
+        }
     }
 
     /// Receive a frame from the CMRInet.
@@ -63,20 +416,25 @@ impl Connection {
     /// * `std::io::ErrorKind::ConnectionAborted`
     /// * `std::io::ErrorKind::NetworkDown`
     /// * `std::io::ErrorKind::BrokenPipe`
+    /// * `std::io::ErrorKind::UnexpectedEof` (if a read returns no bytes, I.E. the peer has closed the connection,
+    ///   or the end of a replayed recording has been reached)
     pub async fn receive(&mut self) -> std::io::Result<RawFrame> {
-        loop {
-            let byte = self.buffer.read_u8().await?;
-            trace!("{} read byte {} {:02x}", self.name, byte, byte);
-            match self.frame.receive(byte) {
-                Err(ReceiveError::AlreadyComplete) => unreachable!(),
-                Err(error) => warn!("Received bad frame from {}: {error:?}", self.name),
-                Ok(false) => (),
-                Ok(true) => {
-                    debug!("Received from {}: {:?}", self.name, self.frame);
-                    return Ok(std::mem::take(&mut self.frame));
-                }
+        let frame = match &mut self.transport {
+            Transport::Stream(frames) => frames.next().await.ok_or(std::io::ErrorKind::UnexpectedEof)??,
+            Transport::Channel { rx, .. } => {
+                let frame = rx.recv().await.ok_or(std::io::ErrorKind::UnexpectedEof)?;
+                self.read_statistics.record(frame.len());
+                frame
+            },
+            Transport::Replay(player) => {
+                let frame = player.next_frame().await.ok_or(std::io::ErrorKind::UnexpectedEof)?;
+                self.read_statistics.record(frame.len());
+                frame
             }
-        }
+        };
+        self.receive_rates.record(frame.len());
+        debug!("Received from {}: {:?}", self.name, frame);
+        Ok(frame)
     }
 
     /// Shutdown/close the connection.
@@ -89,7 +447,14 @@ impl Connection {
     /// * `std::io::ErrorKind::NetworkDown`
     /// * `std::io::ErrorKind::BrokenPipe`
     pub async fn shutdown(&mut self) -> std::io::Result<()> {
-        self.buffer.get_mut().shutdown().await
+        match &mut self.transport {
+            Transport::Stream(frames) => frames.get_mut().shutdown().await,
+            Transport::Channel { tx, .. } => {
+                *tx = None;
+                Ok(())
+            },
+            Transport::Replay(_) => Ok(())
+        }
     }
 
     /// Create a new connection to a TCP server.
@@ -106,8 +471,9 @@ impl Connection {
         let connection = std::net::TcpStream::connect(&address)?;
         connection.set_nonblocking(true)?;
         let connection = tokio::net::TcpStream::from_std(connection)?;
+        let peer = connection.peer_addr()?;
         info!("Connected to {address}");
-        Ok(Self::new(address, Box::new(connection)))
+        Ok(Self::new(address, Box::new(connection)).with_kind(ConnectionKind::Tcp { peer }))
     }
 
     /// Create a new connection to a serial port.
@@ -129,25 +495,63 @@ impl Connection {
         #[cfg(unix)]
         connection.set_exclusive(true)?;
         info!("Connected to {port} at {}bps", readable::num::Unsigned::from(baud));
-        Ok(Self::new(port, Box::new(connection)))
+        Ok(Self::new(port, Box::new(connection)).with_kind(ConnectionKind::Serial { port: port.to_string(), baud }))
+    }
+
+    /// Create a new connection which replays a recording (see `crate::recording`),
+    /// so it can be used anywhere a live connection can.
+    ///
+    /// Each frame is received at its recorded time multiplied by speed (E.G. 2.0 for twice real-time,
+    /// or 0.0 for as fast as possible), once all the frames have been received `receive` returns
+    /// `std::io::ErrorKind::UnexpectedEof`. Anything sent is discarded.
+    ///
+    /// # Errors
+    ///
+    /// * If the recording can't be read (see `crate::recording::read`).
+    /// * `std::io::ErrorKind::InvalidInput` if speed isn't a finite number of at least 0.
+    pub fn new_replay(path: &std::path::Path, speed: f64) -> std::io::Result<Self> {
+        let frames = crate::recording::read(std::io::BufReader::new(std::fs::File::open(path)?))?;
+        let connection = Self::replay(path.display().to_string(), frames, speed)?;
+        info!("Replaying {}", path.display());
+        Ok(connection)
+    }
+
+    /// Create a new connection which replays frames, each paired with the time since the
+    /// start of the recording, see `new_replay`.
+    ///
+    /// # Errors
+    ///
+    /// `std::io::ErrorKind::InvalidInput` if speed isn't a finite number of at least 0.
+    pub fn replay(name: impl Into<String>, frames: impl IntoIterator<Item = (Duration, RawFrame)>, speed: f64) -> std::io::Result<Self> {
+        if !speed.is_finite() || speed < 0.0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Speed must be finite and at least 0, got {speed}")));
+        }
+        let player = if speed == 0.0 {
+            Player::new(frames.into_iter().map(|(_, frame)| (Duration::ZERO, frame)))
+        } else {
+            let player = Player::new(frames);
+            player.set_speed(speed);
+            player
+        };
+        Ok(Self::with_transport(name, Transport::Replay(player)))
     }
 }
 
 impl TryFrom<std::net::TcpStream> for Connection {
     type Error = std::io::Error;
     fn try_from(connection: std::net::TcpStream) -> std::io::Result<Self> {
-        let name = connection.peer_addr()?.to_string();
+        let peer = connection.peer_addr()?;
         connection.set_nonblocking(true)?;
         let connection = TcpStream::from_std(connection)?;
-        Ok(Self::new(name, Box::new(connection)))
+        Ok(Self::new(peer.to_string(), Box::new(connection)).with_kind(ConnectionKind::Tcp { peer }))
     }
 }
 
 impl TryFrom<tokio::net::TcpStream> for Connection {
     type Error = std::io::Error;
     fn try_from(connection: tokio::net::TcpStream) -> std::io::Result<Self> {
-        let name = connection.peer_addr()?.to_string();
-        Ok(Self::new(name, Box::new(connection)))
+        let peer = connection.peer_addr()?;
+        Ok(Self::new(peer.to_string(), Box::new(connection)).with_kind(ConnectionKind::Tcp { peer }))
     }
 }
 
@@ -155,11 +559,254 @@ impl core::fmt::Debug for Connection {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Connection")
          .field("name", &self.name)
+         .field("kind", &self.kind)
+         .field("read_buffer_len", &self.read_buffer_len)
+         .field("read_statistics", &self.read_statistics())
+         .field("receive_rates", &self.receive_rates)
+         .field("mode", &self.mode)
+         .field("write_timeout", &self.write_timeout)
+         .finish_non_exhaustive()
+    }
+}
+
+
+/// Decodes the frames read from an `AsyncRead` (E.G. a TCP stream or serial port) as a `Stream`,
+/// so they can be used with `.next().await` and stream combinators.
+///
+/// Bad frames are skipped (with a warning), a read error is passed on as an item
+/// and the stream ends when a read returns no bytes (I.E. the peer closed the connection).
+///
+/// # Example
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use futures_util::StreamExt;
+/// use cmri_tools::connection::FrameStream;
+/// let bytes: &[u8] = &[0xFF, 0xFF, 0x02, 65, b'P', 0x03, 0xFF, 0xFF, 0x02, 66, b'P', 0x03];
+/// let frames = FrameStream::new(bytes).collect::<Vec<_>>().await;
+/// assert_eq!(frames.len(), 2);
+/// assert_eq!(frames[1].as_ref().unwrap().as_slice(), &[0xFF, 0xFF, 0x02, 66, b'P', 0x03]);
+/// # })
+/// ```
+pub struct FrameStream<R> {
+    reader: R,
+    buffer: Box<[u8]>,
+    /// The range of the buffer which has been read but not yet used.
+    unused: std::ops::Range<usize>,
+    read_statistics: ReadStatistics,
+    mode: Mode,
+    frame: RawFrame,
+    record: Vec<u8>
+}
+
+impl<R: AsyncRead + Unpin> FrameStream<R> {
+    /// Create a new `FrameStream`, reading upto 128 bytes at a time.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: vec![0; BUFFER_LEN].into_boxed_slice(),
+            unused: 0..0,
+            read_statistics: ReadStatistics::default(),
+            mode: Mode::Framed,
+            frame: RawFrame::new(),
+            record: Vec::new()
+        }
+    }
+
+    /// Create a new `FrameStream`, reading upto capacity bytes at a time
+    /// (a larger buffer allows a fast reader to be read with fewer reads).
+    ///
+    /// # Errors
+    ///
+    /// `std::io::ErrorKind::InvalidInput` if capacity is 0.
+    pub fn with_capacity(capacity: usize, reader: R) -> std::io::Result<Self> {
+        check_capacity(capacity)?;
+        Ok(Self {
+            buffer: vec![0; capacity].into_boxed_slice(),
+            ..Self::new(reader)
+        })
+    }
+
+    /// Change how bytes read are split into frames (the default is `Mode::Framed`).
+    ///
+    /// Should be used before the stream is used, as any partially received frame is lost.
+    ///
+    /// # Errors
+    ///
+    /// `std::io::ErrorKind::InvalidInput` if a `Mode::FixedLength` record couldn't fit in a frame.
+    pub fn with_mode(mut self, mode: Mode) -> std::io::Result<Self> {
+        check_mode(mode)?;
+        self.set_mode(mode);
+        Ok(self)
+    }
+
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.frame.reset();
+        self.record.clear();
+    }
+
+    /// Get statistics about the reads made from the reader.
+    #[must_use]
+    pub const fn read_statistics(&self) -> ReadStatistics {
+        self.read_statistics
+    }
+
+    /// Get a reference to the underlying reader.
+    pub const fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader, E.G. to write to it.
+    pub const fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Unwrap the underlying reader, anything read but not yet decoded is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Use the bytes read but not yet used, until a frame is complete.
+    fn decode(&mut self) -> Option<std::io::Result<RawFrame>> {
+        while !self.unused.is_empty() {
+            let bytes = &self.buffer[self.unused.clone()];
+            match self.mode {
+                Mode::Framed => {
+                    let (used, result) = self.frame.receive_slice(bytes);
+                    self.unused.start += used;
+                    match result {
+                        Err(ReceiveError::AlreadyComplete) => self.frame.reset(),
+                        Err(error) => warn!("Received bad frame: {error:?}"),
+                        Ok(false) => (),
+                        Ok(true) => return Some(Ok(std::mem::take(&mut self.frame)))
+                    }
+                },
+                Mode::FixedLength(len) => {
+                    let used = (len - self.record.len()).min(bytes.len());
+                    self.record.extend_from_slice(&bytes[..used]);
+                    self.unused.start += used;
+                    if self.record.len() == len {
+                        let frame = RawFrame::try_from(self.record.as_slice());
+                        self.record.clear();
+                        // The record's length was checked by with_mode, so this can't fail
+                        return Some(frame.map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error)));
+                    }
+                },
+                Mode::LengthPrefixed => {
+                    self.record.push(bytes[0]);
+                    self.unused.start += 1;
+                    if self.record.len() < 2 { continue }
+                    let len = usize::from(u16::from_be_bytes([self.record[0], self.record[1]]));
+                    if !(4..=RawFrame::new().available()).contains(&len) {
+                        warn!("Received bad length prefix: {len}");
+                        self.record.clear();
+                    } else if self.record.len() == len + 2 {
+                        let frame = RawFrame::try_from(&self.record[2..]);
+                        self.record.clear();
+                        // The length prefix was checked as it was received, so this can't fail
+                        return Some(frame.map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error)));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<R: AsyncRead + Unpin> futures_util::Stream for FrameStream<R> {
+    type Item = std::io::Result<RawFrame>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(frame) = this.decode() {
+                debug!("Received {frame:?}");
+                return Poll::Ready(Some(frame));
+            }
+
+            let mut buffer = ReadBuf::new(&mut this.buffer);
+            match Pin::new(&mut this.reader).poll_read(cx, &mut buffer) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(error)) => return Poll::Ready(Some(Err(error))),
+                Poll::Ready(Ok(())) => {
+                    let len = buffer.filled().len();
+                    if len == 0 {
+                        return Poll::Ready(None);
+                    }
+                    this.read_statistics.record(len);
+                    this.unused = 0..len;
+                }
+            }
+        }
+    }
+}
+
+impl<R: std::fmt::Debug> std::fmt::Debug for FrameStream<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameStream")
+         .field("reader", &self.reader)
+         .field("capacity", &self.buffer.len())
+         .field("unused", &self.unused.len())
+         .field("read_statistics", &self.read_statistics)
+         .field("mode", &self.mode)
          .field("frame", &self.frame)
          .finish_non_exhaustive()
     }
 }
 
+/// Check a read buffer can hold at least 1 byte.
+///
+/// # Errors
+///
+/// `std::io::ErrorKind::InvalidInput` if capacity is 0.
+fn check_capacity(capacity: usize) -> std::io::Result<()> {
+    if capacity == 0 {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "A read buffer needs to hold at least 1 byte"));
+    }
+    Ok(())
+}
+
+/// Check a `Mode::FixedLength` record can fit in a frame.
+///
+/// # Errors
+///
+/// `std::io::ErrorKind::InvalidInput` if it can't.
+fn check_mode(mode: Mode) -> std::io::Result<()> {
+    if let Mode::FixedLength(len) = mode {
+        if !(4..=RawFrame::new().available()).contains(&len) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Records of {len} bytes can't fit in a frame")));
+        }
+    }
+    Ok(())
+}
+
+
+/// Create a pair of connections which are linked by in-process channels,
+/// a frame sent on one is received (whole) by the other.
+///
+/// This is useful for running a controller and nodes in one process (E.G. for demos/tests),
+/// without the overhead of sockets or encoding/decoding bytes.
+///
+/// # Example
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use cmri::{Address, packet::Packet};
+/// let (mut controller, mut node) = cmri_tools::connection::channel_pair();
+/// let frame = Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).encode_frame();
+/// controller.send(&frame).await.unwrap();
+/// assert_eq!(node.receive().await.unwrap(), frame);
+/// # })
+/// ```
+#[must_use]
+pub fn channel_pair() -> (Connection, Connection) {
+    let (a_tx, b_rx) = mpsc::channel(CHANNEL_BUFFER);
+    let (b_tx, a_rx) = mpsc::channel(CHANNEL_BUFFER);
+    let new = |name, tx, rx| Connection::with_transport(name, Transport::Channel { tx: Some(tx), rx });
+    (new("channel a", a_tx, a_rx), new("channel b", b_tx, b_rx))
+}
+
 
 /// Split a 'String' (probabbly from the commandline) into a tuple of port and speed.
 ///
@@ -219,6 +866,101 @@ mod tests {
         }
     }
 
+    mod frame_stream {
+        use futures_util::StreamExt;
+        use cmri::packet::Packet;
+        use super::*;
+
+        fn frame(node_address: u8) -> RawFrame {
+            Packet::new_poll_request(Address::try_from_node_address(node_address).unwrap()).encode_frame()
+        }
+
+        #[tokio::test]
+        async fn skips_bad_frame() {
+            let reader = tokio_test::io::Builder::new()
+                .read(frame(1).as_slice())
+                .read(&[0xFF, 0xFF, 0x02, 0x03]) // Too short
+                .read(frame(2).as_slice())
+                .build();
+            let mut frames = FrameStream::new(reader);
+            assert_eq!(frames.next().await.unwrap().unwrap(), frame(1));
+            assert_eq!(frames.next().await.unwrap().unwrap(), frame(2));
+            assert!(frames.next().await.is_none());
+        }
+
+        #[tokio::test]
+        async fn frames_within_one_read() {
+            let bytes = [frame(1).as_slice(), &[0xFF, 0xFF, 0x02, 0x03], frame(2).as_slice()].concat();
+            let reader = tokio_test::io::Builder::new().read(&bytes).build();
+            let frames = FrameStream::new(reader).map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(frames, vec![frame(1), frame(2)]);
+        }
+
+        #[tokio::test]
+        async fn frames_across_reads() {
+            let bytes = [frame(1).as_slice(), &[0xFF, 0xFF, 0x02, 0x03], frame(2).as_slice()].concat();
+            let reader = tokio_test::io::Builder::new()
+                .read(&bytes[..3])
+                .read(&bytes[3..9])
+                .read(&bytes[9..])
+                .build();
+            let frames = FrameStream::with_capacity(4, reader).unwrap().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(frames, vec![frame(1), frame(2)]);
+        }
+
+        #[tokio::test]
+        async fn partial_frame_at_end() {
+            let reader = tokio_test::io::Builder::new()
+                .read(frame(1).as_slice())
+                .read(&[0xFF, 0xFF, 0x02, 65])
+                .build();
+            let frames = FrameStream::new(reader).map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(frames, vec![frame(1)]);
+        }
+
+        #[tokio::test]
+        async fn read_error() {
+            let reader = tokio_test::io::Builder::new()
+                .read(frame(1).as_slice())
+                .read_error(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "testing"))
+                .read(frame(2).as_slice())
+                .build();
+            let mut frames = FrameStream::new(reader);
+            assert_eq!(frames.next().await.unwrap().unwrap(), frame(1));
+            assert_eq!(frames.next().await.unwrap().unwrap_err().kind(), std::io::ErrorKind::ConnectionReset);
+            assert_eq!(frames.next().await.unwrap().unwrap(), frame(2));
+            assert!(frames.next().await.is_none());
+        }
+
+        #[test]
+        fn zero_capacity() {
+            assert_eq!(
+                FrameStream::with_capacity(0, tokio_test::io::Builder::new().build()).unwrap_err().kind(),
+                std::io::ErrorKind::InvalidInput
+            );
+        }
+
+        #[tokio::test]
+        async fn length_prefixed() {
+            let bytes = [&[0x00, 0x06], frame(1).as_slice(), &[0x00, 0x06], frame(2).as_slice()].concat();
+            let reader = tokio_test::io::Builder::new().read(&bytes).build();
+            let frames = FrameStream::new(reader).with_mode(Mode::LengthPrefixed).unwrap().map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(frames, vec![frame(1), frame(2)]);
+        }
+
+        #[tokio::test]
+        async fn read_statistics() {
+            let reader = tokio_test::io::Builder::new()
+                .read(frame(1).as_slice())
+                .read(frame(2).as_slice())
+                .build();
+            let mut frames = FrameStream::new(reader);
+            while frames.next().await.is_some() {}
+            assert_eq!(frames.read_statistics().reads(), 2);
+            assert_eq!(frames.read_statistics().bytes(), 12);
+        }
+    }
+
     mod connection {
         use cmri::packet::Packet;
         use super::*;
@@ -246,6 +988,18 @@ mod tests {
                 let mut connection = Connection::new("connection", Box::new(stream));
                 assert!(connection.send(&frame).await.is_err_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe));
             }
+
+            #[tokio::test(start_paused = true)]
+            async fn timeout() {
+                let frame = Packet::new_poll_request(Address::try_from_node_address(5).unwrap()).encode_frame();
+                let (stream, _peer) = tokio::io::duplex(1); // Peer never reads so writing stalls
+                let mut connection = Connection::new("connection", Box::new(stream));
+                connection.set_write_timeout(Duration::from_secs(2));
+
+                let start = tokio::time::Instant::now();
+                assert!(connection.send(&frame).await.is_err_and(|e| e.kind() == std::io::ErrorKind::TimedOut));
+                assert_eq!(start.elapsed(), Duration::from_secs(2));
+            }
         }
 
         mod receive {
@@ -302,6 +1056,391 @@ mod tests {
                 let mut connection = Connection::new("connection", Box::new(stream));
                 assert!(connection.receive().await.is_err_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe));
             }
+
+            #[tokio::test]
+            async fn eof() {
+                let stream = tokio_test::io::Builder::new()
+                    .read(&[0xFF, 0xFF, 0x02])
+                    .build();
+                let mut connection = Connection::new("connection", Box::new(stream));
+                assert!(connection.receive().await.is_err_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof));
+            }
+
+            #[tokio::test]
+            async fn zero_length_read() {
+                // Every read returns Ok(0), so receive mustn't keep retrying
+                let mut connection = Connection::new("connection", Box::new(tokio::io::empty()));
+                let result = tokio::time::timeout(Duration::from_secs(1), connection.receive()).await;
+                assert!(result.is_ok_and(|r| r.is_err_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof)));
+            }
+        }
+
+        mod mode {
+            use super::*;
+
+            #[tokio::test]
+            async fn fixed_length() {
+                let stream = tokio_test::io::Builder::new()
+                    .read(&[0xFF, 0xFF, 0x02, 0x10]) // Would be the start of a frame if framed
+                    .read(&[0x03, 1, 2, 3, 4, 5, 6])
+                    .build();
+                let mut connection = Connection::new("connection", Box::new(stream)).with_mode(Mode::FixedLength(5)).unwrap();
+                assert_eq!(connection.mode(), Mode::FixedLength(5));
+                assert_eq!(connection.receive().await.unwrap().as_slice(), [0xFF, 0xFF, 0x02, 0x10, 0x03]);
+                assert_eq!(connection.receive().await.unwrap().as_slice(), [1, 2, 3, 4, 5]);
+                assert!(connection.receive().await.is_err_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof));
+                assert_eq!(connection.read_statistics().bytes(), 11);
+            }
+
+            #[test]
+            fn fixed_length_invalid() {
+                for len in [0, 3, 519] {
+                    let stream = tokio_test::io::Builder::new().build();
+                    let result = Connection::new("connection", Box::new(stream)).with_mode(Mode::FixedLength(len));
+                    assert!(result.is_err_and(|e| e.kind() == std::io::ErrorKind::InvalidInput));
+                }
+            }
+
+            #[test]
+            fn default() {
+                let stream = tokio_test::io::Builder::new().build();
+                assert_eq!(Connection::new("connection", Box::new(stream)).mode(), Mode::Framed);
+            }
+
+            mod length_prefixed {
+                use super::*;
+
+                #[tokio::test]
+                async fn split_across_reads() {
+                    let stream = tokio_test::io::Builder::new()
+                        .read(&[0x00])                         // First byte of length
+                        .read(&[0x06, 0xFF, 0xFF, 0x02])       // Rest of length & start of a poll request frame
+                        .read(&[75, b'P', 0x03, 0x00, 0x06])   // End of frame & length of the next
+                        .read(&[0xFF, 0xFF, 0x02, 85, b'P', 0x03])
+                        .build();
+                    let mut connection = Connection::new_length_prefixed("connection", Box::new(stream));
+                    assert_eq!(connection.mode(), Mode::LengthPrefixed);
+                    assert_eq!(
+                        connection.receive().await.unwrap().try_as_packet().unwrap(),
+                        Packet::new_poll_request(Address::try_from_node_address(10).unwrap())
+                    );
+                    assert_eq!(
+                        connection.receive().await.unwrap().try_as_packet().unwrap(),
+                        Packet::new_poll_request(Address::try_from_node_address(20).unwrap())
+                    );
+                    assert!(connection.receive().await.is_err_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof));
+                }
+
+                #[tokio::test]
+                async fn not_scanned_for_syn_stx() {
+                    // Anything can be in a frame, it's just passed on (E.G. a corrupted byte)
+                    let stream = tokio_test::io::Builder::new()
+                        .read(&[0x00, 0x05, 0x02, 0x03, 0xFF, 0xFF, 0x02])
+                        .build();
+                    let mut connection = Connection::new_length_prefixed("connection", Box::new(stream));
+                    assert_eq!(connection.receive().await.unwrap().as_slice(), [0x02, 0x03, 0xFF, 0xFF, 0x02]);
+                }
+
+                #[tokio::test]
+                async fn bad_length() {
+                    let slice = [0xFF, 0xFF, 0x02, 80, b'P', 0x03];
+                    let stream = tokio_test::io::Builder::new()
+                        .read(&[0x00, 0x03])     // Too short to be a frame - ignored
+                        .read(&[0x00, 0x06])
+                        .read(&slice)
+                        .build();
+                    let mut connection = Connection::new_length_prefixed("connection", Box::new(stream));
+                    assert_eq!(connection.receive().await.unwrap().as_slice(), slice);
+                }
+
+                #[tokio::test]
+                async fn send() {
+                    let frame = Packet::new_poll_request(Address::try_from_node_address(5).unwrap()).encode_frame();
+                    let stream = tokio_test::io::Builder::new()
+                        .write(&[0x00, 0x06])
+                        .write(frame.as_slice())
+                        .build();
+                    let mut connection = Connection::new_length_prefixed("connection", Box::new(stream));
+                    assert!(connection.send(&frame).await.is_ok());
+                }
+
+                #[tokio::test]
+                async fn round_trip() {
+                    let (a, b) = tokio::io::duplex(64);
+                    let mut a = Connection::new_length_prefixed("a", Box::new(a));
+                    let mut b = Connection::new("b", Box::new(b)).with_mode(Mode::LengthPrefixed).unwrap();
+                    let frame = Packet::new_receive_data(Address::try_from_node_address(5).unwrap(), [0x10, 2].try_into().unwrap()).encode_frame();
+                    a.send(&frame).await.unwrap();
+                    assert_eq!(b.receive().await.unwrap(), frame);
+                }
+            }
+        }
+
+        mod read_buffer {
+            use super::*;
+
+            fn frames() -> Vec<u8> {
+                [10, 20, 30].into_iter()
+                    .flat_map(|a| Packet::new_poll_request(Address::try_from_node_address(a).unwrap()).encode_frame().to_vec())
+                    .collect()
+            }
+
+            #[tokio::test]
+            async fn large_chunk_read_in_one_go() {
+                let chunk = frames();
+                let stream = tokio_test::io::Builder::new()
+                    .read(&chunk)
+                    .build();
+                let mut connection = Connection::new("connection", Box::new(stream)).with_read_buffer(256).unwrap();
+                assert_eq!(connection.read_buffer_len(), 256);
+
+                for address in [10, 20, 30] {
+                    assert_eq!(
+                        connection.receive().await.unwrap().try_as_packet().unwrap(),
+                        Packet::new_poll_request(Address::try_from_node_address(address).unwrap())
+                    );
+                }
+                assert_eq!(connection.read_statistics().reads(), 1);
+                assert_eq!(connection.read_statistics().bytes(), 18);
+                assert_eq!(connection.read_statistics().bytes_per_read(), Some(18.0));
+            }
+
+            #[tokio::test]
+            async fn small_buffer_needs_more_reads() {
+                let chunk = frames();
+                let stream = tokio_test::io::Builder::new()
+                    .read(&chunk)
+                    .build();
+                let mut connection = Connection::new("connection", Box::new(stream)).with_read_buffer(4).unwrap();
+                assert_eq!(connection.read_buffer_len(), 4);
+
+                for address in [10, 20, 30] {
+                    assert_eq!(
+                        connection.receive().await.unwrap().try_as_packet().unwrap(),
+                        Packet::new_poll_request(Address::try_from_node_address(address).unwrap())
+                    );
+                }
+                assert_eq!(connection.read_statistics().reads(), 5);
+                assert_eq!(connection.read_statistics().bytes(), 18);
+            }
+
+            #[test]
+            fn zero() {
+                let stream = tokio_test::io::Builder::new().build();
+                assert_eq!(Connection::new("connection", Box::new(stream)).with_read_buffer(0).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+                let (connection, _) = channel_pair();
+                assert_eq!(connection.with_read_buffer(0).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+            }
+
+            #[test]
+            fn default() {
+                let stream = tokio_test::io::Builder::new().build();
+                let connection = Connection::new("connection", Box::new(stream));
+                assert_eq!(connection.read_buffer_len(), BUFFER_LEN);
+                assert_eq!(connection.read_statistics(), ReadStatistics::default());
+                assert_eq!(connection.read_statistics().bytes_per_read(), None);
+            }
+        }
+
+        mod receive_rates {
+            use super::*;
+
+            fn frame() -> RawFrame {
+                Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).encode_frame()
+            }
+
+            #[tokio::test(start_paused = true)]
+            async fn windowed() {
+                let (mut a, mut b) = channel_pair();
+                assert!(b.receive_rates().frames_per_sec().abs() < f64::EPSILON);
+
+                // 4 frames in the first second, 2 in the second
+                for count in [4, 2] {
+                    for _ in 0..count {
+                        a.send(&frame()).await.unwrap();
+                        b.receive().await.unwrap();
+                    }
+                    tokio::time::advance(Duration::from_secs(1)).await;
+                }
+                assert!((b.receive_rates().frames_per_sec() - 3.0).abs() < f64::EPSILON);
+                assert!((b.receive_rates().bytes_per_sec() - 18.0).abs() < f64::EPSILON);
+
+                // Idle seconds bring the average down, until the window is all idle
+                tokio::time::advance(Duration::from_secs(2)).await;
+                assert!((b.receive_rates().frames_per_sec() - 1.5).abs() < f64::EPSILON);
+                tokio::time::advance(Duration::from_secs(RATE_WINDOW as u64)).await;
+                assert!(b.receive_rates().frames_per_sec().abs() < f64::EPSILON);
+            }
+
+            #[tokio::test(start_paused = true)]
+            async fn current_second_not_included() {
+                let (mut a, mut b) = channel_pair();
+                a.send(&frame()).await.unwrap();
+                b.receive().await.unwrap();
+                assert!(b.receive_rates().frames_per_sec().abs() < f64::EPSILON);
+                tokio::time::advance(Duration::from_secs(1)).await;
+                assert!((b.receive_rates().frames_per_sec() - 1.0).abs() < f64::EPSILON);
+            }
+
+            #[tokio::test(start_paused = true)]
+            async fn reset() {
+                let (mut a, mut b) = channel_pair();
+                a.send(&frame()).await.unwrap();
+                b.receive().await.unwrap();
+                tokio::time::advance(Duration::from_secs(1)).await;
+                assert!(b.receive_rates().frames_per_sec() > 0.0);
+
+                b.reset_stats();
+                assert!(b.receive_rates().frames_per_sec().abs() < f64::EPSILON);
+                assert!(b.receive_rates().bytes_per_sec().abs() < f64::EPSILON);
+                assert_eq!(b.read_statistics(), ReadStatistics::default());
+            }
+        }
+
+        mod channel_pair {
+            use super::*;
+
+            #[tokio::test]
+            async fn both_directions() {
+                let (mut a, mut b) = channel_pair();
+                let poll = Packet::new_poll_request(Address::try_from_node_address(5).unwrap()).encode_frame();
+                let receive = Packet::new_receive_data(Address::try_from_node_address(5).unwrap(), [0x10, 2].try_into().unwrap()).encode_frame();
+
+                a.send(&poll).await.unwrap();
+                assert_eq!(b.receive().await.unwrap(), poll);
+
+                b.send(&receive).await.unwrap();
+                assert_eq!(a.receive().await.unwrap(), receive);
+
+                assert_eq!(a.read_statistics().reads(), 1);
+                assert_eq!(b.read_statistics().reads(), 1);
+            }
+
+            #[tokio::test]
+            async fn names() {
+                let (a, b) = channel_pair();
+                assert_eq!(a.name(), "channel a");
+                assert_eq!(b.name(), "channel b");
+            }
+
+            #[tokio::test]
+            async fn renamed() {
+                let (mut a, b) = channel_pair();
+                let mut b = b.with_name("renamed");
+                assert_eq!(b.name(), "renamed");
+
+                let frame = Packet::new_poll_request(Address::try_from_node_address(5).unwrap()).encode_frame();
+                a.send(&frame).await.unwrap();
+                assert_eq!(b.receive().await.unwrap(), frame);
+            }
+
+            #[tokio::test]
+            async fn shutdown() {
+                let (mut a, mut b) = channel_pair();
+                a.shutdown().await.unwrap();
+                assert!(b.receive().await.is_err_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof));
+                assert!(a.send(&RawFrame::new()).await.is_err_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe));
+            }
+
+            #[tokio::test]
+            async fn peer_dropped() {
+                let (mut a, b) = channel_pair();
+                drop(b);
+                assert!(a.send(&RawFrame::new()).await.is_err_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe));
+                assert!(a.receive().await.is_err_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof));
+            }
+        }
+
+        mod replay {
+            use super::*;
+
+            fn frames() -> Vec<(Duration, RawFrame)> {
+                (1..=3).map(|node| (
+                    Duration::from_millis(u64::from(node - 1) * 100),
+                    Packet::new_poll_request(Address::try_from_node_address(node).unwrap()).encode_frame()
+                )).collect()
+            }
+
+            /// A recording as it would be read from a file.
+            fn recording() -> Vec<u8> {
+                let mut bytes = crate::recording::MAGIC.to_vec();
+                for (offset, frame) in frames() {
+                    crate::recording::write_record(&mut bytes, offset, &frame).unwrap();
+                }
+                bytes
+            }
+
+            async fn receive_all(connection: &mut Connection) -> Vec<(Duration, RawFrame)> {
+                let started = tokio::time::Instant::now();
+                let mut received = Vec::new();
+                loop {
+                    match connection.receive().await {
+                        Ok(frame) => received.push((started.elapsed(), frame)),
+                        Err(error) => {
+                            assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+                            return received;
+                        }
+                    }
+                }
+            }
+
+            #[tokio::test(start_paused = true)]
+            async fn recorded_speed() {
+                let recorded = crate::recording::read(recording().as_slice()).unwrap();
+                let mut connection = Connection::replay("replay", recorded, 1.0).unwrap();
+                assert_eq!(receive_all(&mut connection).await, frames());
+                assert_eq!(connection.read_statistics().reads(), 3);
+            }
+
+            #[tokio::test(start_paused = true)]
+            async fn receive_cancelled() {
+                let mut connection = Connection::replay("replay", frames(), 1.0).unwrap();
+                assert_eq!(connection.receive().await.unwrap(), frames()[0].1);
+
+                // E.G. another branch of a select! completing first
+                assert!(tokio::time::timeout(Duration::from_millis(50), connection.receive()).await.is_err());
+                assert_eq!(receive_all(&mut connection).await.into_iter().map(|(_, frame)| frame).collect::<Vec<_>>(), vec![frames()[1].1, frames()[2].1]);
+            }
+
+            #[tokio::test(start_paused = true)]
+            async fn double_speed() {
+                let mut connection = Connection::replay("replay", frames(), 2.0).unwrap();
+                let expected = frames().into_iter().map(|(offset, frame)| (offset / 2, frame)).collect::<Vec<_>>();
+                assert_eq!(receive_all(&mut connection).await, expected);
+            }
+
+            #[tokio::test(start_paused = true)]
+            async fn as_fast_as_possible() {
+                let mut connection = Connection::replay("replay", frames(), 0.0).unwrap();
+                let expected = frames().into_iter().map(|(_, frame)| (Duration::ZERO, frame)).collect::<Vec<_>>();
+                assert_eq!(receive_all(&mut connection).await, expected);
+            }
+
+            #[tokio::test(start_paused = true)]
+            async fn send_is_discarded() {
+                let mut connection = Connection::replay("replay", frames(), 1.0).unwrap();
+                connection.send(&frames()[2].1).await.unwrap();
+                assert_eq!(connection.receive().await.unwrap(), frames()[0].1);
+                connection.shutdown().await.unwrap();
+            }
+
+            #[test]
+            fn invalid_speed() {
+                for speed in [-1.0, f64::NAN, f64::INFINITY] {
+                    assert_eq!(
+                        Connection::replay("replay", frames(), speed).unwrap_err().kind(),
+                        std::io::ErrorKind::InvalidInput
+                    );
+                }
+            }
+
+            #[test]
+            fn missing_file() {
+                assert_eq!(
+                    Connection::new_replay(std::path::Path::new("/does/not/exist.rec"), 1.0).unwrap_err().kind(),
+                    std::io::ErrorKind::NotFound
+                );
+            }
         }
 
         #[test]
@@ -313,5 +1452,90 @@ mod tests {
                 "connection name"
             );
         }
+
+        mod kind {
+            use super::*;
+
+            #[test]
+            fn stream() {
+                let connection = Connection::new("connection", Box::new(tokio_test::io::Builder::new().build()));
+                assert_eq!(connection.kind(), &ConnectionKind::Stream);
+            }
+
+            #[test]
+            fn channel() {
+                let (a, b) = channel_pair();
+                assert_eq!(a.kind(), &ConnectionKind::Channel);
+                assert_eq!(b.with_name("renamed").kind(), &ConnectionKind::Channel);
+            }
+
+            #[test]
+            fn replay() {
+                let connection = Connection::replay("replay", [], 1.0).unwrap();
+                assert_eq!(connection.kind(), &ConnectionKind::Replay);
+            }
+
+            #[tokio::test]
+            async fn tcp_client() {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                let peer = listener.local_addr().unwrap();
+                let connection = Connection::new_tcp_client(peer.to_string()).unwrap();
+                assert_eq!(connection.kind(), &ConnectionKind::Tcp { peer });
+            }
+
+            #[tokio::test]
+            async fn from_std_tcp_stream() {
+                let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+                let peer = listener.local_addr().unwrap();
+                let connection = Connection::try_from(std::net::TcpStream::connect(peer).unwrap()).unwrap();
+                assert_eq!(connection.name(), peer.to_string());
+                assert_eq!(connection.kind(), &ConnectionKind::Tcp { peer });
+            }
+
+            #[tokio::test]
+            async fn from_tokio_tcp_stream() {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                let peer = listener.local_addr().unwrap();
+                let connection = Connection::try_from(TcpStream::connect(peer).await.unwrap()).unwrap();
+                assert_eq!(connection.name(), peer.to_string());
+                assert_eq!(connection.kind(), &ConnectionKind::Tcp { peer });
+            }
+
+            #[test]
+            fn serial() {
+                let kind = ConnectionKind::Serial { port: String::from("/dev/ttyACM0"), baud: 115_200 };
+                let connection = Connection::new("/dev/ttyACM0", Box::new(tokio_test::io::Builder::new().build())).with_kind(kind.clone());
+                assert_eq!(connection.kind(), &kind);
+            }
+
+            #[test]
+            fn websocket() {
+                let kind = ConnectionKind::WebSocket { peer: "192.168.1.2:5000".parse().unwrap() };
+                let (connection, _) = channel_pair();
+                let connection = connection.with_kind(kind.clone());
+                assert_eq!(connection.kind(), &kind);
+            }
+
+            #[test]
+            fn display() {
+                let peer = "192.168.1.2:5000".parse().unwrap();
+                assert_eq!(ConnectionKind::Tcp { peer }.to_string(), "TCP 192.168.1.2:5000");
+                assert_eq!(ConnectionKind::Serial { port: String::from("/dev/ttyACM0"), baud: 115_200 }.to_string(), "Serial /dev/ttyACM0 at 115200bps");
+                assert_eq!(ConnectionKind::WebSocket { peer }.to_string(), "WebSocket 192.168.1.2:5000");
+                assert_eq!(ConnectionKind::Channel.to_string(), "Channel");
+                assert_eq!(ConnectionKind::Replay.to_string(), "Replay");
+                assert_eq!(ConnectionKind::Stream.to_string(), "Stream");
+            }
+        }
+
+        #[test]
+        fn write_timeout() {
+            let stream = tokio_test::io::Builder::new().build();
+            let mut connection = Connection::new("connection", Box::new(stream));
+            assert_eq!(connection.write_timeout(), None);
+
+            connection.set_write_timeout(Duration::from_millis(500));
+            assert_eq!(connection.write_timeout(), Some(Duration::from_millis(500)));
+        }
     }
 }
@@ -1,8 +1,8 @@
 //! Common methods for saving/loading data.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use anyhow::Context;
-use cmri::{Address, NodeSort};
+use cmri::{Address, NodeSort, node_configuration::NodeSortChanges, packet::Data};
 
 #[derive(Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 struct File {
@@ -104,6 +104,29 @@ pub struct Labels {
 }
 
 impl Labels {
+    /// Generate default labels for each of a node's input and output bits.
+    ///
+    /// * SMINI - "Card C Port P Bit B" (output cards 0 & 1, input card 2, ports A-C).
+    /// * USIC/SUSIC - "Card C Port P Bit B" (cards numbered by position, ports A-C or A-D).
+    /// * CPNODE/CPMEGA - "Input Byte N Bit B" and "Output Byte N Bit B".
+    #[must_use]
+    pub fn default_for(sort: &NodeSort) -> Self {
+        use cmri::node_configuration::Direction;
+        let mut labels = Self::default();
+        for bit in sort.map_configuration(|configuration| configuration.bit_map()).unwrap_or_default() {
+            let (direction, map) = match bit.direction {
+                Direction::Input => ("Input", &mut labels.inputs),
+                Direction::Output => ("Output", &mut labels.outputs)
+            };
+            let label = bit.card.map_or_else(
+                || format!("{direction} Byte {} Bit {}", bit.byte, bit.bit),
+                |card| format!("Card {card} Port {} Bit {}", ["A", "B", "C", "D"].get(usize::from(bit.byte)).unwrap_or(&"?"), bit.bit)
+            );
+            map.insert(usize::from(bit.index), label);
+        }
+        labels
+    }
+
   #[allow(clippy::missing_errors_doc)]
   fn deserialize_field<'de, D>(deserializer: D) -> Result<HashMap<usize, String>, D::Error> where D: serde::Deserializer<'de> {
         struct Visitor;
@@ -135,6 +158,61 @@ impl Labels {
     }
 }
 
+/// Find the index of the bit with a label (the lowest if several bits share it).
+fn index_of_label<H: std::hash::BuildHasher>(labels: &HashMap<usize, String, H>, label: &str) -> Option<usize> {
+    labels.iter()
+        .filter(|(_, value)| *value == label)
+        .map(|(index, _)| *index)
+        .min()
+}
+
+/// Get the state of the bit with a label (E.G. from `Labels::inputs`),
+/// if several bits share the label the lowest is used.
+///
+/// Returns `None` if no bit has the label, or its bit is beyond the end of data.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use cmri::packet::Data;
+/// use cmri_tools::file::bit_by_label;
+/// let data = Data::try_from(&[0b0000_0010]).unwrap();
+/// let labels = HashMap::from([(1, String::from("Turnout 1"))]);
+/// assert_eq!(bit_by_label(&data, &labels, "Turnout 1"), Some(true));
+/// assert_eq!(bit_by_label(&data, &labels, "Turnout 2"), None);
+/// ```
+#[must_use]
+pub fn bit_by_label<H: std::hash::BuildHasher>(data: &Data, labels: &HashMap<usize, String, H>, label: &str) -> Option<bool> {
+    index_of_label(labels, label)
+        .filter(|index| *index < data.len() * 8)
+        .map(|index| data.get_bit(index))
+}
+
+/// Set the state of the bit with a label (E.G. from `Labels::outputs`),
+/// if several bits share the label the lowest is used.
+///
+/// Returns `false` (leaving data unchanged) if no bit has the label, or its bit is beyond the end of data.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use cmri::packet::Data;
+/// use cmri_tools::file::set_bit_by_label;
+/// let mut data = Data::new(1);
+/// let labels = HashMap::from([(1, String::from("Signal 1"))]);
+/// assert!(set_bit_by_label(&mut data, &labels, "Signal 1", true));
+/// assert_eq!(*data, [0b0000_0010]);
+/// ```
+pub fn set_bit_by_label<H: std::hash::BuildHasher>(data: &mut Data, labels: &HashMap<usize, String, H>, label: &str, value: bool) -> bool {
+    let Some(index) = index_of_label(labels, label).filter(|index| *index < data.len() * 8) else {
+        return false;
+    };
+    data.set_bit(index, value);
+    true
+}
+
 /// Details about a CMRInet node.
 #[derive(Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Node {
@@ -189,6 +267,125 @@ pub fn save_nodes(path: &std::path::Path, nodes: Vec<Node>) -> anyhow::Result<()
 }
 
 
+/// A difference between two lists of `Node`s, see [`diff`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NodeChange {
+    /// A node was added at the address.
+    Added(Address),
+
+    /// The node at the address was removed.
+    Removed(Address),
+
+    /// The node at the address was changed.
+    Modified {
+        /// The node's address.
+        address: Address,
+        /// Whether the name changed.
+        name: bool,
+        /// Which parts of the node's type/configuration changed.
+        sort: NodeSortChanges,
+        /// Whether the labels changed.
+        labels: bool
+    }
+}
+
+/// Find what changed between two lists of `Node`s (E.G. two versions of a file), matching nodes by address.
+///
+/// The changes are in address order.
+#[must_use]
+pub fn diff(old: &[Node], new: &[Node]) -> Vec<NodeChange> {
+    let old = old.iter().map(|node| (node.address, node)).collect::<BTreeMap<_, _>>();
+    let new = new.iter().map(|node| (node.address, node)).collect::<BTreeMap<_, _>>();
+    let addresses = old.keys().chain(new.keys()).copied().collect::<BTreeSet<_>>();
+
+    addresses.into_iter()
+        .filter_map(|address| {
+            match (old.get(&address), new.get(&address)) {
+                (None, None) => None,
+                (None, Some(_)) => Some(NodeChange::Added(address)),
+                (Some(_), None) => Some(NodeChange::Removed(address)),
+                (Some(old), Some(new)) => {
+                    let name = old.name != new.name;
+                    let sort = old.sort.diff(&new.sort);
+                    let labels = old.labels != new.labels;
+                    (name || labels || !sort.is_empty()).then_some(NodeChange::Modified { address, name, sort, labels })
+                }
+            }
+        })
+        .collect()
+}
+
+
+/// Packet counts from a session, see [`Session`].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    /// The total number of packets seen.
+    pub packets: u64,
+    /// The number of packets which couldn't be decoded.
+    pub bad_packets: u64,
+    /// The number of initialization packets seen.
+    pub initialization_packets: u64,
+    /// The number of poll request packets seen.
+    pub poll_packets: u64,
+    /// The number of receive data packets seen.
+    pub receive_data_packets: u64,
+    /// The number of transmit data packets seen.
+    pub transmit_data_packets: u64,
+    /// The number of set packets seen.
+    pub set_packets: u64,
+    /// The number of packets of an unknown type seen.
+    pub unknown_packets: u64
+}
+
+/// A debugging session (E.G. from the hub or monitor), bundling the nodes discovered,
+/// statistics about the traffic seen and where the traffic was recorded to, so it can be shared.
+#[derive(Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Session {
+    /// The nodes discovered.
+    #[serde(default)]
+    pub nodes: Vec<Node>,
+
+    /// Statistics about the traffic seen.
+    #[serde(default)]
+    pub statistics: Statistics,
+
+    /// The file the session's traffic was recorded to, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recording: Option<std::path::PathBuf>
+}
+
+impl Session {
+    /// Load a previously saved session from a file.
+    ///
+    /// # Errors
+    ///
+    /// * If the file can't be read.
+    /// * If the JSON can't be parsed.
+    /// * If the JSON contains invalid data (E.G. two nodes with the same address).
+    pub fn load(path: &std::path::Path) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path).context(format!("Failed to read file {}", path.display()))?;
+        let session: Self = serde_json::from_str(&json).context(format!("Failed to parse JSON in {}", path.display()))?;
+
+        let mut addresses = BTreeSet::new();
+        if let Some(node) = session.nodes.iter().find(|node| !addresses.insert(node.address)) {
+            anyhow::bail!("Duplicate node address {} in {}", node.address.as_node_address(), path.display());
+        }
+        Ok(session)
+    }
+
+    /// Save the session to a file, replacing it if it already exists.
+    ///
+    /// # Errors
+    ///
+    /// * If the file can't be written.
+    pub fn save(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to generate JSON")?;
+        std::fs::write(path, json.as_bytes()).context(format!("Failed to write file {}", path.display()))
+    }
+}
+
+
 #[allow(clippy::missing_panics_doc, reason="tests")]
 #[cfg(test)]
 mod tests {
@@ -299,6 +496,181 @@ mod tests {
         }"#}
     }
 
+    mod default_labels {
+        use cmri::node_configuration::{CpnodeOptions, node_cards::NodeCard};
+        use super::*;
+
+        #[test]
+        fn smini() {
+            let labels = Labels::default_for(&NodeSort::try_new_smini(0, [0; 6]).unwrap());
+            assert_eq!(labels.inputs.len(), 24);
+            assert_eq!(labels.outputs.len(), 48);
+            assert_eq!(labels.inputs.get(&0).map(String::as_str), Some("Card 2 Port A Bit 0"));
+            assert_eq!(labels.inputs.get(&23).map(String::as_str), Some("Card 2 Port C Bit 7"));
+            assert_eq!(labels.outputs.get(&0).map(String::as_str), Some("Card 0 Port A Bit 0"));
+            assert_eq!(labels.outputs.get(&47).map(String::as_str), Some("Card 1 Port C Bit 7"));
+        }
+
+        #[test]
+        fn usic() {
+            let sort = NodeSort::try_new_usic(0, &[NodeCard::Output, NodeCard::Input, NodeCard::Output]).unwrap();
+            let labels = Labels::default_for(&sort);
+            assert_eq!(labels.inputs.len(), 24);
+            assert_eq!(labels.outputs.len(), 48);
+            assert_eq!(labels.inputs.get(&9).map(String::as_str), Some("Card 1 Port B Bit 1"));
+            assert_eq!(labels.outputs.get(&24).map(String::as_str), Some("Card 2 Port A Bit 0"));
+        }
+
+        #[test]
+        fn susic() {
+            let sort = NodeSort::try_new_susic(0, &[NodeCard::Input, NodeCard::Output]).unwrap();
+            let labels = Labels::default_for(&sort);
+            assert_eq!(labels.inputs.len(), 32);
+            assert_eq!(labels.outputs.len(), 32);
+            assert_eq!(labels.inputs.get(&31).map(String::as_str), Some("Card 0 Port D Bit 7"));
+            assert_eq!(labels.outputs.get(&0).map(String::as_str), Some("Card 1 Port A Bit 0"));
+        }
+
+        #[test]
+        fn cpnode() {
+            let sort = NodeSort::try_new_cpnode(0, CpnodeOptions::default(), 2, 3).unwrap();
+            let labels = Labels::default_for(&sort);
+            assert_eq!(labels.inputs.len(), 16);
+            assert_eq!(labels.outputs.len(), 24);
+            assert_eq!(labels.inputs.get(&10).map(String::as_str), Some("Input Byte 1 Bit 2"));
+            assert_eq!(labels.outputs.get(&23).map(String::as_str), Some("Output Byte 2 Bit 7"));
+        }
+    }
+
+    mod bit_by_label {
+        use super::*;
+
+        fn labels() -> HashMap<usize, String> {
+            HashMap::from([
+                (0, String::from("Turnout 1")),
+                (9, String::from("Turnout 2")),
+                (3, String::from("Signal")),
+                (1, String::from("Signal")),
+                (16, String::from("Beyond"))
+            ])
+        }
+
+        #[test]
+        fn get() {
+            let data = Data::try_from(&[0b0000_0001, 0b0000_0000]).unwrap();
+            assert_eq!(bit_by_label(&data, &labels(), "Turnout 1"), Some(true));
+            assert_eq!(bit_by_label(&data, &labels(), "Turnout 2"), Some(false));
+        }
+
+        #[test]
+        fn duplicate_uses_lowest() {
+            let data = Data::try_from(&[0b0000_1000]).unwrap();
+            assert_eq!(bit_by_label(&data, &labels(), "Signal"), Some(false));
+
+            let mut data = Data::new(1);
+            assert!(set_bit_by_label(&mut data, &labels(), "Signal", true));
+            assert_eq!(*data, [0b0000_0010]);
+        }
+
+        #[test]
+        fn set() {
+            let mut data = Data::try_from(&[0b0000_0000, 0b1111_1111]).unwrap();
+            assert!(set_bit_by_label(&mut data, &labels(), "Turnout 1", true));
+            assert!(set_bit_by_label(&mut data, &labels(), "Turnout 2", false));
+            assert_eq!(*data, [0b0000_0001, 0b1111_1101]);
+        }
+
+        #[test]
+        fn missing_label() {
+            let mut data = Data::new(2);
+            assert_eq!(bit_by_label(&data, &labels(), "Nothing"), None);
+            assert!(!set_bit_by_label(&mut data, &labels(), "Nothing", true));
+            assert_eq!(data, Data::new(2));
+        }
+
+        #[test]
+        fn beyond_data() {
+            let mut data = Data::new(2);
+            assert_eq!(bit_by_label(&data, &labels(), "Beyond"), None);
+            assert!(!set_bit_by_label(&mut data, &labels(), "Beyond", true));
+            assert_eq!(data, Data::new(2));
+        }
+    }
+
+    mod diff {
+        use super::*;
+
+        #[test]
+        fn unchanged() {
+            assert_eq!(diff(&nodes(), &nodes()), Vec::new());
+        }
+
+        #[test]
+        fn added_node() {
+            let mut new = nodes();
+            new.push(Node {
+                name: None,
+                address: Address::try_from_node_address(5).unwrap(),
+                sort: NodeSort::try_new_smini(0, [0; 6]).unwrap(),
+                labels: Labels::default()
+            });
+            assert_eq!(diff(&nodes(), &new), vec![NodeChange::Added(Address::try_from_node_address(5).unwrap())]);
+        }
+
+        #[test]
+        fn removed_node() {
+            let mut new = nodes();
+            new.remove(0);
+            assert_eq!(diff(&nodes(), &new), vec![NodeChange::Removed(Address::try_from_node_address(0).unwrap())]);
+        }
+
+        #[test]
+        fn reconfigured_node() {
+            let mut new = nodes();
+            new[1].sort = NodeSort::try_new_smini(100, [3, 6, 12, 24, 48, 96]).unwrap();
+            new[1].labels.inputs.insert(5, String::from("Input 5"));
+            assert_eq!(
+                diff(&nodes(), &new),
+                vec![NodeChange::Modified {
+                    address: Address::try_from_node_address(1).unwrap(),
+                    name: false,
+                    sort: NodeSortChanges::TRANSMIT_DELAY,
+                    labels: true
+                }]
+            );
+        }
+
+        #[test]
+        fn renamed_node() {
+            let mut new = nodes();
+            new[0].name = Some(String::from("Renamed"));
+            assert_eq!(
+                diff(&nodes(), &new),
+                vec![NodeChange::Modified {
+                    address: Address::try_from_node_address(0).unwrap(),
+                    name: true,
+                    sort: NodeSortChanges::empty(),
+                    labels: false
+                }]
+            );
+        }
+
+        #[test]
+        fn changed_node_type() {
+            let mut new = nodes();
+            new[0].sort = NodeSort::try_new_usic(0, &[cmri::node_configuration::node_cards::NodeCard::Input]).unwrap();
+            assert_eq!(
+                diff(&nodes(), &new),
+                vec![NodeChange::Modified {
+                    address: Address::try_from_node_address(0).unwrap(),
+                    name: false,
+                    sort: NodeSortChanges::NODE_TYPE,
+                    labels: false
+                }]
+            );
+        }
+    }
+
     mod save_nodes {
         use super::*;
 
@@ -421,4 +793,62 @@ mod tests {
             assert_eq!(&error_message, "invalid value: integer `200`, expected between 0 and 127 (inclusive) at line 1 column 24");
         }
     }
+
+    mod session {
+        use super::*;
+
+        fn session() -> Session {
+            Session {
+                nodes: nodes().into_iter().take(1).collect(),
+                statistics: Statistics {
+                    packets: 100,
+                    bad_packets: 2,
+                    initialization_packets: 1,
+                    poll_packets: 50,
+                    receive_data_packets: 40,
+                    transmit_data_packets: 7,
+                    set_packets: 3,
+                    unknown_packets: 0
+                },
+                recording: Some(std::path::PathBuf::from("traffic.log"))
+            }
+        }
+
+        #[test]
+        fn round_trip() {
+            let temp_file = TempFile::new();
+            session().save(temp_file.path()).unwrap();
+            assert_eq!(Session::load(temp_file.path()).unwrap(), session());
+        }
+
+        #[test]
+        fn without_recording() {
+            let temp_file = TempFile::new();
+            let session = Session { recording: None, ..session() };
+            session.save(temp_file.path()).unwrap();
+            assert!(!std::fs::read_to_string(temp_file.path()).unwrap().contains("recording"));
+            assert_eq!(Session::load(temp_file.path()).unwrap(), session);
+        }
+
+        #[test]
+        fn nodes_file() {
+            // A file of just nodes can be loaded as a session
+            let temp_file = TempFile::new();
+            std::fs::write(temp_file.path(), nodes_json()).unwrap();
+            let session = Session::load(temp_file.path()).unwrap();
+            assert_eq!(session.nodes, nodes());
+            assert_eq!(session.statistics, Statistics::default());
+            assert_eq!(session.recording, None);
+        }
+
+        #[test]
+        fn duplicate_address() {
+            let temp_file = TempFile::new();
+            let mut session = session();
+            session.nodes.extend(nodes().into_iter().take(1));
+            session.save(temp_file.path()).unwrap();
+            let error_message = Session::load(temp_file.path()).err().unwrap().root_cause().to_string();
+            assert!(error_message.starts_with("Duplicate node address 0 in "));
+        }
+    }
 }
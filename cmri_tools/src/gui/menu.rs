@@ -48,4 +48,19 @@ pub mod file {
             }
         }
     }
+
+    /// Button to save a session (nodes, statistics etc.) to a file.
+    ///
+    /// Opens the file picker in path, and updates it once a file is picked.
+    /// Makes use of modal for displaying any errors which occured.
+    pub fn save_session(ui: &mut egui::Ui, modal: &egui_modal::Modal, file_path: &mut Option<std::path::PathBuf>, get: impl Fn() -> crate::file::Session) {
+        if ui.button("Save Session").clicked() {
+            if let Some(file) = file_prompt("Save Session", file_path.as_ref()).pick_file() {
+                match get().save(&file).context("Failed to save session.") {
+                    Err(error) => modal_error(modal, &error),
+                    Ok(()) => { file_path.replace(file); }
+                }
+            }
+        }
+    }
 }
@@ -4,6 +4,7 @@ use std::{collections::HashMap, ops::{BitAnd, BitOrAssign}};
 
 pub mod connection;
 pub mod menu;
+pub mod node_grid;
 
 /// A reference which can be mutable or not.
 /// Used to control whether the item is editable when displayed.
@@ -0,0 +1,70 @@
+//! Shared behaviour for GUIs which display a grid of nodes.
+
+/// Lay out the present nodes in a grid, `per_row` nodes wide.
+///
+/// Returns the rows of the grid, each containing the addresses (in ascending order) to render.
+/// A `per_row` of 0 is treated as 1.
+///
+/// ```
+/// let mut present = [false; 128];
+/// present[1] = true;
+/// present[5] = true;
+/// present[9] = true;
+/// assert_eq!(cmri_tools::gui::node_grid::layout(&present, 2), [vec![1, 5], vec![9]]);
+/// ```
+#[must_use]
+pub fn layout(present: &[bool; 128], per_row: usize) -> Vec<Vec<usize>> {
+    let addresses = present.iter()
+        .enumerate()
+        .filter_map(|(address, present)| present.then_some(address))
+        .collect::<Vec<_>>();
+    addresses.chunks(per_row.max(1))
+        .map(<[usize]>::to_vec)
+        .collect()
+}
+
+
+#[allow(clippy::missing_panics_doc, reason = "tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert!(layout(&[false; 128], 4).is_empty());
+    }
+
+    #[test]
+    fn sparse() {
+        let mut present = [false; 128];
+        for address in [0, 3, 64, 100, 127] {
+            present[address] = true;
+        }
+        assert_eq!(layout(&present, 2), [vec![0, 3], vec![64, 100], vec![127]]);
+        assert_eq!(layout(&present, 5), [vec![0, 3, 64, 100, 127]]);
+        assert_eq!(layout(&present, 10), [vec![0, 3, 64, 100, 127]]);
+    }
+
+    #[test]
+    fn full() {
+        let grid = layout(&[true; 128], 16);
+        assert_eq!(grid.len(), 8);
+        assert!(grid.iter().all(|row| row.len() == 16));
+        assert!(grid.into_iter().flatten().eq(0..128));
+    }
+
+    #[test]
+    fn full_uneven() {
+        let grid = layout(&[true; 128], 5);
+        assert_eq!(grid.len(), 26);
+        assert_eq!(grid[25], [125, 126, 127]);
+    }
+
+    #[test]
+    fn zero_per_row() {
+        let mut present = [false; 128];
+        present[7] = true;
+        present[8] = true;
+        assert_eq!(layout(&present, 0), [vec![7], vec![8]]);
+    }
+}
@@ -3,6 +3,7 @@
 pub mod connection;
 pub mod file;
 pub mod readings;
+pub mod recording;
 
 pub mod gui;
 
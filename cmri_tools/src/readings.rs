@@ -1,4 +1,7 @@
-//! A ring buffer allowing any number of items to be added, but only the last N retrieved.
+//! A ring buffer allowing any number of items to be added, but only the last N retrieved,
+//! and a per second `Counter` built on a bounded history.
+
+use std::collections::VecDeque;
 
 /// A collection of upto N readings of type T.
 ///
@@ -106,6 +109,121 @@ impl<T, const N: usize> From<Readings<T, N>> for Vec<T> where T: Clone {
     }
 }
 
+/// A running count of events, along with how many happened in each of the last few seconds.
+///
+/// Events are counted into the current second with `increment`, then `tick` (called once a second)
+/// moves that count into the history, dropping the oldest once there are more than `window` of them.
+///
+/// # Example:
+/// ```
+/// let mut counter = cmri_tools::readings::Counter::new(2);
+/// counter.increment(3);
+/// assert_eq!(counter.current(), 3);
+/// counter.tick();
+/// counter.increment(5);
+/// counter.tick();
+/// counter.increment(7);
+/// counter.tick();
+/// assert_eq!(counter.total(), 15);
+/// assert_eq!(counter.current(), 0);
+/// assert_eq!(counter.rate_last_second(), 7);
+/// assert_eq!(counter.history(), &[5, 7]); // Only the last two seconds are kept
+/// assert!((counter.average_rate(2) - 6.0).abs() < f64::EPSILON);
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Counter {
+    current: u32,
+    total: u64,
+    history: VecDeque<u32>,
+    window: usize
+}
+
+impl Counter {
+    /// Create a new counter, keeping upto `window` seconds of history.
+    #[must_use]
+    pub fn new(window: usize) -> Self {
+        Self { current: 0, total: 0, history: VecDeque::with_capacity(window), window }
+    }
+
+    /// Count `n` more events.
+    pub fn increment(&mut self, n: u32) {
+        self.current = self.current.saturating_add(n);
+        self.total = self.total.saturating_add(u64::from(n));
+    }
+
+    /// Move the current second's count into the history.
+    pub fn tick(&mut self) {
+        self.history.push_back(std::mem::take(&mut self.current));
+        while self.history.len() > self.window {
+            self.history.pop_front();
+        }
+    }
+
+    /// Get the total number of events counted.
+    #[must_use]
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Get the number of events counted since the last `tick`.
+    #[must_use]
+    pub const fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// Get the number of events counted in the last whole second (zero if there isn't one yet).
+    #[must_use]
+    pub fn rate_last_second(&self) -> u32 {
+        self.history.back().copied().unwrap_or_default()
+    }
+
+    /// Get the average number of events per second over the last `over` whole seconds,
+    /// (or as many as are in the history if that's fewer, zero if there are none).
+    #[must_use]
+    pub fn average_rate(&self, over: usize) -> f64 {
+        let over = over.min(self.history.len());
+        if over == 0 { return 0.0 }
+        let sum = self.history.iter().rev().take(over).copied().map(u64::from).sum::<u64>();
+        #[expect(clippy::cast_precision_loss, reason = "Sums and windows are nowhere near 2^52")]
+        let average = sum as f64 / over as f64;
+        average
+    }
+
+    /// Get the count for each whole second in the history, from oldest to newest.
+    #[must_use]
+    pub const fn history(&self) -> &VecDeque<u32> {
+        &self.history
+    }
+
+    /// Get the maximum number of seconds kept in the history.
+    #[must_use]
+    pub const fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Merge another counter into this one (E.G. when combining several recordings).
+    ///
+    /// Current seconds and totals are summed, histories are aligned on their newest reading
+    /// and summed where they overlap, then trimmed to this counter's window.
+    pub fn merge(&mut self, other: &Self) {
+        self.current = self.current.saturating_add(other.current);
+        self.total = self.total.saturating_add(other.total);
+
+        // Align on the newest reading, so pad the front of the shorter history
+        while self.history.len() < other.history.len() {
+            self.history.push_front(0);
+        }
+        let offset = self.history.len() - other.history.len();
+        for (mine, theirs) in self.history.iter_mut().skip(offset).zip(other.history.iter()) {
+            *mine = mine.saturating_add(*theirs);
+        }
+        while self.history.len() > self.window {
+            self.history.pop_front();
+        }
+    }
+}
+
+
 #[allow(clippy::missing_panics_doc, reason="tests")]
 #[cfg(test)]
 mod tests {
@@ -255,4 +373,160 @@ mod tests {
             Readings { values: [0; LEN], full: false, next: 0 }
         );
     }
+
+    mod counter {
+        use super::super::Counter;
+
+        #[test]
+        fn new() {
+            let counter = Counter::new(3);
+            assert_eq!(counter.total(), 0);
+            assert_eq!(counter.current(), 0);
+            assert_eq!(counter.rate_last_second(), 0);
+            assert!(counter.history().is_empty());
+            assert_eq!(counter.window(), 3);
+        }
+
+        #[test]
+        fn increment() {
+            let mut counter = Counter::new(3);
+            counter.increment(2);
+            counter.increment(3);
+            assert_eq!(counter.current(), 5);
+            assert_eq!(counter.total(), 5);
+            assert!(counter.history().is_empty());
+        }
+
+        #[test]
+        fn increment_saturates() {
+            let mut counter = Counter::new(3);
+            counter.increment(u32::MAX);
+            counter.increment(1);
+            assert_eq!(counter.current(), u32::MAX);
+            assert_eq!(counter.total(), u64::from(u32::MAX) + 1);
+        }
+
+        #[test]
+        fn tick() {
+            let mut counter = Counter::new(3);
+            for n in 1..=5 {
+                counter.increment(n);
+                counter.tick();
+                assert_eq!(counter.current(), 0);
+                assert_eq!(counter.rate_last_second(), n);
+                assert!(counter.history().len() <= 3, "History should be capped at the window");
+            }
+            assert_eq!(counter.history(), &[3, 4, 5]);
+            assert_eq!(counter.total(), 15);
+        }
+
+        #[test]
+        fn tick_with_zero_window() {
+            let mut counter = Counter::new(0);
+            counter.increment(1);
+            counter.tick();
+            assert!(counter.history().is_empty());
+            assert_eq!(counter.rate_last_second(), 0);
+            assert_eq!(counter.total(), 1);
+        }
+
+        mod average_rate {
+            use super::*;
+
+            #[test]
+            fn when_empty() {
+                let counter = Counter::new(3);
+                assert!(counter.average_rate(3).abs() < f64::EPSILON);
+            }
+
+            #[test]
+            fn over_less_than_history() {
+                let mut counter = Counter::new(5);
+                for n in [1, 2, 4, 8] {
+                    counter.increment(n);
+                    counter.tick();
+                }
+                assert!((counter.average_rate(1) - 8.0).abs() < f64::EPSILON);
+                assert!((counter.average_rate(2) - 6.0).abs() < f64::EPSILON);
+                assert!((counter.average_rate(3) - (14.0 / 3.0)).abs() < f64::EPSILON);
+            }
+
+            #[test]
+            fn over_more_than_history() {
+                let mut counter = Counter::new(5);
+                for n in [2, 4] {
+                    counter.increment(n);
+                    counter.tick();
+                }
+                assert!((counter.average_rate(10) - 3.0).abs() < f64::EPSILON);
+            }
+
+            #[test]
+            fn ignores_current_second() {
+                let mut counter = Counter::new(5);
+                counter.increment(2);
+                counter.tick();
+                counter.increment(100);
+                assert!((counter.average_rate(5) - 2.0).abs() < f64::EPSILON);
+            }
+
+            #[test]
+            fn only_within_window() {
+                let mut counter = Counter::new(2);
+                for n in [100, 2, 4] {
+                    counter.increment(n);
+                    counter.tick();
+                }
+                assert!((counter.average_rate(3) - 3.0).abs() < f64::EPSILON);
+            }
+        }
+
+        mod merge {
+            use super::*;
+
+            fn counter(window: usize, history: &[u32], current: u32) -> Counter {
+                let mut counter = Counter::new(window);
+                for n in history {
+                    counter.increment(*n);
+                    counter.tick();
+                }
+                counter.increment(current);
+                counter
+            }
+
+            #[test]
+            fn sums_current_and_total() {
+                let mut counter = counter(5, &[1], 2);
+                counter.merge(&self::counter(5, &[10], 20));
+                assert_eq!(counter.current(), 22);
+                assert_eq!(counter.total(), 33);
+            }
+
+            #[test]
+            fn aligns_histories() {
+                let mut counter = counter(5, &[1, 2, 3], 0);
+                counter.merge(&self::counter(5, &[10, 20], 0));
+                assert_eq!(counter.history(), &[1, 12, 23]);
+
+                let mut counter = self::counter(5, &[10, 20], 0);
+                counter.merge(&self::counter(5, &[1, 2, 3], 0));
+                assert_eq!(counter.history(), &[1, 12, 23]);
+            }
+
+            #[test]
+            fn trims_to_window() {
+                let mut counter = counter(2, &[1, 2], 0);
+                counter.merge(&self::counter(5, &[10, 20, 30], 0));
+                assert_eq!(counter.history(), &[21, 32]);
+            }
+
+            #[test]
+            fn with_empty() {
+                let mut counter = counter(5, &[1, 2], 3);
+                let expected = counter.clone();
+                counter.merge(&Counter::new(5));
+                assert_eq!(counter, expected);
+            }
+        }
+    }
 }
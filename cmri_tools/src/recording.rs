@@ -0,0 +1,364 @@
+//! Record and play back CMRInet traffic.
+
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use cmri::frame::Raw as RawFrame;
+
+/// The start of every recording, the last byte is the version of the format.
+///
+/// After which each record is:
+/// * The time since the recording started, in microseconds (`u64`, big endian).
+/// * The length of the frame (`u16`, big endian).
+/// * The frame's bytes.
+pub const MAGIC: [u8; 8] = *b"CMRIREC\x01";
+
+/// Write a record of a frame (which needn't be valid) received offset after the recording started.
+///
+/// # Errors
+///
+/// If writer can't be written to.
+pub fn write_record(writer: &mut impl Write, offset: Duration, frame: &RawFrame) -> std::io::Result<()> {
+    let offset = u64::try_from(offset.as_micros()).unwrap_or(u64::MAX);
+    #[expect(clippy::cast_possible_truncation, reason = "A frame is at most a few hundred bytes")]
+    let len = frame.len() as u16;
+    writer.write_all(&offset.to_be_bytes())?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(frame)
+}
+
+/// Read a recording (starting with `MAGIC`), each frame is paired with the time since the start of the recording.
+///
+/// # Errors
+///
+/// * If reader can't be read.
+/// * `std::io::ErrorKind::InvalidData` if it isn't a recording, or a record is invalid.
+/// * `std::io::ErrorKind::UnexpectedEof` if it ends part way through a record.
+pub fn read(mut reader: impl Read) -> std::io::Result<Vec<(Duration, RawFrame)>> {
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Not a recording"));
+    }
+
+    let mut frames = Vec::new();
+    let mut offset = [0; 8];
+    loop {
+        // Running out of bytes before a record starts is the end of the recording
+        match reader.read(&mut offset[..1]) {
+            Ok(0) => break,
+            Ok(_) => reader.read_exact(&mut offset[1..])?,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error)
+        }
+        let mut len = [0; 2];
+        reader.read_exact(&mut len)?;
+        let mut bytes = vec![0; usize::from(u16::from_be_bytes(len))];
+        reader.read_exact(&mut bytes)?;
+        let frame = RawFrame::try_from(bytes.as_slice()).map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        frames.push((Duration::from_micros(u64::from_be_bytes(offset)), frame));
+    }
+    Ok(frames)
+}
+
+/// Plays back recorded frames at their original timing (or faster/slower),
+/// the timing is against the `tokio::time` clock so it can be paused in tests.
+///
+/// All methods take `&self`, so a `Player` can be shared (E.G. in an `Arc`)
+/// allowing it to be paused/resumed whilst waiting for the next frame.
+///
+/// # Example
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use std::time::Duration;
+/// use cmri::{Address, packet::Packet};
+/// use cmri_tools::recording::Player;
+///
+/// let frame = Packet::new_poll_request(Address::try_from_node_address(1).unwrap()).encode_frame();
+/// let player = Player::new([(Duration::ZERO, frame), (Duration::from_millis(10), frame)]);
+/// player.set_speed(10.0);
+/// while let Some(frame) = player.next_frame().await {
+///     // Now write the frame to a connection.
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct Player {
+    frames: Mutex<VecDeque<(Duration, RawFrame)>>,
+    clock: Mutex<Clock>,
+    changed: Notify
+}
+
+/// Tracks how far through the recording playback has reached.
+#[derive(Debug, Clone, Copy)]
+struct Clock {
+    speed: f64,
+    paused: bool,
+    /// How far through the recording playback had reached at anchor.
+    position: Duration,
+    anchor: Instant
+}
+
+impl Player {
+    /// Create a new `Player`, each frame is paired with the time since the start of the recording
+    /// (frames should be in chronological order), playback starts immediately at normal speed.
+    pub fn new(frames: impl IntoIterator<Item = (Duration, RawFrame)>) -> Self {
+        Self {
+            frames: Mutex::new(frames.into_iter().collect()),
+            clock: Mutex::new(Clock { speed: 1.0, paused: false, position: Duration::ZERO, anchor: Instant::now() }),
+            changed: Notify::new()
+        }
+    }
+
+    /// The current playback speed (1.0 is the recorded speed).
+    #[expect(clippy::missing_panics_doc, reason = "Only panics if the lock is poisoned.")]
+    #[must_use]
+    pub fn speed(&self) -> f64 {
+        self.clock.lock().expect("Clock lock to not be poisoned").speed
+    }
+
+    /// Change the playback speed, E.G. 2.0 plays back at twice the recorded speed.
+    ///
+    /// # Panics
+    ///
+    /// If speed isn't a finite number greater than 0.
+    pub fn set_speed(&self, speed: f64) {
+        assert!(speed.is_finite() && speed > 0.0, "Speed must be finite and greater than 0, got {speed}");
+        self.update_clock(|clock| {
+            clock.rebase();
+            clock.speed = speed;
+        });
+    }
+
+    /// Whether playback is paused.
+    #[expect(clippy::missing_panics_doc, reason = "Only panics if the lock is poisoned.")]
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.clock.lock().expect("Clock lock to not be poisoned").paused
+    }
+
+    /// Pause playback, `next_frame` won't return until playback is resumed.
+    pub fn pause(&self) {
+        self.update_clock(|clock| {
+            clock.rebase();
+            clock.paused = true;
+        });
+    }
+
+    /// Resume playback from where it was paused.
+    pub fn resume(&self) {
+        self.update_clock(|clock| {
+            clock.rebase();
+            clock.paused = false;
+        });
+    }
+
+    /// The number of frames still to be played.
+    #[expect(clippy::missing_panics_doc, reason = "Only panics if the lock is poisoned.")]
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.frames.lock().expect("Frames lock to not be poisoned").len()
+    }
+
+    /// Wait until it's time for the next frame, then get it.
+    /// Returns `None` once all the frames have been played.
+    ///
+    /// This is cancel safe (E.G. for use in `tokio::select!`), the frame is only taken once it's due.
+    #[expect(clippy::missing_panics_doc, reason = "Only panics if a lock is poisoned.")]
+    pub async fn next_frame(&self) -> Option<RawFrame> {
+        loop {
+            // Created before checking the clock, so a change made in the meantime isn't missed.
+            let changed = self.changed.notified();
+            let at = self.frames.lock().expect("Frames lock to not be poisoned").front()?.0;
+            let due = self.clock.lock().expect("Clock lock to not be poisoned").due(at);
+            match due {
+                None => changed.await,
+                Some(due) => tokio::select! {
+                    () = tokio::time::sleep_until(due) => {
+                        return self.frames.lock().expect("Frames lock to not be poisoned").pop_front().map(|(_, frame)| frame);
+                    },
+                    () = changed => ()
+                }
+            }
+        }
+    }
+
+    #[expect(clippy::missing_panics_doc, reason = "Only panics if the lock is poisoned.")]
+    fn update_clock(&self, update: impl FnOnce(&mut Clock)) {
+        update(&mut self.clock.lock().expect("Clock lock to not be poisoned"));
+        self.changed.notify_waiters();
+    }
+}
+
+impl Clock {
+    /// How far through the recording playback has reached.
+    fn position(&self) -> Duration {
+        if self.paused {
+            self.position
+        } else {
+            self.position + self.anchor.elapsed().mul_f64(self.speed)
+        }
+    }
+
+    /// Move the anchor to now, ready for changing the speed or pausing/resuming.
+    fn rebase(&mut self) {
+        self.position = self.position();
+        self.anchor = Instant::now();
+    }
+
+    /// When a frame recorded at `at` should be played, `None` if paused.
+    fn due(&self, at: Duration) -> Option<Instant> {
+        (!self.paused).then(|| self.anchor + at.saturating_sub(self.position).div_f64(self.speed))
+    }
+}
+
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use cmri::{Address, packet::Packet};
+    use super::*;
+
+    fn frames() -> Vec<(Duration, RawFrame)> {
+        [0, 1_000, 3_000].into_iter()
+            .enumerate()
+            .map(|(index, millis)| (
+                Duration::from_millis(millis),
+                Packet::new_poll_request(Address::try_from_node_address(u8::try_from(index).unwrap()).unwrap()).encode_frame()
+            ))
+            .collect()
+    }
+
+    async fn waits(player: &Player) -> Vec<Duration> {
+        let mut waits = Vec::new();
+        let mut last = Instant::now();
+        while player.next_frame().await.is_some() {
+            waits.push(last.elapsed());
+            last = Instant::now();
+        }
+        waits
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recorded_speed() {
+        let player = Player::new(frames());
+        assert!((player.speed() - 1.0).abs() < f64::EPSILON);
+        assert_eq!(
+            waits(&player).await,
+            vec![Duration::ZERO, Duration::from_secs(1), Duration::from_secs(2)]
+        );
+        assert_eq!(player.remaining(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn double_speed() {
+        let player = Player::new(frames());
+        player.set_speed(2.0);
+        assert_eq!(
+            waits(&player).await,
+            vec![Duration::ZERO, Duration::from_millis(500), Duration::from_secs(1)]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn change_speed_part_way() {
+        let player = Player::new(frames());
+        assert!(player.next_frame().await.is_some());
+        tokio::time::advance(Duration::from_millis(500)).await; // Half way to the next frame
+        player.set_speed(0.5);
+
+        let start = Instant::now();
+        assert!(player.next_frame().await.is_some());
+        assert_eq!(start.elapsed(), Duration::from_secs(1)); // Remaining 500ms at half speed
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pause_and_resume() {
+        let player = Arc::new(Player::new(frames()));
+        assert!(player.next_frame().await.is_some());
+        player.pause();
+        assert!(player.is_paused());
+
+        let next = {
+            let player = player.clone();
+            tokio::spawn(async move { player.next_frame().await })
+        };
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert!(!next.is_finished());
+
+        player.resume();
+        assert!(!player.is_paused());
+        let start = Instant::now();
+        assert!(next.await.unwrap().is_some());
+        assert_eq!(start.elapsed(), Duration::from_secs(1)); // Paused before any time had elapsed
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancel_safe() {
+        let player = Player::new(frames());
+        assert!(player.next_frame().await.is_some());
+
+        // Give up waiting before the next frame is due
+        assert!(tokio::time::timeout(Duration::from_millis(500), player.next_frame()).await.is_err());
+        assert_eq!(player.remaining(), 2);
+
+        let start = Instant::now();
+        assert_eq!(player.next_frame().await, Some(frames()[1].1));
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    #[test]
+    #[should_panic(expected = "Speed must be finite and greater than 0")]
+    fn invalid_speed() {
+        Player::new(frames()).set_speed(0.0);
+    }
+
+    mod file_format {
+        use super::*;
+
+        fn recording(frames: &[(Duration, RawFrame)]) -> Vec<u8> {
+            let mut bytes = MAGIC.to_vec();
+            for (offset, frame) in frames {
+                write_record(&mut bytes, *offset, frame).unwrap();
+            }
+            bytes
+        }
+
+        #[test]
+        fn round_trip() {
+            let bad = RawFrame::try_from(&[0xFF, 0xFF, 0x02, 0x03]).unwrap();
+            let mut frames = frames();
+            frames.push((Duration::from_micros(3_000_001), bad));
+            assert_eq!(read(recording(&frames).as_slice()).unwrap(), frames);
+        }
+
+        #[test]
+        fn record() {
+            let mut bytes = Vec::new();
+            write_record(&mut bytes, Duration::from_micros(258), &RawFrame::try_from(&[0xFF, 0xFF, 0x02, 65, b'P', 0x03]).unwrap()).unwrap();
+            assert_eq!(bytes, [0, 0, 0, 0, 0, 0, 1, 2, 0, 6, 0xFF, 0xFF, 0x02, 65, b'P', 0x03]);
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(read(MAGIC.as_slice()).unwrap(), Vec::new());
+        }
+
+        #[test]
+        fn not_a_recording() {
+            assert!(read(b"Something else".as_slice()).is_err_and(|error| error.kind() == std::io::ErrorKind::InvalidData));
+        }
+
+        #[test]
+        fn truncated() {
+            let mut bytes = recording(&frames());
+            bytes.pop();
+            assert!(read(bytes.as_slice()).is_err_and(|error| error.kind() == std::io::ErrorKind::UnexpectedEof));
+        }
+    }
+}
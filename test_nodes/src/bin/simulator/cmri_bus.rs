@@ -3,27 +3,40 @@ use embassy_rp::uart::{Uart, Config, Instance, Blocking, TxPin, RxPin};
 use embassy_rp::Peripheral;
 use defmt::{error, warn, info, debug, trace, panic, Debug2Format};
 
+pub use crate::gap::RECOMMENDED_GAP_BYTES;
+
+/// Calculate how long to leave the bus idle between frames,
+/// given the baud rate and the length of the gap in bytes (see `RECOMMENDED_GAP_BYTES`).
+/// Rounded up to the next whole microsecond.
+pub const fn inter_frame_gap(baud: u32, preamble_bytes: u32) -> embassy_time::Duration {
+    embassy_time::Duration::from_micros(crate::gap::inter_frame_gap_micros(baud, preamble_bytes))
+}
+
 /// A CMRInet bus running over RS232/RS485.
 pub trait Bus {
     fn transmit(&mut self, packet: &cmri::packet::Packet) -> Result<(), embassy_rp::uart::Error>;
 }
 
+/// Create a new bus, leaving it idle for `gap` after each frame (see `inter_frame_gap`).
 pub fn new<'d, U: Instance, P: Pin>(
     uart: impl Peripheral<P = U> + 'd,
     tx: impl Peripheral<P = impl TxPin<U>> + 'd,
     rx: impl Peripheral<P = impl RxPin<U>> + 'd,
     config: Config,
-    rs485: P
+    rs485: P,
+    gap: embassy_time::Duration
 ) -> BusImpl<'d, U, P> {
     BusImpl {
         uart: Uart::new_blocking(uart, tx, rx, config),
-        rs385_pin: Output::new(rs485, Level::Low)
+        rs385_pin: Output::new(rs485, Level::Low),
+        gap
     }
 }
 
 pub struct BusImpl<'d, U: Instance, P: Pin> {
     uart: Uart<'d, U, Blocking>,
-    rs385_pin: Output<'d, P>
+    rs385_pin: Output<'d, P>,
+    gap: embassy_time::Duration
 }
 impl<'d, U, P> Bus for BusImpl<'d, U, P> where U: Instance, P: Pin {
     fn transmit(&mut self, packet: &cmri::packet::Packet) -> Result<(), embassy_rp::uart::Error> {
@@ -34,6 +47,9 @@ impl<'d, U, P> Bus for BusImpl<'d, U, P> where U: Instance, P: Pin {
         let result = self.uart.blocking_write(frame.as_slice());
         while self.uart.busy() {}
         self.rs385_pin.set_low();
+        if self.gap.as_ticks() > 0 {
+            embassy_time::block_for(self.gap);
+        }
         result
     }
 }
@@ -0,0 +1,46 @@
+//! Timing of the gap between frames on a CMRInet bus.
+//!
+//! This has no dependencies so it can be tested on the host (see `test.sh`).
+
+/// Bits used to send each byte (1 start, 8 data, 1 stop).
+const BITS_PER_BYTE: u64 = 10;
+
+/// The recommended inter-frame gap, in bytes, is the length of the frame's preamble (SYN SYN),
+/// this gives receivers which lost sync mid frame time to notice the silence before the next frame starts.
+pub const RECOMMENDED_GAP_BYTES: u32 = 2;
+
+/// Calculate how many microseconds to leave the bus idle between frames,
+/// given the baud rate and the length of the gap in bytes (see `RECOMMENDED_GAP_BYTES`).
+/// Rounded up to the next whole microsecond.
+pub const fn inter_frame_gap_micros(baud: u32, preamble_bytes: u32) -> u64 {
+    let bits = preamble_bytes as u64 * BITS_PER_BYTE;
+    (bits * 1_000_000).div_ceil(baud as u64)
+}
+
+
+#[allow(clippy::missing_panics_doc, reason="tests")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod inter_frame_gap_micros {
+        use super::*;
+
+        #[test]
+        fn recommended() {
+            assert_eq!(inter_frame_gap_micros(115_200, RECOMMENDED_GAP_BYTES), 174); // 173.6
+            assert_eq!(inter_frame_gap_micros(9_600, RECOMMENDED_GAP_BYTES), 2_084); // 2083.3
+        }
+
+        #[test]
+        fn exact() {
+            assert_eq!(inter_frame_gap_micros(1_000_000, 1), 10);
+            assert_eq!(inter_frame_gap_micros(10_000, 3), 3_000);
+        }
+
+        #[test]
+        fn no_gap() {
+            assert_eq!(inter_frame_gap_micros(115_200, 0), 0);
+        }
+    }
+}
@@ -15,6 +15,7 @@ use {defmt_rtt as _, panic_probe as _};
 use rand::{rngs::SmallRng, SeedableRng};
 
 mod cmri_bus;
+mod gap;
 mod node;
 use node::Node;
 
@@ -23,7 +24,10 @@ async fn main(_spawner: Spawner) {
     info!("CMRInet Simulator.");
     unsafe { embassy_rp::time_driver::init(); }
     let p = embassy_rp::init(Default::default());
-    let mut bus = cmri_bus::new(p.UART0, p.PIN_0, p.PIN_1, Default::default(), p.PIN_2);
+    let config = embassy_rp::uart::Config::default();
+    let gap = cmri_bus::inter_frame_gap(config.baudrate, cmri_bus::RECOMMENDED_GAP_BYTES);
+    debug!("Inter-frame gap {}µs.", gap.as_micros());
+    let mut bus = cmri_bus::new(p.UART0, p.PIN_0, p.PIN_1, config, p.PIN_2, gap);
     let mut adc = embassy_rp::adc::Adc::new_blocking(p.ADC, Config::default());
     let mut p28 = Channel::new_pin(p.PIN_28, embassy_rp::gpio::Pull::None);
     let mut random = create_prng(&mut adc, &mut p28);
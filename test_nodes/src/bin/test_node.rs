@@ -64,6 +64,7 @@ async fn main(_spawner: Spawner) {
                 }
             },
             cmri::packet::Payload::ReceiveData { .. } => defmt::error!("Node with conflicting address exists!"),
+            cmri::packet::Payload::Set { .. } => defmt::debug!("Ignoring set packet"),
             cmri::packet::Payload::TransmitData { data } => {
                 defmt::debug!("Received data packet: {}", data.as_slice());
                 state = data[0] & 0b1111_0000;
@@ -0,0 +1,11 @@
+#!/usr/bin/bash
+
+set -e
+set -v
+
+
+# The nodes themselves only run on the microcontroller,
+# so the parts with no dependencies are tested on the host.
+mkdir -p target/host_tests
+rustc --edition 2021 --test src/bin/simulator/gap.rs -o target/host_tests/gap
+target/host_tests/gap